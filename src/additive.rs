@@ -0,0 +1,322 @@
+//! Additive synthesis with a bank of recursive sine oscillators.
+
+use super::audionode::*;
+use super::math::*;
+use super::shared::*;
+use super::signal::*;
+use super::*;
+use numeric_array::typenum::*;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+
+/// Number of samples between partial parameter updates.
+const BLOCK: usize = 64;
+
+/// Parameters of a single partial in an additive oscillator bank.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Partial {
+    /// Frequency ratio to the fundamental.
+    pub ratio: f32,
+    /// Amplitude.
+    pub amplitude: f32,
+    /// Phase in 0...1.
+    pub phase: f32,
+}
+
+impl Partial {
+    /// Create new partial with frequency `ratio` to the fundamental, `amplitude` and `phase` in 0...1.
+    pub fn new(ratio: f32, amplitude: f32, phase: f32) -> Self {
+        Self {
+            ratio,
+            amplitude,
+            phase,
+        }
+    }
+}
+
+/// Source of partial parameters for an additive oscillator bank.
+pub trait PartialSource: Clone + Send + Sync {
+    /// Number of partials.
+    fn partials(&self) -> usize;
+    /// Write current partial parameters to `partials`. Called once per block.
+    /// `time` is stream time in seconds.
+    fn update(&mut self, time: f64, partials: &mut [Partial]);
+}
+
+/// Atomic partial table that can be modified on the fly from another thread.
+pub struct AtomicPartials {
+    ratio: Vec<AtomicU32>,
+    amplitude: Vec<AtomicU32>,
+    phase: Vec<AtomicU32>,
+}
+
+impl AtomicPartials {
+    /// Create new partial table from a slice.
+    pub fn new(partials: &[Partial]) -> Self {
+        Self {
+            ratio: partials.iter().map(|x| f32::storage(x.ratio)).collect(),
+            amplitude: partials.iter().map(|x| f32::storage(x.amplitude)).collect(),
+            phase: partials.iter().map(|x| f32::storage(x.phase)).collect(),
+        }
+    }
+
+    /// Create new table of `n` harmonic partials with ratios 1, 2, ..., `n`.
+    /// Amplitudes and phases are zero.
+    pub fn harmonic(n: usize) -> Self {
+        let partials: Vec<Partial> = (1..=n).map(|i| Partial::new(i as f32, 0.0, 0.0)).collect();
+        Self::new(&partials)
+    }
+
+    /// Number of partials.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.ratio.len()
+    }
+
+    /// Get partial `i`.
+    #[inline]
+    pub fn at(&self, i: usize) -> Partial {
+        Partial::new(
+            f32::get_stored(&self.ratio[i]),
+            f32::get_stored(&self.amplitude[i]),
+            f32::get_stored(&self.phase[i]),
+        )
+    }
+
+    /// Set partial `i`.
+    #[inline]
+    pub fn set(&self, i: usize, partial: Partial) {
+        f32::store(&self.ratio[i], partial.ratio);
+        f32::store(&self.amplitude[i], partial.amplitude);
+        f32::store(&self.phase[i], partial.phase);
+    }
+
+    /// Set frequency ratio of partial `i`.
+    #[inline]
+    pub fn set_ratio(&self, i: usize, ratio: f32) {
+        f32::store(&self.ratio[i], ratio);
+    }
+
+    /// Set amplitude of partial `i`.
+    #[inline]
+    pub fn set_amplitude(&self, i: usize, amplitude: f32) {
+        f32::store(&self.amplitude[i], amplitude);
+    }
+
+    /// Set phase in 0...1 of partial `i`.
+    #[inline]
+    pub fn set_phase(&self, i: usize, phase: f32) {
+        f32::store(&self.phase[i], phase);
+    }
+}
+
+impl PartialSource for Arc<AtomicPartials> {
+    fn partials(&self) -> usize {
+        self.len()
+    }
+
+    fn update(&mut self, _time: f64, partials: &mut [Partial]) {
+        for (i, partial) in partials.iter_mut().enumerate() {
+            *partial = self.at(i);
+        }
+    }
+}
+
+/// Partial source from closure `f(t, partials)`, which writes partial parameters
+/// at time `t` seconds into `partials`.
+#[derive(Clone)]
+pub struct PartialFn<F: Fn(f64, &mut [Partial]) + Clone + Send + Sync> {
+    partials: usize,
+    f: F,
+}
+
+impl<F: Fn(f64, &mut [Partial]) + Clone + Send + Sync> PartialFn<F> {
+    /// Create new partial source with `partials` partials from closure `f`.
+    pub fn new(partials: usize, f: F) -> Self {
+        Self { partials, f }
+    }
+}
+
+impl<F: Fn(f64, &mut [Partial]) + Clone + Send + Sync> PartialSource for PartialFn<F> {
+    fn partials(&self) -> usize {
+        self.partials
+    }
+
+    fn update(&mut self, time: f64, partials: &mut [Partial]) {
+        (self.f)(time, partials);
+    }
+}
+
+/// Runtime state of a partial oscillator.
+#[derive(Clone, Default)]
+struct PartialState<T: Real> {
+    /// Oscillator state as a unit complex number.
+    re: T,
+    im: T,
+    /// Rotation per sample.
+    cos: T,
+    sin: T,
+    /// Current amplitude.
+    gain: T,
+    /// Amplitude at the end of the block.
+    target: T,
+    /// Amplitude increment per sample.
+    step: T,
+    /// Phase offset in 0...1 that has been applied to the oscillator.
+    phase: f32,
+}
+
+/// Additive oscillator bank. Partials are recursive sine oscillators
+/// with parameters updated from a `PartialSource` every 64 samples.
+/// Partial frequency is `f * ratio ^ stretch * sqrt(1 + inharmonicity * ratio ^ 2)`,
+/// where `f` is the fundamental frequency. Partials at or above the Nyquist frequency are culled.
+/// - Input 0: fundamental frequency in Hz
+/// - Output 0: sum of partials
+#[derive(Clone)]
+pub struct Additive<T: Real, S: PartialSource> {
+    source: S,
+    partials: Vec<Partial>,
+    state: Vec<PartialState<T>>,
+    stretch: f32,
+    inharmonicity: f32,
+    /// Samples until the next parameter update.
+    counter: usize,
+    /// Whether partial parameters have been updated since reset.
+    started: bool,
+    time: f64,
+    sample_rate: f64,
+}
+
+impl<T: Real, S: PartialSource> Additive<T, S> {
+    /// Create new additive oscillator bank with partials from `source`.
+    pub fn new(sample_rate: f64, source: S) -> Self {
+        let n = source.partials();
+        let mut node = Self {
+            source,
+            partials: vec![Partial::default(); n],
+            state: vec![PartialState::default(); n],
+            stretch: 1.0,
+            inharmonicity: 0.0,
+            counter: 0,
+            started: false,
+            time: 0.0,
+            sample_rate,
+        };
+        node.reset();
+        node
+    }
+
+    /// Access partial source.
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Set stretch exponent applied to partial ratios. The default is 1 (no stretch).
+    pub fn set_stretch(&mut self, stretch: f32) {
+        self.stretch = stretch;
+    }
+
+    /// Set inharmonicity coefficient, as in stiff strings. The default is 0.
+    pub fn set_inharmonicity(&mut self, inharmonicity: f32) {
+        self.inharmonicity = inharmonicity;
+    }
+
+    /// Update partial parameters for the next block from fundamental frequency `f`.
+    fn update(&mut self, f: T) {
+        self.source.update(self.time, &mut self.partials);
+        let nyquist = 0.5 * self.sample_rate;
+        let z = T::from_f64(1.0 / BLOCK as f64);
+        for (partial, state) in self.partials.iter().zip(self.state.iter_mut()) {
+            let ratio = partial.ratio as f64;
+            let frequency = f.to_f64()
+                * pow(abs(ratio), self.stretch as f64)
+                * signum(ratio)
+                * sqrt(1.0 + self.inharmonicity as f64 * ratio * ratio);
+            let target = if abs(frequency) < nyquist {
+                T::from_f32(partial.amplitude)
+            } else {
+                T::zero()
+            };
+            // Start the first block at full amplitude.
+            state.gain = if self.started { state.target } else { target };
+            state.target = target;
+            state.step = (state.target - state.gain) * z;
+            if state.gain == T::zero() && state.target == T::zero() {
+                continue;
+            }
+            // Rotate the oscillator if the phase offset has changed,
+            // and correct any drift in magnitude.
+            let angle = T::from_f64((partial.phase - state.phase) as f64 * TAU);
+            let (c, s) = (cos(angle), sin(angle));
+            let norm = T::one() / sqrt(state.re * state.re + state.im * state.im);
+            let (re, im) = (state.re * norm, state.im * norm);
+            state.re = re * c - im * s;
+            state.im = re * s + im * c;
+            state.phase = partial.phase;
+            let delta = T::from_f64(frequency / self.sample_rate * TAU);
+            state.cos = cos(delta);
+            state.sin = sin(delta);
+        }
+        self.time += BLOCK as f64 / self.sample_rate;
+        self.started = true;
+    }
+}
+
+impl<T: Real, S: PartialSource> AudioNode for Additive<T, S> {
+    const ID: u64 = 97;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.counter = 0;
+        self.started = false;
+        self.time = 0.0;
+        for state in self.state.iter_mut() {
+            // Phase offsets are applied at the first update.
+            state.re = T::one();
+            state.im = T::zero();
+            state.phase = 0.0;
+            state.gain = T::zero();
+            state.target = T::zero();
+            state.step = T::zero();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.counter = 0;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        if self.counter == 0 {
+            self.update(input[0]);
+            self.counter = BLOCK;
+        }
+        self.counter -= 1;
+        let mut output = T::zero();
+        for state in self.state.iter_mut() {
+            if state.gain == T::zero() && state.step == T::zero() {
+                continue;
+            }
+            output += state.gain * state.im;
+            let re = state.re * state.cos - state.im * state.sin;
+            state.im = state.re * state.sin + state.im * state.cos;
+            state.re = re;
+            state.gain += state.step;
+        }
+        [output].into()
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output
+    }
+}
@@ -2,6 +2,7 @@
 
 use super::audionode::*;
 use super::math::*;
+use super::pan::pan_weights;
 use super::signal::*;
 use super::*;
 use num_complex::Complex64;
@@ -62,6 +63,71 @@ impl<N: Size<T>, T: Float> AudioNode for Tick<N, T> {
     }
 }
 
+/// Interpolating delay line with a power of two buffer.
+#[derive(Clone, Default)]
+pub(crate) struct DelayLine<T: Float> {
+    buffer: Vec<T>,
+    mask: usize,
+    i: usize,
+}
+
+impl<T: Float> DelayLine<T> {
+    /// Allocate buffer for delays up to `length` samples.
+    pub fn resize(&mut self, length: usize) {
+        let size = (length + 2).next_power_of_two();
+        self.buffer.resize(size, T::zero());
+        self.mask = size - 1;
+        self.reset();
+    }
+
+    /// Clear the delay line.
+    pub fn reset(&mut self) {
+        self.buffer.fill(T::zero());
+        self.i = 0;
+    }
+
+    /// Write the next sample.
+    #[inline]
+    pub fn write(&mut self, x: T) {
+        self.i = (self.i + 1) & self.mask;
+        self.buffer[self.i] = x;
+    }
+
+    /// Read sample written `delay` samples ago (0 is the latest sample) using linear interpolation.
+    #[inline]
+    pub fn read(&self, delay: T) -> T {
+        let delay = clamp(T::zero(), T::from_f64(self.mask as f64 - 1.0), delay);
+        let whole = delay.to_f64() as usize;
+        let fraction = delay - T::from_f64(whole as f64);
+        let x0 = self.buffer[(self.i.wrapping_sub(whole)) & self.mask];
+        let x1 = self.buffer[(self.i.wrapping_sub(whole + 1)) & self.mask];
+        x0 + (x1 - x0) * fraction
+    }
+
+    /// Sample written `delay` whole samples ago (0 is the latest sample).
+    #[inline]
+    pub fn at(&self, delay: usize) -> T {
+        self.buffer[self.i.wrapping_sub(delay) & self.mask]
+    }
+
+    /// Read sample written `delay` samples ago using cubic spline interpolation.
+    /// The delay is at least one sample.
+    #[inline]
+    pub fn read_cubic(&self, delay: T) -> T {
+        let delay = clamp(T::one(), T::from_f64(self.mask as f64 - 2.0), delay);
+        let whole = delay.to_f64() as usize;
+        let fraction = delay - T::from_f64(whole as f64);
+        let i = self.i.wrapping_sub(whole);
+        spline(
+            self.buffer[i.wrapping_add(1) & self.mask],
+            self.buffer[i & self.mask],
+            self.buffer[i.wrapping_sub(1) & self.mask],
+            self.buffer[i.wrapping_sub(2) & self.mask],
+            fraction,
+        )
+    }
+}
+
 /// Fixed delay.
 /// - Allocates: the delay line.
 /// - Input 0: input
@@ -443,3 +509,780 @@ where
         output
     }
 }
+
+/// Fractional delay interpolation methods for `VariableDelay`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation. Attenuates high frequencies at fractional delays.
+    Linear,
+    /// Cubic Hermite (Catmull-Rom) interpolation.
+    #[default]
+    Hermite,
+    /// Lagrange interpolation of order 3, 4 or 5.
+    Lagrange(usize),
+    /// First order Thiran allpass interpolation. The magnitude response is flat,
+    /// but quick delay changes cause transients.
+    Thiran,
+    /// Windowed sinc interpolation with the given number of taps on each side (2 to 16).
+    Sinc(usize),
+}
+
+impl Interpolation {
+    /// Minimum delay in samples supported by the interpolation method.
+    pub fn min_delay(&self) -> f64 {
+        match *self {
+            Interpolation::Linear => 0.0,
+            Interpolation::Hermite => 1.0,
+            Interpolation::Lagrange(order) => ((order.clamp(3, 5) - 1) / 2) as f64,
+            Interpolation::Thiran => 0.5,
+            Interpolation::Sinc(taps) => (taps.clamp(2, 16) - 1) as f64,
+        }
+    }
+
+    /// Number of samples the method reads past the whole part of the delay.
+    fn reach(&self) -> usize {
+        match *self {
+            Interpolation::Linear | Interpolation::Thiran => 1,
+            Interpolation::Hermite => 2,
+            Interpolation::Lagrange(order) => order.clamp(3, 5),
+            Interpolation::Sinc(taps) => taps.clamp(2, 16),
+        }
+    }
+}
+
+/// Variable delay line with selectable fractional delay interpolation.
+/// In modulation-safe mode, enabled with `set_smoothing`, the delay time is smoothed
+/// to avoid zipper noise when it changes rapidly.
+/// Setting: interpolation method.
+/// - Allocates: the delay line.
+/// - Input 0: input
+/// - Input 1: delay amount in seconds.
+/// - Output 0: delayed input
+#[derive(Clone)]
+pub struct VariableDelay<T: Real> {
+    line: DelayLine<T>,
+    interpolation: Interpolation,
+    min_delay: f64,
+    max_delay: f64,
+    /// Smoothing time in seconds.
+    smoothing: f64,
+    /// Per-sample smoothing coefficient.
+    coefficient: f64,
+    /// Current delay in samples, or negative if not yet initialized.
+    delay: f64,
+    /// Thiran allpass state.
+    state: T,
+    sample_rate: f64,
+}
+
+impl<T: Real> VariableDelay<T> {
+    /// Create a variable delay line. Minimum and maximum delays are specified in seconds.
+    pub fn new(min_delay: f64, max_delay: f64, interpolation: Interpolation) -> Self {
+        assert!(min_delay >= 0.0);
+        assert!(min_delay <= max_delay);
+        let mut node = Self {
+            line: DelayLine::default(),
+            interpolation,
+            min_delay,
+            max_delay,
+            smoothing: 0.0,
+            coefficient: 1.0,
+            delay: -1.0,
+            state: T::zero(),
+            sample_rate: 0.0,
+        };
+        node.set_sample_rate(DEFAULT_SR);
+        node
+    }
+
+    /// Interpolation method.
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Set interpolation method.
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+        self.allocate();
+        self.state = T::zero();
+    }
+
+    /// Set delay smoothing time in seconds. A nonzero time enables modulation-safe mode,
+    /// where the delay follows its input with a one-pole lowpass of the given time constant.
+    pub fn set_smoothing(&mut self, smoothing: f64) {
+        self.smoothing = max(0.0, smoothing);
+        self.update_coefficient();
+    }
+
+    fn update_coefficient(&mut self) {
+        self.coefficient = if self.smoothing > 0.0 {
+            1.0 - exp(-1.0 / (self.smoothing * self.sample_rate))
+        } else {
+            1.0
+        };
+    }
+
+    fn allocate(&mut self) {
+        let length =
+            (self.max_delay * self.sample_rate).ceil() as usize + self.interpolation.reach() + 2;
+        if self.line.buffer.len() < (length + 2).next_power_of_two() {
+            self.line.resize(length);
+        }
+    }
+
+    /// Read at `delay` samples.
+    #[inline]
+    fn read(&mut self, delay: f64) -> T {
+        let delay = max(delay, self.interpolation.min_delay());
+        let whole = delay as usize;
+        let fraction = delay - whole as f64;
+        match self.interpolation {
+            Interpolation::Linear => {
+                let x0 = self.line.at(whole);
+                x0 + (self.line.at(whole + 1) - x0) * T::from_f64(fraction)
+            }
+            Interpolation::Hermite => spline(
+                self.line.at(whole - 1),
+                self.line.at(whole),
+                self.line.at(whole + 1),
+                self.line.at(whole + 2),
+                T::from_f64(fraction),
+            ),
+            Interpolation::Lagrange(order) => {
+                let order = order.clamp(3, 5);
+                let base = whole - (order - 1) / 2;
+                let d = delay - base as f64;
+                let mut output = T::zero();
+                for j in 0..=order {
+                    let mut h = 1.0;
+                    for m in 0..=order {
+                        if m != j {
+                            h *= (d - m as f64) / (j as f64 - m as f64);
+                        }
+                    }
+                    output += self.line.at(base + j) * T::from_f64(h);
+                }
+                output
+            }
+            Interpolation::Thiran => {
+                // Keep the fractional part in 0.5...1.5 for a well behaved allpass.
+                let whole = (delay - 0.5) as usize;
+                let fraction = delay - whole as f64;
+                let a = T::from_f64((1.0 - fraction) / (1.0 + fraction));
+                self.state = a * (self.line.at(whole) - self.state) + self.line.at(whole + 1);
+                self.state
+            }
+            Interpolation::Sinc(taps) => {
+                let taps = taps.clamp(2, 16) as isize;
+                if fraction == 0.0 {
+                    return self.line.at(whole);
+                }
+                let sin_fraction = sin(PI * fraction);
+                let mut output = T::zero();
+                let mut total = 0.0;
+                for j in 1 - taps..=taps {
+                    // Distance from the tap to the interpolated point.
+                    let x = fraction - j as f64;
+                    let sign = if j & 1 == 0 { 1.0 } else { -1.0 };
+                    let window = 0.5 + 0.5 * cos(PI * x / taps as f64);
+                    let h = sign * sin_fraction / (PI * x) * window;
+                    total += h;
+                    output += self.line.at((whole as isize + j) as usize) * T::from_f64(h);
+                }
+                output / T::from_f64(total)
+            }
+        }
+    }
+}
+
+impl<T: Real> AudioNode for VariableDelay<T> {
+    const ID: u64 = 109;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U1;
+    type Setting = Interpolation;
+
+    fn set(&mut self, interpolation: Self::Setting) {
+        self.set_interpolation(interpolation);
+    }
+
+    fn reset(&mut self) {
+        self.line.reset();
+        self.delay = -1.0;
+        self.state = T::zero();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            self.line = DelayLine::default();
+            self.allocate();
+            self.update_coefficient();
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.line.write(input[0]);
+        let target = clamp(self.min_delay, self.max_delay, input[1].to_f64()) * self.sample_rate;
+        if self.delay < 0.0 {
+            self.delay = target;
+        } else {
+            self.delay += (target - self.delay) * self.coefficient;
+        }
+        [self.read(self.delay)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = input[0].distort(self.min_delay * self.sample_rate);
+        output
+    }
+}
+
+/// Speed of sound in meters per second.
+pub const SPEED_OF_SOUND: f64 = 343.0;
+
+/// Shoebox room for image-source early reflections.
+/// The room spans 0...`dimensions[i]` meters along each axis.
+/// The listener faces the positive Y axis with the positive X axis to the right.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Room {
+    /// Room width, depth and height in meters.
+    pub dimensions: [f64; 3],
+    /// Energy absorption coefficients in 0...1 of the walls at
+    /// minimum X, maximum X, minimum Y, maximum Y, floor and ceiling.
+    pub absorption: [f64; 6],
+    /// High frequency damping in 0...1 applied at each reflection.
+    pub damping: f64,
+}
+
+/// Early reflection from an image source.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Reflection {
+    /// Delay in seconds.
+    pub delay: f64,
+    /// Gain, including distance attenuation relative to 1 meter.
+    pub gain: f64,
+    /// Pan value in -1...1.
+    pub pan: f64,
+    /// Lowpass cutoff in Hz.
+    pub cutoff: f64,
+    /// Number of wall reflections.
+    pub order: usize,
+}
+
+impl Room {
+    /// Create new room with `dimensions` (width, depth, height) in meters
+    /// and the same `absorption` in 0...1 for all walls.
+    pub fn new(dimensions: [f64; 3], absorption: f64) -> Self {
+        Self {
+            dimensions,
+            absorption: [absorption; 6],
+            damping: 0.3,
+        }
+    }
+
+    /// Clamp `position` to the interior of the room.
+    pub fn clamp(&self, position: [f64; 3]) -> [f64; 3] {
+        std::array::from_fn(|i| clamp(0.0, self.dimensions[i], position[i]))
+    }
+
+    /// Image source coordinate and reflection gain along `axis` for image index `n`.
+    fn image(&self, axis: usize, n: i64, source: f64) -> (f64, f64) {
+        let length = self.dimensions[axis];
+        let x = n as f64 * length + if n & 1 == 0 { source } else { length - source };
+        // Reflections alternate between the two walls, starting from the wall in the direction of `n`.
+        let (near, far) = if n > 0 {
+            (axis * 2 + 1, axis * 2)
+        } else {
+            (axis * 2, axis * 2 + 1)
+        };
+        let n = n.unsigned_abs() as i32;
+        let beta = |wall: usize| sqrt(1.0 - clamp01(self.absorption[wall]));
+        let gain = pow(beta(near), ((n + 1) / 2) as f64) * pow(beta(far), (n / 2) as f64);
+        (x, gain)
+    }
+
+    /// Compute early reflections from `source` to `listener` up to reflection `order`,
+    /// excluding the direct path. Positions are clamped to the room.
+    /// The reflections are listed in a fixed order that depends only on `order`.
+    pub fn reflections(
+        &self,
+        source: [f64; 3],
+        listener: [f64; 3],
+        order: usize,
+    ) -> Vec<Reflection> {
+        let mut reflections = Vec::new();
+        self.for_each_reflection(source, listener, order, |reflection| {
+            reflections.push(reflection)
+        });
+        reflections
+    }
+
+    /// Call `f` with each early reflection from `source` to `listener` up to reflection `order`
+    /// in the same order as [`Room::reflections`], without allocating.
+    pub fn for_each_reflection<F: FnMut(Reflection)>(
+        &self,
+        source: [f64; 3],
+        listener: [f64; 3],
+        order: usize,
+        mut f: F,
+    ) {
+        let source = self.clamp(source);
+        let listener = self.clamp(listener);
+        let order = order as i64;
+        for i in -order..=order {
+            for j in -(order - i.abs())..=(order - i.abs()) {
+                let remaining = order - i.abs() - j.abs();
+                for k in -remaining..=remaining {
+                    let reflection_order = (i.abs() + j.abs() + k.abs()) as usize;
+                    if reflection_order == 0 {
+                        continue;
+                    }
+                    let (x, gx) = self.image(0, i, source[0]);
+                    let (y, gy) = self.image(1, j, source[1]);
+                    let (z, gz) = self.image(2, k, source[2]);
+                    let (dx, dy, dz) = (x - listener[0], y - listener[1], z - listener[2]);
+                    let distance = max(0.1, sqrt(dx * dx + dy * dy + dz * dz));
+                    f(Reflection {
+                        delay: distance / SPEED_OF_SOUND,
+                        gain: gx * gy * gz / max(1.0, distance),
+                        pan: dx / distance,
+                        cutoff: 20000.0
+                            * pow(1.0 - 0.5 * clamp01(self.damping), reflection_order as f64),
+                        order: reflection_order,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Longest possible delay in seconds of a reflection up to `order`.
+    pub fn max_delay(&self, order: usize) -> f64 {
+        let [w, d, h] = self.dimensions;
+        (order + 1) as f64 * sqrt(w * w + d * d + h * h) / SPEED_OF_SOUND
+    }
+}
+
+/// Time in seconds to interpolate taps after a change in positions.
+const REFLECTION_SMOOTHING: f64 = 0.05;
+
+/// Interpolated reflection tap.
+#[derive(Clone, Default)]
+struct ReflectionTap<T: Float> {
+    /// Current delay in samples, left and right gains and lowpass coefficient.
+    value: [T; 4],
+    /// Per-sample increments.
+    step: [T; 4],
+    /// Lowpass state.
+    state: T,
+}
+
+/// Image-source early reflections in a shoebox room, rendered as a multi-tap delay
+/// with per-tap gain, lowpass filtering and panning.
+/// The direct path is not included.
+/// Setting: (source position, listener position) in meters.
+/// - Allocates: the delay line.
+/// - Input 0: source signal
+/// - Output 0: left reflections
+/// - Output 1: right reflections
+#[derive(Clone)]
+pub struct Reflections<T: Real> {
+    room: Room,
+    source: [f64; 3],
+    listener: [f64; 3],
+    order: usize,
+    taps: Vec<ReflectionTap<T>>,
+    line: DelayLine<T>,
+    sample_rate: f64,
+    /// Samples until the taps reach their targets.
+    counter: usize,
+}
+
+impl<T: Real> Reflections<T> {
+    /// Create new early reflection renderer.
+    pub fn new(
+        sample_rate: f64,
+        room: &Room,
+        source: [f64; 3],
+        listener: [f64; 3],
+        order: usize,
+    ) -> Self {
+        let mut count = 0;
+        room.for_each_reflection(source, listener, order, |_| count += 1);
+        let mut node = Self {
+            room: room.clone(),
+            source,
+            listener,
+            order,
+            taps: vec![ReflectionTap::default(); count],
+            line: DelayLine::default(),
+            sample_rate,
+            counter: 0,
+        };
+        node.allocate();
+        node.update(false);
+        node
+    }
+
+    /// The room.
+    pub fn room(&self) -> &Room {
+        &self.room
+    }
+
+    /// Current reflections.
+    /// - Allocates: the returned list.
+    pub fn reflections(&self) -> Vec<Reflection> {
+        self.room
+            .reflections(self.source, self.listener, self.order)
+    }
+
+    /// Set source position in meters. Taps are interpolated to the new position.
+    pub fn set_source(&mut self, source: [f64; 3]) {
+        self.source = source;
+        self.update(true);
+    }
+
+    /// Set listener position in meters. Taps are interpolated to the new position.
+    pub fn set_listener(&mut self, listener: [f64; 3]) {
+        self.listener = listener;
+        self.update(true);
+    }
+
+    /// Set room. Taps are interpolated to the new room. Allocates if the room grows.
+    pub fn set_room(&mut self, room: &Room) {
+        self.room = room.clone();
+        self.allocate();
+        self.update(true);
+    }
+
+    fn allocate(&mut self) {
+        let length = (self.room.max_delay(self.order) * self.sample_rate) as usize + 2;
+        if self.line.buffer.len() < (length + 2).next_power_of_two() {
+            self.line.resize(length);
+        }
+    }
+
+    /// Compute tap targets, either interpolated (`smooth`) or immediately.
+    fn update(&mut self, smooth: bool) {
+        let samples = max(1.0, round(REFLECTION_SMOOTHING * self.sample_rate));
+        let z = T::from_f64(1.0 / samples);
+        let sample_rate = self.sample_rate;
+        let taps = &mut self.taps;
+        let mut index = 0;
+        self.room
+            .for_each_reflection(self.source, self.listener, self.order, |reflection| {
+                let tap = &mut taps[index];
+                index += 1;
+                let (left, right) = pan_weights(reflection.pan);
+                let cutoff = min(reflection.cutoff, 0.45 * sample_rate);
+                let target = [
+                    reflection.delay * sample_rate,
+                    reflection.gain * left,
+                    reflection.gain * right,
+                    1.0 - exp(-TAU * cutoff / sample_rate),
+                ]
+                .map(T::from_f64);
+                for i in 0..4 {
+                    if smooth {
+                        tap.step[i] = (target[i] - tap.value[i]) * z;
+                    } else {
+                        tap.value[i] = target[i];
+                        tap.step[i] = T::zero();
+                    }
+                }
+            });
+        self.counter = if smooth { samples as usize } else { 0 };
+    }
+}
+
+impl<T: Real> AudioNode for Reflections<T> {
+    const ID: u64 = 104;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U2;
+    type Setting = ([f64; 3], [f64; 3]);
+
+    fn set(&mut self, (source, listener): Self::Setting) {
+        self.source = source;
+        self.listener = listener;
+        self.update(true);
+    }
+
+    fn reset(&mut self) {
+        self.line.reset();
+        for tap in self.taps.iter_mut() {
+            tap.state = T::zero();
+        }
+        self.update(false);
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            self.line = DelayLine::default();
+            self.allocate();
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.line.write(input[0]);
+        let mut left = T::zero();
+        let mut right = T::zero();
+        for tap in self.taps.iter_mut() {
+            let x = self.line.read_cubic(tap.value[0]);
+            tap.state += (x - tap.state) * tap.value[3];
+            left += tap.state * tap.value[1];
+            right += tap.state * tap.value[2];
+        }
+        if self.counter > 0 {
+            self.counter -= 1;
+            for tap in self.taps.iter_mut() {
+                for i in 0..4 {
+                    tap.value[i] += tap.step[i];
+                }
+            }
+        }
+        [left, right].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Maximum number of tape delay playback heads.
+pub const MAX_TAPE_HEADS: usize = 4;
+
+/// Peak wow modulation of the tape path in seconds (about 0.5% pitch deviation).
+const WOW_DEPTH: f64 = 0.0013;
+
+/// Peak flutter modulation of the tape path in seconds (about 0.2% pitch deviation).
+const FLUTTER_DEPTH: f64 = 0.00005;
+
+/// Maximum rate of change of tape delay time in seconds per second.
+const MAX_GLIDE_RATE: f64 = 0.5;
+
+/// Tape delay with a variable speed tape path. Changes in delay time glide
+/// smoothly, bending pitch like real tape. Wow and flutter modulate the tape path.
+/// Repeats pass through highpass and lowpass filters and tape saturation in the feedback loop.
+/// There are 4 playback heads at 1, 2, 3 and 4 times the delay time.
+/// Setting: (delay time in seconds, feedback amount, mix in 0...1).
+/// - Allocates: the delay line.
+/// - Input 0: input signal
+/// - Output 0: delayed signal mixed with input
+#[derive(Clone)]
+pub struct TapeDelay<T: Real> {
+    line: DelayLine<T>,
+    max_time: f64,
+    /// Target delay time in seconds.
+    time: f64,
+    /// Current delay time in seconds.
+    current: f64,
+    glide: f64,
+    feedback: T,
+    mix: T,
+    heads: [T; MAX_TAPE_HEADS],
+    /// Delay time multiplier of the last active head.
+    longest_head: usize,
+    wow: f64,
+    flutter: f64,
+    drive: T,
+    highpass: f64,
+    lowpass: f64,
+    /// Highpass and lowpass filter states.
+    state: [T; 2],
+    /// Filter and glide coefficients.
+    coefficient: [T; 2],
+    glide_coefficient: f64,
+    /// Time in seconds for wow and flutter.
+    t: f64,
+    sample_rate: f64,
+}
+
+impl<T: Real> TapeDelay<T> {
+    /// Create new tape delay. `max_time` is the longest delay time in seconds.
+    /// The delay time `time` is in seconds, feedback amount `feedback` is in 0...1.5
+    /// (above 1 the delay self-oscillates into saturation) and `mix` is in 0...1.
+    pub fn new(sample_rate: f64, max_time: f64, time: T, feedback: T, mix: T) -> Self {
+        let mut heads = [T::zero(); MAX_TAPE_HEADS];
+        heads[0] = T::one();
+        let mut node = Self {
+            line: DelayLine::default(),
+            max_time: max(0.0, max_time),
+            time: 0.0,
+            current: 0.0,
+            glide: 0.2,
+            feedback: T::zero(),
+            mix: T::zero(),
+            heads,
+            longest_head: 1,
+            wow: 0.2,
+            flutter: 0.2,
+            drive: T::one(),
+            highpass: 100.0,
+            lowpass: 5000.0,
+            state: [T::zero(); 2],
+            coefficient: [T::zero(); 2],
+            glide_coefficient: 0.0,
+            t: 0.0,
+            sample_rate: 0.0,
+        };
+        node.set((time, feedback, mix));
+        node.current = node.time;
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set delay time in seconds. The tape path glides to the new time.
+    pub fn set_time(&mut self, time: T) {
+        self.time = clamp(0.0, self.max_time, time.to_f64());
+    }
+
+    /// Set delay time to `beats` beats at tempo `bpm`.
+    /// The delay time is left unchanged if `bpm` is not positive.
+    pub fn set_tempo(&mut self, bpm: f64, beats: f64) {
+        let time = beats / bpm_hz(bpm);
+        if bpm > 0.0 && time.is_finite() {
+            self.set_time(T::from_f64(time));
+        }
+    }
+
+    /// Set glide time in seconds, the time constant of delay time changes. The default is 0.2 seconds.
+    pub fn set_glide(&mut self, glide: f64) {
+        self.glide = max(0.0, glide);
+        self.update_coefficients();
+    }
+
+    /// Set feedback amount in 0...1.5.
+    pub fn set_feedback(&mut self, feedback: T) {
+        self.feedback = clamp(T::zero(), T::from_f64(1.5), feedback);
+    }
+
+    /// Set wet mix in 0...1.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+
+    /// Set playback head gains. Head `i` plays back at `i + 1` times the delay time.
+    /// The default is `[1.0, 0.0, 0.0, 0.0]`.
+    pub fn set_heads(&mut self, heads: [T; MAX_TAPE_HEADS]) {
+        self.heads = heads;
+        self.longest_head = heads
+            .iter()
+            .rposition(|&gain| gain != T::zero())
+            .map_or(1, |i| i + 1);
+    }
+
+    /// Set wow (slow speed variation) and flutter (fast speed variation) amounts in 0...1.
+    /// The defaults are 0.2.
+    pub fn set_wow_flutter(&mut self, wow: f64, flutter: f64) {
+        self.wow = clamp01(wow);
+        self.flutter = clamp01(flutter);
+    }
+
+    /// Set tape saturation drive as a linear gain. The default is 1.
+    pub fn set_drive(&mut self, drive: T) {
+        self.drive = max(T::from_f64(0.01), drive);
+    }
+
+    /// Set feedback loop highpass and lowpass cutoffs in Hz. The defaults are 100 Hz and 5 kHz.
+    pub fn set_filter(&mut self, highpass: f64, lowpass: f64) {
+        self.highpass = max(0.0, highpass);
+        self.lowpass = max(0.0, lowpass);
+        self.update_coefficients();
+    }
+
+    fn update_coefficients(&mut self) {
+        let c = |f: f64| 1.0 - exp(-TAU * min(f, 0.45 * self.sample_rate) / self.sample_rate);
+        self.coefficient = [T::from_f64(c(self.highpass)), T::from_f64(c(self.lowpass))];
+        self.glide_coefficient = if self.glide > 0.0 {
+            1.0 - exp(-1.0 / (self.glide * self.sample_rate))
+        } else {
+            1.0
+        };
+    }
+}
+
+impl<T: Real> AudioNode for TapeDelay<T> {
+    const ID: u64 = 108;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = (T, T, T);
+
+    fn set(&mut self, (time, feedback, mix): Self::Setting) {
+        self.set_time(time);
+        self.set_feedback(feedback);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        self.line.reset();
+        self.current = self.time;
+        self.state = [T::zero(); 2];
+        self.t = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            let length = ((self.max_time * MAX_TAPE_HEADS as f64 + WOW_DEPTH + FLUTTER_DEPTH)
+                * sample_rate) as usize
+                + 4;
+            self.line.resize(length);
+            self.update_coefficients();
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        // Limit the rate of change to keep tape speed within 0.5...1.5 times nominal.
+        // Head `i` reads at `i + 1` times the delay time, so the longest active head sets the limit.
+        let limit = MAX_GLIDE_RATE / (self.longest_head as f64 * self.sample_rate);
+        self.current += clamp(
+            -limit,
+            limit,
+            (self.time - self.current) * self.glide_coefficient,
+        );
+        let wow =
+            self.wow * WOW_DEPTH * (0.7 * sin(TAU * 0.6 * self.t) + 0.3 * sin(TAU * 1.3 * self.t));
+        let flutter = self.flutter
+            * FLUTTER_DEPTH
+            * (0.8 * sin(TAU * 6.5 * self.t) + 0.2 * sin(TAU * 11.7 * self.t));
+        self.t += 1.0 / self.sample_rate;
+        let mut wet = T::zero();
+        for (i, gain) in self.heads.iter().enumerate() {
+            if *gain != T::zero() {
+                // The current sample is not written yet, so read one sample less.
+                let delay =
+                    (self.current * (i + 1) as f64 + wow + flutter) * self.sample_rate - 1.0;
+                wet += *gain * self.line.read_cubic(T::from_f64(delay));
+            }
+        }
+        let x = input[0] + wet * self.feedback;
+        self.state[0] += (x - self.state[0]) * self.coefficient[0];
+        self.state[1] += (x - self.state[0] - self.state[1]) * self.coefficient[1];
+        self.line
+            .write(tanh(self.state[1] * self.drive) / self.drive);
+        [input[0] * (T::one() - self.mix) + wet * self.mix].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 1)
+    }
+}
@@ -3,9 +3,11 @@
 use super::audionode::*;
 use super::combinator::*;
 use super::follow::*;
+use super::loudness::*;
 use super::math::*;
 use super::shared::*;
 use super::signal::*;
+use super::svf::*;
 use super::*;
 use numeric_array::typenum::*;
 use std::sync::Arc;
@@ -117,7 +119,11 @@ where
     }
 }
 
-/// Look-ahead limiter.
+/// Latency of true peak detection in samples.
+const TRUE_PEAK_DELAY: usize = 6;
+
+/// Look-ahead limiter with optional true peak detection.
+/// Release is program dependent: release slows down after sustained gain reduction.
 #[derive(Clone)]
 pub struct Limiter<T, N, S>
 where
@@ -126,11 +132,19 @@ where
     S: ScalarOrPair<Sample = T>,
 {
     lookahead: f64,
-    #[allow(dead_code)]
-    release: f64,
     sample_rate: f64,
-    reducer: ReduceBuffer<T, Maximum<T>>,
-    follower: AFollow<T, T, S>,
+    /// Output ceiling (amplitude).
+    ceiling: T,
+    /// Channel linking amount in 0...1.
+    link: T,
+    /// Whether inter-sample peaks are detected.
+    true_peak: bool,
+    detector: Vec<TruePeak>,
+    reducer: Vec<ReduceBuffer<T, Maximum<T>>>,
+    /// Fast followers, per channel.
+    follower: Vec<AFollow<T, T, S>>,
+    /// Slow followers, per channel. These hold the gain down after sustained limiting.
+    sustain: Vec<AFollow<T, T, (T, T)>>,
     buffer: Vec<Frame<T, N>>,
     index: usize,
 }
@@ -141,39 +155,97 @@ where
     N: Size<T>,
     S: ScalarOrPair<Sample = T>,
 {
+    #[inline]
+    fn length(&self) -> usize {
+        self.reducer[0].length()
+    }
+
     #[inline]
     fn advance(&mut self) {
         self.index += 1;
-        if self.index >= self.reducer.length() {
+        if self.index >= self.length() {
             self.index = 0;
         }
     }
 
-    fn buffer_length(sample_rate: f64, lookahead: f64) -> usize {
-        max(1, round(sample_rate * lookahead) as usize)
+    fn buffer_length(sample_rate: f64, lookahead: f64, true_peak: bool) -> usize {
+        let length = max(1, round(sample_rate * lookahead) as usize);
+        if true_peak {
+            length + TRUE_PEAK_DELAY
+        } else {
+            length
+        }
     }
 
-    fn new_buffer(sample_rate: f64, lookahead: f64) -> ReduceBuffer<T, Maximum<T>> {
-        ReduceBuffer::new(Self::buffer_length(sample_rate, lookahead), Maximum::new())
+    fn new_buffers(&mut self) {
+        let length = Self::buffer_length(self.sample_rate, self.lookahead, self.true_peak);
+        self.reducer = vec![ReduceBuffer::new(length, Maximum::new()); N::USIZE];
     }
 
     pub fn new(sample_rate: f64, time: S) -> Self {
         let (lookahead, release) = time.broadcast();
-        Limiter {
-            lookahead: lookahead.to_f64(),
-            release: release.to_f64(),
+        let follower = AFollow::new(
             sample_rate,
-            follower: AFollow::new(
-                sample_rate,
-                S::construct(
-                    lookahead * convert::<f64, T>(0.4),
-                    release * convert::<f64, T>(0.4),
-                ),
+            S::construct(
+                lookahead * convert::<f64, T>(0.4),
+                release * convert::<f64, T>(0.4),
+            ),
+        );
+        let sustain = AFollow::new(
+            sample_rate,
+            (
+                release * convert::<f64, T>(10.0),
+                release * convert::<f64, T>(2.0),
             ),
+        );
+        let mut limiter = Limiter {
+            lookahead: lookahead.to_f64(),
+            sample_rate,
+            ceiling: T::one(),
+            link: T::one(),
+            true_peak: false,
+            detector: vec![TruePeak::new(); N::USIZE],
+            reducer: Vec::new(),
+            follower: vec![follower; N::USIZE],
+            sustain: vec![sustain; N::USIZE],
             buffer: vec![],
-            reducer: Self::new_buffer(sample_rate, lookahead.to_f64()),
             index: 0,
+        };
+        limiter.new_buffers();
+        limiter
+    }
+
+    /// Set output ceiling in dB (default is 0 dB).
+    /// In true peak mode, the ceiling is in dBTP.
+    pub fn set_ceiling(&mut self, ceiling: T) {
+        self.ceiling = db_amp(ceiling);
+    }
+
+    /// Enable or disable true peak mode (disabled by default).
+    /// In true peak mode, peaks are detected from a 4x oversampled signal,
+    /// which catches inter-sample peaks. True peak detection adds
+    /// 6 samples of latency. Resets the limiter.
+    pub fn set_true_peak(&mut self, true_peak: bool) {
+        self.true_peak = true_peak;
+        self.new_buffers();
+        self.reset();
+    }
+
+    /// Set channel linking amount in 0...1 (default is 1).
+    /// With full linking, all channels receive the same gain reduction.
+    /// With no linking, channels are limited independently.
+    pub fn set_link(&mut self, link: T) {
+        self.link = clamp01(link);
+    }
+
+    /// Current gain reduction in dB (zero or negative), averaged over channels.
+    pub fn gain_reduction(&self) -> T {
+        let mut reduction = T::zero();
+        for i in 0..N::USIZE {
+            let limit = max(self.follower[i].value(), self.sustain[i].value());
+            reduction += amp_db(min(T::one(), self.ceiling / limit));
         }
+        reduction / T::new(N::I64)
     }
 }
 
@@ -196,12 +268,18 @@ where
     fn set_sample_rate(&mut self, sample_rate: f64) {
         self.index = 0;
         self.sample_rate = sample_rate;
-        let length = Self::buffer_length(sample_rate, self.lookahead);
-        if length != self.reducer.length {
-            self.reducer = Self::new_buffer(sample_rate, self.lookahead);
+        let length = Self::buffer_length(sample_rate, self.lookahead, self.true_peak);
+        if length != self.length() {
+            self.new_buffers();
+        }
+        for i in 0..N::USIZE {
+            self.follower[i].set_sample_rate(sample_rate);
+            self.sustain[i].set_sample_rate(sample_rate);
+            self.follower[i].reset();
+            self.sustain[i].reset();
+            self.detector[i].reset();
+            self.reducer[i].clear();
         }
-        self.follower.set_sample_rate(sample_rate);
-        self.reducer.clear();
         self.buffer.clear();
     }
 
@@ -210,26 +288,48 @@ where
         &mut self,
         input: &Frame<Self::Sample, Self::Inputs>,
     ) -> Frame<Self::Sample, Self::Outputs> {
-        let amplitude = input.iter().fold(T::zero(), |amp, &x| max(amp, abs(x)));
-        self.reducer.set(self.index, amplitude);
-        if self.buffer.len() < self.reducer.length() {
+        let mut total = T::zero();
+        for i in 0..N::USIZE {
+            let mut amplitude = abs(input[i]);
+            if self.true_peak {
+                amplitude = max(
+                    amplitude,
+                    T::from_f64(self.detector[i].tick(input[i].to_f64())),
+                );
+            }
+            self.reducer[i].set(self.index, amplitude);
+            total = max(total, self.reducer[i].total());
+        }
+        // Leave some headroom.
+        let headroom = T::from_f64(1.10);
+        if self.buffer.len() < self.length() {
             // We are filling up the initial buffer.
             self.buffer.push(input.clone());
-            if self.buffer.len() == self.reducer.length() {
+            for i in 0..N::USIZE {
+                self.sustain[i].filter_mono(self.ceiling);
+            }
+            if self.buffer.len() == self.length() {
                 // When the buffer is full, start following from its total peak.
-                self.follower.set_value(self.reducer.total());
+                for i in 0..N::USIZE {
+                    let peak = max(self.reducer[i].total(), total * self.link);
+                    self.follower[i].set_value(max(self.ceiling, peak * headroom));
+                }
             }
             self.advance();
             Frame::default()
         } else {
-            let output = self.buffer[self.index].clone();
+            let mut output = self.buffer[self.index].clone();
             self.buffer[self.index] = input.clone();
-            // Leave some headroom.
-            self.follower
-                .filter_mono(max(T::one(), self.reducer.total() * T::from_f64(1.10)));
             self.advance();
-            let limit = self.follower.value();
-            output * Frame::splat(T::from_f64(1.0) / limit)
+            for i in 0..N::USIZE {
+                let peak = max(self.reducer[i].total(), total * self.link);
+                let target = max(self.ceiling, peak * headroom);
+                self.follower[i].filter_mono(target);
+                self.sustain[i].filter_mono(target);
+                let limit = max(self.follower[i].value(), self.sustain[i].value());
+                output[i] *= self.ceiling / limit;
+            }
+            output
         }
     }
 
@@ -237,16 +337,928 @@ where
         let mut output = new_signal_frame(self.outputs());
         for i in 0..N::USIZE {
             // We pretend that the limiter does not alter the frequency response.
-            output[i] = input[i].delay(self.reducer.length() as f64);
+            output[i] = input[i].delay(self.length() as f64);
         }
         output
     }
 
     fn allocate(&mut self) {
-        if self.buffer.capacity() < self.reducer.length() {
+        if self.buffer.capacity() < self.length() {
+            self.buffer.reserve(self.length() - self.buffer.capacity());
+        }
+    }
+}
+
+/// Gain computer for a downward compressor with a soft knee.
+/// Returns gain change in dB (zero or negative) for input level `x` dB.
+#[inline]
+pub fn compressor_gain<T: Real>(threshold: T, ratio: T, knee: T, x: T) -> T {
+    let over = x - threshold;
+    let slope = T::one() / ratio - T::one();
+    if over + over <= -knee {
+        T::zero()
+    } else if over + over < knee {
+        let y = over + knee * T::from_f64(0.5);
+        slope * y * y / (knee + knee)
+    } else {
+        slope * over
+    }
+}
+
+/// Feedforward compressor without lookahead.
+/// Setting: (threshold in dB, ratio, knee width in dB).
+/// The number of inputs is `I`, either the number of channels (internal sidechain)
+/// or twice the number of channels (external sidechain).
+/// The number of outputs is `O`, either the number of channels,
+/// or one more if a gain reduction output is desired.
+/// - Inputs 0...: input signal
+/// - Inputs (optional): sidechain signal
+/// - Outputs 0...: compressed signal
+/// - Output (optional): gain reduction in dB (zero or negative)
+#[derive(Clone)]
+pub struct Compressor<T: Real, I: Size<T>, O: Size<T>> {
+    _marker: std::marker::PhantomData<(I, O)>,
+    channels: usize,
+    sidechain: bool,
+    threshold: T,
+    ratio: T,
+    knee: T,
+    attack: T,
+    release: T,
+    link: T,
+    sample_rate: f64,
+    acoeff: T,
+    rcoeff: T,
+    /// Per-channel level detectors.
+    followers: Vec<EFollow<T, T>>,
+    /// Per-channel smoothed gain in dB.
+    gain: Vec<T>,
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> Compressor<T, I, O> {
+    /// Create new compressor with `threshold` in dB, `ratio` (1 or greater), `knee` width in dB
+    /// and `(attack, release)` times in seconds. Attack and release times
+    /// are the times it takes for the gain to move halfway to a new value.
+    /// If `sidechain` is set, then the second half of the inputs is the sidechain signal.
+    /// The number of channels is the number of inputs, or half of it with a sidechain.
+    /// If there is one more output than channels, then the last output is gain reduction.
+    pub fn new<S: ScalarOrPair<Sample = T>>(
+        sample_rate: f64,
+        threshold: T,
+        ratio: T,
+        knee: T,
+        time: S,
+        sidechain: bool,
+    ) -> Self {
+        let channels = if sidechain { I::USIZE / 2 } else { I::USIZE };
+        assert!(channels > 0);
+        assert!(I::USIZE == if sidechain { channels * 2 } else { channels });
+        assert!(O::USIZE == channels || O::USIZE == channels + 1);
+        let (attack, release) = time.broadcast();
+        let mut node = Self {
+            _marker: std::marker::PhantomData,
+            channels,
+            sidechain,
+            threshold,
+            ratio: max(T::one(), ratio),
+            knee: max(T::zero(), knee),
+            attack,
+            release,
+            link: T::one(),
+            sample_rate,
+            acoeff: T::zero(),
+            rcoeff: T::zero(),
+            // Level detection is instantaneous; attack and release apply to the gain.
+            followers: vec![EFollow::new(sample_rate, T::zero()); channels],
+            gain: vec![T::zero(); channels],
+        };
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set threshold in dB, ratio (1 or greater) and knee width in dB.
+    pub fn set_curve(&mut self, threshold: T, ratio: T, knee: T) {
+        self.threshold = threshold;
+        self.ratio = max(T::one(), ratio);
+        self.knee = max(T::zero(), knee);
+    }
+
+    /// Set attack and release times in seconds.
+    pub fn set_time(&mut self, attack: T, release: T) {
+        self.attack = attack;
+        self.release = release;
+        self.acoeff = halfway_coefficient(self.sample_rate, attack.to_f64());
+        self.rcoeff = halfway_coefficient(self.sample_rate, release.to_f64());
+    }
+
+    /// Set level detection mode.
+    pub fn set_detector(&mut self, detector: Detector) {
+        for follower in self.followers.iter_mut() {
+            follower.set_detector(detector);
+        }
+    }
+
+    /// Set amount of channel linking in 0...1.
+    /// At 1, all channels receive the same gain reduction (the default).
+    /// At 0, each channel is compressed independently.
+    pub fn set_link(&mut self, link: T) {
+        self.link = clamp01(link);
+    }
+
+    /// Current gain reduction in dB (zero or negative) averaged over channels.
+    pub fn gain_reduction(&self) -> T {
+        self.gain.iter().fold(T::zero(), |acc, &x| acc + x) / T::new(self.channels as i64)
+    }
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> AudioNode for Compressor<T, I, O> {
+    const ID: u64 = 87;
+    type Sample = T;
+    type Inputs = I;
+    type Outputs = O;
+    type Setting = (T, T, T);
+
+    fn set(&mut self, (threshold, ratio, knee): Self::Setting) {
+        self.set_curve(threshold, ratio, knee);
+    }
+
+    fn reset(&mut self) {
+        for follower in self.followers.iter_mut() {
+            follower.reset();
+        }
+        self.gain.fill(T::zero());
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.set_time(self.attack, self.release);
+        for follower in self.followers.iter_mut() {
+            follower.set_sample_rate(sample_rate);
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let offset = if self.sidechain { self.channels } else { 0 };
+        let mut output = Frame::default();
+        let mut linked = T::from_f64(-200.0);
+        for i in 0..self.channels {
+            let level = self.followers[i].follow(input[offset + i]);
+            output[i] = level;
+            linked = max(linked, level);
+        }
+        let mut gain_sum = T::zero();
+        for i in 0..self.channels {
+            let level = lerp(output[i], linked, self.link);
+            let target = compressor_gain(self.threshold, self.ratio, self.knee, level);
+            // Attack is movement toward more gain reduction.
+            let coeff = if target < self.gain[i] {
+                self.acoeff
+            } else {
+                self.rcoeff
+            };
+            self.gain[i] = target + (self.gain[i] - target) * coeff;
+            gain_sum += self.gain[i];
+            output[i] = input[i] * db_amp(self.gain[i]);
+        }
+        if O::USIZE > self.channels {
+            output[self.channels] = gain_sum / T::new(self.channels as i64);
+        }
+        output
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        for i in 0..self.channels {
+            // We pretend that the compressor does not alter the frequency response.
+            output[i] = input[i];
+        }
+        if O::USIZE > self.channels {
+            output[self.channels] = input[0].distort(0.0);
+        }
+        output
+    }
+}
+
+/// Noise gate and downward expander.
+/// Setting: (threshold in dB, range in dB).
+/// The number of inputs is `I`, either the number of channels (internal sidechain)
+/// or twice the number of channels (external sidechain, enabled with the `sidechain` flag).
+/// The number of outputs is `O`, either the number of channels,
+/// or one more if a gate state output is desired.
+/// - Inputs 0...: input signal
+/// - Inputs (optional): sidechain signal
+/// - Outputs 0...: gated signal
+/// - Output (optional): gate gain in 0...1
+#[derive(Clone)]
+pub struct Gate<T: Real, I: Size<T>, O: Size<T>> {
+    _marker: std::marker::PhantomData<(I, O)>,
+    channels: usize,
+    sidechain: bool,
+    threshold: T,
+    range: T,
+    ratio: T,
+    hysteresis: T,
+    attack: T,
+    hold: T,
+    release: T,
+    lookahead: f64,
+    sample_rate: f64,
+    acoeff: T,
+    rcoeff: T,
+    hold_samples: usize,
+    hold_counter: usize,
+    open: bool,
+    /// Smoothed gain in dB.
+    gain: T,
+    /// Whether the sidechain filter is enabled.
+    filter: bool,
+    /// Sidechain highpass filters, one per channel.
+    highpass: Vec<FixedSvf<T, T, HighpassMode<T>>>,
+    /// Sidechain lowpass filters, one per channel.
+    lowpass: Vec<FixedSvf<T, T, LowpassMode<T>>>,
+    /// Detected levels over the look-ahead window.
+    reducer: ReduceBuffer<T, Maximum<T>>,
+    /// Look-ahead delay buffer with interleaved channels.
+    buffer: Vec<T>,
+    index: usize,
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> Gate<T, I, O> {
+    /// Create new gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+    /// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+    /// If `sidechain` is set, then the second half of the inputs is the sidechain signal.
+    /// The number of channels is the number of inputs, or half of it with a sidechain.
+    /// If there is one more output than channels, then the last output is gate gain.
+    pub fn new(
+        sample_rate: f64,
+        threshold: T,
+        range: T,
+        attack: T,
+        hold: T,
+        release: T,
+        sidechain: bool,
+    ) -> Self {
+        let channels = if sidechain { I::USIZE / 2 } else { I::USIZE };
+        assert!(channels > 0);
+        assert!(I::USIZE == if sidechain { channels * 2 } else { channels });
+        assert!(O::USIZE == channels || O::USIZE == channels + 1);
+        let params = SvfParams {
+            sample_rate: T::from_f64(sample_rate),
+            cutoff: T::from_f64(20.0),
+            q: T::from_f64(1.0 / SQRT_2),
+            gain: T::one(),
+        };
+        let highpass = vec![FixedSvf::new(HighpassMode::new(), &params); channels];
+        let params = SvfParams {
+            cutoff: T::from_f64(20_000.0),
+            ..params
+        };
+        let lowpass = vec![FixedSvf::new(LowpassMode::new(), &params); channels];
+        let mut node = Self {
+            _marker: std::marker::PhantomData,
+            channels,
+            sidechain,
+            threshold,
+            range: min(T::zero(), range),
+            ratio: T::from_f64(1.0e6),
+            hysteresis: T::zero(),
+            attack,
+            hold,
+            release,
+            lookahead: 0.0,
+            sample_rate,
+            acoeff: T::zero(),
+            rcoeff: T::zero(),
+            hold_samples: 0,
+            hold_counter: 0,
+            open: false,
+            gain: T::zero(),
+            filter: false,
+            highpass,
+            lowpass,
+            reducer: ReduceBuffer::new(1, Maximum::new()),
+            buffer: vec![],
+            index: 0,
+        };
+        node.set_sample_rate(sample_rate);
+        node.reset();
+        node
+    }
+
+    /// Set threshold in dB and range in dB (maximum attenuation, zero or negative).
+    pub fn set_threshold_range(&mut self, threshold: T, range: T) {
+        self.threshold = threshold;
+        self.range = min(T::zero(), range);
+    }
+
+    /// Set expansion ratio (1 or greater). The gate closes fully at large ratios (the default).
+    /// At smaller ratios, signals below the threshold are attenuated by
+    /// `ratio - 1` dB per dB below threshold, down to the range.
+    pub fn set_ratio(&mut self, ratio: T) {
+        self.ratio = max(T::one(), ratio);
+    }
+
+    /// Set hysteresis in dB. The gate opens when the level rises above the threshold
+    /// and closes only after the level falls below the threshold minus hysteresis.
+    pub fn set_hysteresis(&mut self, hysteresis: T) {
+        self.hysteresis = max(T::zero(), hysteresis);
+    }
+
+    /// Set attack, hold and release times in seconds.
+    pub fn set_time(&mut self, attack: T, hold: T, release: T) {
+        self.attack = attack;
+        self.hold = hold;
+        self.release = release;
+        self.acoeff = halfway_coefficient(self.sample_rate, attack.to_f64());
+        self.rcoeff = halfway_coefficient(self.sample_rate, release.to_f64());
+        self.hold_samples = round(max(0.0, hold.to_f64()) * self.sample_rate) as usize;
+    }
+
+    /// Set sidechain filter cutoffs in Hz. The detector listens to
+    /// the band between `highpass` and `lowpass` cutoffs.
+    /// The sidechain filter is disabled by default.
+    pub fn set_sidechain_filter(&mut self, highpass: T, lowpass: T) {
+        self.filter = true;
+        for filter in self.highpass.iter_mut() {
+            filter.set_cutoff(highpass);
+        }
+        for filter in self.lowpass.iter_mut() {
+            filter.set_cutoff(lowpass);
+        }
+    }
+
+    /// Set look-ahead time in seconds. The signal is delayed by the look-ahead time
+    /// so that the gate can open before transients.
+    /// - Allocates: look-ahead buffers.
+    pub fn set_lookahead(&mut self, lookahead: f64) {
+        self.lookahead = max(0.0, lookahead);
+        self.set_sample_rate(self.sample_rate);
+    }
+
+    /// Whether the gate is currently open.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn lookahead_length(&self) -> usize {
+        round(self.lookahead * self.sample_rate) as usize
+    }
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> AudioNode for Gate<T, I, O> {
+    const ID: u64 = 88;
+    type Sample = T;
+    type Inputs = I;
+    type Outputs = O;
+    type Setting = (T, T);
+
+    fn set(&mut self, (threshold, range): Self::Setting) {
+        self.set_threshold_range(threshold, range);
+    }
+
+    fn reset(&mut self) {
+        self.open = false;
+        self.hold_counter = 0;
+        self.gain = self.range;
+        self.index = 0;
+        self.reducer.clear();
+        self.buffer.fill(T::zero());
+        for filter in self.highpass.iter_mut() {
+            filter.reset();
+        }
+        for filter in self.lowpass.iter_mut() {
+            filter.reset();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.set_time(self.attack, self.hold, self.release);
+        for filter in self.highpass.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+        for filter in self.lowpass.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+        let length = self.lookahead_length();
+        if length + 1 != self.reducer.length() {
+            // The window includes the current sample.
+            self.reducer = ReduceBuffer::new(length + 1, Maximum::new());
             self.buffer
-                .reserve(self.reducer.length() - self.buffer.capacity());
+                .resize(max(1, length) * self.channels, T::zero());
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let offset = if self.sidechain { self.channels } else { 0 };
+        let mut level = T::zero();
+        for i in 0..self.channels {
+            let x = if self.filter {
+                let x = self.highpass[i].filter_mono(input[offset + i]);
+                self.lowpass[i].filter_mono(x)
+            } else {
+                input[offset + i]
+            };
+            level = max(level, abs(x));
+        }
+        let window = self.reducer.length();
+        self.reducer.set(self.index % window, level);
+        let level = amp_db(max(self.reducer.total(), T::from_f64(1.0e-10)));
+
+        if level >= self.threshold {
+            self.open = true;
+            self.hold_counter = self.hold_samples;
+        } else if level < self.threshold - self.hysteresis {
+            if self.hold_counter > 0 {
+                self.hold_counter -= 1;
+            } else {
+                self.open = false;
+            }
+        }
+        let target = if self.open {
+            T::zero()
+        } else {
+            max(
+                self.range,
+                min(
+                    T::zero(),
+                    (level - self.threshold) * (self.ratio - T::one()),
+                ),
+            )
+        };
+        let coeff = if target > self.gain {
+            self.acoeff
+        } else {
+            self.rcoeff
+        };
+        self.gain = target + (self.gain - target) * coeff;
+        let amp = db_amp(self.gain);
+
+        let mut output = Frame::default();
+        if window > 1 {
+            let i = (self.index % (window - 1)) * self.channels;
+            for j in 0..self.channels {
+                output[j] = self.buffer[i + j] * amp;
+                self.buffer[i + j] = input[j];
+            }
+        } else {
+            for j in 0..self.channels {
+                output[j] = input[j] * amp;
+            }
+        }
+        self.index = self.index.wrapping_add(1);
+        if O::USIZE > self.channels {
+            output[self.channels] = amp;
+        }
+        output
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        for i in 0..self.channels {
+            // We pretend that the gate does not alter the frequency response.
+            output[i] = input[i].delay(self.lookahead_length() as f64);
+        }
+        if O::USIZE > self.channels {
+            output[self.channels] = input[0].distort(0.0);
         }
+        output
+    }
+}
+
+/// Adaptive normalizer (automatic gain control) without lookahead.
+/// The gain is adjusted slowly so that the detected level approaches a target level.
+/// Setting: (target level in dB, maximum gain in dB).
+/// - Inputs 0...: input signal
+/// - Outputs 0...: normalized signal
+#[derive(Clone)]
+pub struct Normalizer<T: Real, N: Size<T>> {
+    _marker: std::marker::PhantomData<N>,
+    target: T,
+    max_gain: T,
+    floor: T,
+    attack: T,
+    release: T,
+    detector: Detector,
+    sample_rate: f64,
+    acoeff: T,
+    rcoeff: T,
+    /// Level detector for the combined channels.
+    follower: EFollow<T, T>,
+    /// Smoothed gain in dB.
+    gain: T,
+}
+
+impl<T: Real, N: Size<T>> Normalizer<T, N> {
+    /// Create new normalizer with `target` level in dB, `max_gain` in dB,
+    /// noise `floor` in dB and `(attack, release)` times in seconds.
+    /// Attack is the halfway response time of gain reduction, release is that of gain increase.
+    /// Below the noise floor the gain is frozen. The detector defaults to RMS with a 0.2 second timescale.
+    pub fn new<S: ScalarOrPair<Sample = T>>(
+        sample_rate: f64,
+        target: T,
+        max_gain: T,
+        floor: T,
+        time: S,
+    ) -> Self {
+        let (attack, release) = time.broadcast();
+        let mut node = Self {
+            _marker: std::marker::PhantomData,
+            target,
+            max_gain,
+            floor,
+            attack,
+            release,
+            detector: Detector::Rms(0.2),
+            sample_rate,
+            acoeff: T::zero(),
+            rcoeff: T::zero(),
+            follower: EFollow::new(sample_rate, T::zero()),
+            gain: T::zero(),
+        };
+        node.set_detector(node.detector);
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set target level in dB and maximum gain in dB.
+    pub fn set_target(&mut self, target: T, max_gain: T) {
+        self.target = target;
+        self.max_gain = max_gain;
+    }
+
+    /// Set noise floor in dB. The gain is not adjusted while the level is below the floor.
+    pub fn set_floor(&mut self, floor: T) {
+        self.floor = floor;
+    }
+
+    /// Set attack and release times in seconds.
+    pub fn set_time(&mut self, attack: T, release: T) {
+        self.attack = attack;
+        self.release = release;
+        self.acoeff = halfway_coefficient(self.sample_rate, attack.to_f64());
+        self.rcoeff = halfway_coefficient(self.sample_rate, release.to_f64());
+    }
+
+    /// Set level detection mode.
+    pub fn set_detector(&mut self, detector: Detector) {
+        self.detector = detector;
+        self.follower.set_detector(detector);
+    }
+
+    /// Current gain in dB.
+    pub fn gain(&self) -> T {
+        self.gain
+    }
+}
+
+impl<T: Real, N: Size<T>> AudioNode for Normalizer<T, N> {
+    const ID: u64 = 89;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = N;
+    type Setting = (T, T);
+
+    fn set(&mut self, (target, max_gain): Self::Setting) {
+        self.set_target(target, max_gain);
+    }
+
+    fn reset(&mut self) {
+        self.follower.reset();
+        self.gain = T::zero();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.set_time(self.attack, self.release);
+        self.follower.set_sample_rate(sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        // Combine channels into a single amplitude for the detector.
+        let amplitude = match self.detector {
+            Detector::Peak => input.iter().fold(T::zero(), |acc, &x| max(acc, abs(x))),
+            Detector::Rms(_) => {
+                sqrt(input.iter().fold(T::zero(), |acc, &x| acc + x * x) / T::new(N::I64))
+            }
+        };
+        let level = self.follower.follow(amplitude);
+        if level >= self.floor {
+            let target = min(self.max_gain, self.target - level);
+            let coeff = if target < self.gain {
+                self.acoeff
+            } else {
+                self.rcoeff
+            };
+            self.gain = target + (self.gain - target) * coeff;
+        }
+        input * Frame::splat(db_amp(self.gain))
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        // We pretend that the normalizer does not alter the frequency response.
+        input.clone()
+    }
+}
+
+/// Transient shaper. Emphasizes or suppresses attacks and sustains.
+/// A follower with a fast attack and a slow release tracks the envelope.
+/// Attacks are detected from its difference to a slow attack envelope,
+/// sustains from its difference to a fast release envelope.
+/// Channels are linked.
+/// Setting: (attack gain in dB, sustain gain in dB).
+/// - Inputs 0...: input signal
+/// - Outputs 0...: shaped signal
+#[derive(Clone)]
+pub struct TransientShaper<T: Real, N: Size<T>> {
+    _marker: std::marker::PhantomData<N>,
+    attack: T,
+    sustain: T,
+    attack_time: T,
+    release_time: T,
+    /// Follower with a fast attack and a slow release.
+    fast_attack: EFollow<T, T>,
+    /// Follower with a slow attack and a slow release.
+    slow_attack: EFollow<T, T>,
+    /// Follower with a fast attack and a fast release.
+    fast_release: EFollow<T, T>,
+    /// Current gain in dB.
+    gain: T,
+}
+
+impl<T: Real, N: Size<T>> TransientShaper<T, N> {
+    /// Response time of the fast followers in seconds.
+    const FAST_TIME: f64 = 0.0005;
+    /// Envelope difference in dB that results in full attack or sustain gain.
+    const SENSITIVITY: f64 = 6.0;
+    /// Lowest detected level in dB.
+    const FLOOR: f64 = -80.0;
+
+    /// Create new transient shaper with `attack` and `sustain` gains in dB.
+    /// Positive gains emphasize and negative gains suppress.
+    /// Slow follower attack and release times default to 20 ms and 200 ms.
+    pub fn new(sample_rate: f64, attack: T, sustain: T) -> Self {
+        let follower = EFollow::new(sample_rate, T::zero());
+        let mut node = Self {
+            _marker: std::marker::PhantomData,
+            attack,
+            sustain,
+            attack_time: T::from_f64(0.02),
+            release_time: T::from_f64(0.2),
+            fast_attack: follower.clone(),
+            slow_attack: follower.clone(),
+            fast_release: follower,
+            gain: T::zero(),
+        };
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set attack and sustain gains in dB.
+    pub fn set_gain(&mut self, attack: T, sustain: T) {
+        self.attack = attack;
+        self.sustain = sustain;
+    }
+
+    /// Set slow follower attack and release times in seconds.
+    /// Attacks shorter than the attack time and sustains longer than
+    /// the attack time are shaped.
+    pub fn set_time(&mut self, attack: T, release: T) {
+        self.attack_time = attack;
+        self.release_time = release;
+        let fast = T::from_f64(Self::FAST_TIME);
+        self.fast_attack.set_time(fast, release);
+        self.slow_attack.set_time(attack, release);
+        self.fast_release.set_time(fast, attack);
+    }
+
+    /// Current gain in dB.
+    pub fn gain(&self) -> T {
+        self.gain
+    }
+}
+
+impl<T: Real, N: Size<T>> AudioNode for TransientShaper<T, N> {
+    const ID: u64 = 91;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = N;
+    type Setting = (T, T);
+
+    fn set(&mut self, (attack, sustain): Self::Setting) {
+        self.set_gain(attack, sustain);
+    }
+
+    fn reset(&mut self) {
+        let floor = db_amp(T::from_f64(Self::FLOOR));
+        self.fast_attack.set_value(floor);
+        self.slow_attack.set_value(floor);
+        self.fast_release.set_value(floor);
+        self.gain = T::zero();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.fast_attack.set_sample_rate(sample_rate);
+        self.slow_attack.set_sample_rate(sample_rate);
+        self.fast_release.set_sample_rate(sample_rate);
+        self.set_time(self.attack_time, self.release_time);
+        self.reset();
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let floor = db_amp(T::from_f64(Self::FLOOR));
+        let level = input.iter().fold(floor, |acc, &x| max(acc, abs(x)));
+        let envelope = self.fast_attack.follow(level);
+        let attack = envelope - self.slow_attack.follow(level);
+        let sustain = envelope - self.fast_release.follow(level);
+        let sensitivity = T::from_f64(Self::SENSITIVITY);
+        self.gain = self.attack * clamp01(attack / sensitivity)
+            + self.sustain * clamp01(sustain / sensitivity);
+        input * Frame::splat(db_amp(self.gain))
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        // We pretend that the transient shaper does not alter the frequency response.
+        input.clone()
+    }
+}
+
+/// Split-band de-esser. The signal is split into two bands with a Linkwitz-Riley crossover.
+/// The band above the split frequency is compressed when its level exceeds the threshold.
+/// The rest of the signal is left untouched. Channels are linked.
+/// Setting: (split frequency in Hz, threshold in dB).
+/// - Inputs 0...: input signal
+/// - Outputs 0...: de-essed signal
+/// - Optional last output: sidechain (high band) signal for listening
+#[derive(Clone)]
+pub struct DeEsser<T: Real, I: Size<T>, O: Size<T>> {
+    _marker: std::marker::PhantomData<(I, O)>,
+    frequency: T,
+    threshold: T,
+    range: T,
+    ratio: T,
+    follower: EFollow<T, T>,
+    /// Low band filters, two per channel.
+    lowpass: Vec<FixedSvf<T, T, LowpassMode<T>>>,
+    /// High band filters, two per channel.
+    highpass: Vec<FixedSvf<T, T, HighpassMode<T>>>,
+    /// Current gain of the high band in dB.
+    gain: T,
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> DeEsser<T, I, O> {
+    /// Create new de-esser with split `frequency` in Hz, `threshold` in dB
+    /// and `range` in dB (maximum attenuation, negative).
+    /// Ratio defaults to 4. Attack and release times default to 1 ms and 50 ms.
+    /// If there is one more output than inputs, then the last output is the sidechain signal.
+    pub fn new(sample_rate: f64, frequency: T, threshold: T, range: T) -> Self {
+        assert!(I::USIZE > 0);
+        assert!(O::USIZE == I::USIZE || O::USIZE == I::USIZE + 1);
+        let params = SvfParams {
+            sample_rate: T::from_f64(sample_rate),
+            cutoff: frequency,
+            q: T::from_f64(1.0 / SQRT_2),
+            gain: T::one(),
+        };
+        let mut node = Self {
+            _marker: std::marker::PhantomData,
+            frequency,
+            threshold,
+            range: min(T::zero(), range),
+            ratio: T::from_f64(4.0),
+            follower: EFollow::new(sample_rate, (T::from_f64(0.001), T::from_f64(0.05))),
+            lowpass: vec![FixedSvf::new(LowpassMode::new(), &params); I::USIZE * 2],
+            highpass: vec![FixedSvf::new(HighpassMode::new(), &params); I::USIZE * 2],
+            gain: T::zero(),
+        };
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set split frequency in Hz.
+    pub fn set_frequency(&mut self, frequency: T) {
+        self.frequency = frequency;
+        for filter in self.lowpass.iter_mut() {
+            filter.set_cutoff(frequency);
+        }
+        for filter in self.highpass.iter_mut() {
+            filter.set_cutoff(frequency);
+        }
+    }
+
+    /// Set threshold in dB and range in dB (maximum attenuation, zero or negative).
+    pub fn set_threshold_range(&mut self, threshold: T, range: T) {
+        self.threshold = threshold;
+        self.range = min(T::zero(), range);
+    }
+
+    /// Set compression ratio (1 or greater).
+    pub fn set_ratio(&mut self, ratio: T) {
+        self.ratio = max(T::one(), ratio);
+    }
+
+    /// Set attack and release times in seconds.
+    pub fn set_time(&mut self, attack: T, release: T) {
+        self.follower.set_time(attack, release);
+    }
+
+    /// Current gain reduction of the high band in dB (zero or negative).
+    pub fn gain_reduction(&self) -> T {
+        self.gain
+    }
+}
+
+impl<T: Real, I: Size<T>, O: Size<T>> AudioNode for DeEsser<T, I, O> {
+    const ID: u64 = 92;
+    type Sample = T;
+    type Inputs = I;
+    type Outputs = O;
+    type Setting = (T, T);
+
+    fn set(&mut self, (frequency, threshold): Self::Setting) {
+        self.set_frequency(frequency);
+        self.threshold = threshold;
+    }
+
+    fn reset(&mut self) {
+        self.follower.reset();
+        for filter in self.lowpass.iter_mut() {
+            filter.reset();
+        }
+        for filter in self.highpass.iter_mut() {
+            filter.reset();
+        }
+        self.gain = T::zero();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.follower.set_sample_rate(sample_rate);
+        for filter in self.lowpass.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+        for filter in self.highpass.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let mut low = Frame::<T, I>::default();
+        let mut high = Frame::<T, I>::default();
+        let mut level = T::zero();
+        for i in 0..I::USIZE {
+            let x = self.lowpass[i * 2].filter_mono(input[i]);
+            low[i] = self.lowpass[i * 2 + 1].filter_mono(x);
+            let x = self.highpass[i * 2].filter_mono(input[i]);
+            high[i] = self.highpass[i * 2 + 1].filter_mono(x);
+            level = max(level, abs(high[i]));
+        }
+        let level = self.follower.follow(level);
+        self.gain = max(
+            self.range,
+            compressor_gain(self.threshold, self.ratio, T::zero(), level),
+        );
+        let amp = db_amp(self.gain);
+        let mut output = Frame::default();
+        for i in 0..I::USIZE {
+            output[i] = low[i] + high[i] * amp;
+        }
+        if O::USIZE > I::USIZE {
+            output[I::USIZE] = high.iter().fold(T::zero(), |acc, &x| acc + x) / T::new(I::I64);
+        }
+        output
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        for i in 0..I::USIZE {
+            // The crossover is an allpass filter. We pretend that it does not alter the frequency response.
+            output[i] = input[i];
+        }
+        if O::USIZE > I::USIZE {
+            output[I::USIZE] = input[0].distort(0.0);
+        }
+        output
     }
 }
 
@@ -348,6 +1360,16 @@ pub enum Meter {
     /// RMS meter with smoothing timescale in seconds.
     /// Smoothing timescale is the time it takes for level estimation to move halfway to a new level.
     Rms(f64),
+    /// Momentary loudness (K-weighted, 400 ms window) in LUFS according to EBU R128.
+    Momentary,
+    /// Short-term loudness (K-weighted, 3 s window) in LUFS according to EBU R128.
+    ShortTerm,
+    /// Gated integrated loudness in LUFS since reset according to EBU R128.
+    Integrated,
+    /// Loudness range in LU since reset according to EBU R128.
+    LoudnessRange,
+    /// Maximum true peak amplitude (4x oversampled) since reset.
+    TruePeak,
 }
 
 impl Meter {
@@ -355,6 +1377,18 @@ impl Meter {
     pub fn latest_only(&self) -> bool {
         matches!(self, Meter::Sample)
     }
+
+    /// Whether the meter mode is a loudness measurement according to ITU-R BS.1770.
+    pub fn loudness(&self) -> bool {
+        matches!(
+            self,
+            Meter::Momentary
+                | Meter::ShortTerm
+                | Meter::Integrated
+                | Meter::LoudnessRange
+                | Meter::TruePeak
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -363,6 +1397,8 @@ pub struct MeterState<T: Real> {
     smoothing: T,
     /// Current meter level.
     state: T,
+    /// Loudness meter for loudness modes.
+    loudness: Option<LoudnessMeter>,
 }
 
 impl<T: Real> MeterState<T> {
@@ -371,6 +1407,13 @@ impl<T: Real> MeterState<T> {
         let mut state = Self {
             smoothing: T::zero(),
             state: T::zero(),
+            loudness: if meter.loudness() {
+                let mut loudness = LoudnessMeter::new(DEFAULT_SR, 1);
+                loudness.set_true_peak(matches!(meter, Meter::TruePeak));
+                Some(loudness)
+            } else {
+                None
+            },
         };
         state.set_sample_rate(meter, DEFAULT_SR);
         state
@@ -379,16 +1422,24 @@ impl<T: Real> MeterState<T> {
     /// Reset meter state.
     pub fn reset(&mut self, _meter: Meter) {
         self.state = T::zero();
+        if let Some(loudness) = &mut self.loudness {
+            loudness.reset();
+        }
     }
 
     /// Set meter sample rate.
     pub fn set_sample_rate(&mut self, meter: Meter, sample_rate: f64) {
         let timescale = match meter {
-            Meter::Sample => {
-                return;
-            }
             Meter::Peak(timescale) => timescale,
             Meter::Rms(timescale) => timescale,
+            _ => {
+                if let Some(loudness) = &mut self.loudness {
+                    if loudness.sample_rate() != sample_rate {
+                        loudness.set_sample_rate(sample_rate);
+                    }
+                }
+                return;
+            }
         };
         self.smoothing = T::from_f64(pow(0.5, 1.0 / (timescale * sample_rate)));
     }
@@ -403,6 +1454,11 @@ impl<T: Real> MeterState<T> {
                 self.state =
                     self.state * self.smoothing + squared(value) * (T::one() - self.smoothing)
             }
+            _ => {
+                if let Some(loudness) = &mut self.loudness {
+                    loudness.tick(&[value.to_f64()]);
+                }
+            }
         }
     }
 
@@ -413,6 +1469,16 @@ impl<T: Real> MeterState<T> {
             Meter::Sample => self.state,
             Meter::Peak(_) => self.state,
             Meter::Rms(_) => sqrt(self.state),
+            _ => match &self.loudness {
+                Some(loudness) => T::from_f64(match meter {
+                    Meter::Momentary => loudness.momentary(),
+                    Meter::ShortTerm => loudness.short_term(),
+                    Meter::Integrated => loudness.integrated(),
+                    Meter::LoudnessRange => loudness.loudness_range(),
+                    _ => loudness.true_peak(),
+                }),
+                None => T::zero(),
+            },
         }
     }
 }
@@ -0,0 +1,524 @@
+//! FM (phase modulation) operator synthesis in the style of the DX7.
+
+use super::audionode::*;
+use super::math::*;
+use super::signal::*;
+use super::*;
+use numeric_array::typenum::*;
+use std::path::Path;
+
+/// Operator envelope with four rates and four levels in the style of the DX7.
+/// At note on, the envelope moves from its current level to levels 0, 1 and 2 in turn
+/// and stays at level 2 while the note is held. At note off, it moves to level 3.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FmEnvelope {
+    /// Segment times in seconds. Each time is how long it takes to sweep the full level range.
+    pub times: [f64; 4],
+    /// Segment target levels in 0...1. Levels are on a logarithmic scale spanning `RANGE_DB` decibels,
+    /// except for level 0, which is silence.
+    pub levels: [f64; 4],
+}
+
+impl FmEnvelope {
+    /// Dynamic range of envelope levels in dB.
+    pub const RANGE_DB: f64 = 74.25;
+
+    /// Create new envelope from segment `times` in seconds and target `levels` in 0...1.
+    pub fn new(times: [f64; 4], levels: [f64; 4]) -> Self {
+        Self { times, levels }
+    }
+
+    /// Create new ADSR style envelope with `attack`, `decay` and `release` times in seconds
+    /// and `sustain` level in 0...1.
+    pub fn adsr(attack: f64, decay: f64, sustain: f64, release: f64) -> Self {
+        Self::new(
+            [attack, decay, decay, release],
+            [1.0, sustain, sustain, 0.0],
+        )
+    }
+
+    /// Convert envelope level in 0...1 to amplitude.
+    #[inline]
+    pub fn amplitude<T: Real>(level: T) -> T {
+        if level <= T::zero() {
+            T::zero()
+        } else {
+            db_amp((level - T::one()) * T::from_f64(Self::RANGE_DB))
+        }
+    }
+}
+
+impl Default for FmEnvelope {
+    fn default() -> Self {
+        Self::adsr(0.001, 0.0, 1.0, 0.05)
+    }
+}
+
+/// FM operator parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FmOperator {
+    /// Frequency ratio to note pitch.
+    pub ratio: f64,
+    /// Fixed frequency in Hz. If set, then the ratio is ignored.
+    pub fixed: Option<f64>,
+    /// Detune in cents.
+    pub detune: f64,
+    /// Output level (amplitude) in 0...1.
+    pub level: f64,
+    /// Amplitude envelope.
+    pub envelope: FmEnvelope,
+}
+
+impl FmOperator {
+    /// Create new operator with frequency `ratio` to note pitch, output `level` in 0...1 and `envelope`.
+    pub fn new(ratio: f64, level: f64, envelope: FmEnvelope) -> Self {
+        Self {
+            ratio,
+            fixed: None,
+            detune: 0.0,
+            level,
+            envelope,
+        }
+    }
+
+    /// Create new operator with fixed `frequency` in Hz, output `level` in 0...1 and `envelope`.
+    pub fn fixed(frequency: f64, level: f64, envelope: FmEnvelope) -> Self {
+        Self {
+            ratio: 1.0,
+            fixed: Some(frequency),
+            detune: 0.0,
+            level,
+            envelope,
+        }
+    }
+}
+
+impl Default for FmOperator {
+    fn default() -> Self {
+        Self::new(1.0, 1.0, FmEnvelope::default())
+    }
+}
+
+/// FM patch: operators, modulation matrix and output mix.
+///
+/// ### Example: Two Operator Electric Piano
+/// ```
+/// use fundsp::hacker::*;
+/// let mut patch = FmPatch::new(vec![
+///     FmOperator::new(1.0, 1.0, FmEnvelope::adsr(0.001, 2.0, 0.0, 0.3)),
+///     FmOperator::new(14.0, 0.3, FmEnvelope::adsr(0.001, 0.5, 0.0, 0.3)),
+/// ]);
+/// patch.set_modulation(1, 0, 4.0);
+/// patch.set_output(0, 1.0);
+/// let synth = fm(&patch);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FmPatch {
+    /// Patch name.
+    pub name: String,
+    /// Operators.
+    pub operators: Vec<FmOperator>,
+    /// Modulation matrix. `modulation[i][j]` is the modulation index in radians
+    /// from operator `j` to operator `i`. Diagonal entries are self-feedback.
+    pub modulation: Vec<Vec<f64>>,
+    /// Output gains of operators.
+    pub output: Vec<f64>,
+    /// Transposition in semitones.
+    pub transpose: f64,
+    /// Whether operator phases are reset at note on.
+    pub key_sync: bool,
+}
+
+impl FmPatch {
+    /// Modulation index in radians of a full level DX7 modulator.
+    pub const DX7_INDEX: f64 = 4.0 * PI;
+
+    /// Create new patch from `operators`. There is no modulation and no output initially.
+    pub fn new(operators: Vec<FmOperator>) -> Self {
+        let n = operators.len();
+        Self {
+            name: String::new(),
+            operators,
+            modulation: vec![vec![0.0; n]; n],
+            output: vec![0.0; n],
+            transpose: 0.0,
+            key_sync: true,
+        }
+    }
+
+    /// Number of operators.
+    pub fn size(&self) -> usize {
+        self.operators.len()
+    }
+
+    /// Set modulation `index` in radians from operator `from` to operator `to`.
+    /// Operators may modulate themselves.
+    pub fn set_modulation(&mut self, from: usize, to: usize, index: f64) {
+        self.modulation[to][from] = index;
+    }
+
+    /// Set output `gain` of `operator`.
+    pub fn set_output(&mut self, operator: usize, gain: f64) {
+        self.output[operator] = gain;
+    }
+
+    /// Create new six operator patch with DX7 `algorithm` (1 to 32) and `feedback` index in radians.
+    /// Operators are numbered from zero, so DX7 operator 1 is operator 0.
+    /// Modulators have modulation index `DX7_INDEX` at full level.
+    /// Carriers are mixed with equal gains that sum to one.
+    pub fn dx7(operators: Vec<FmOperator>, algorithm: usize, feedback: f64) -> Self {
+        assert!(operators.len() == 6);
+        assert!((1..=32).contains(&algorithm));
+        // Routes are (modulator, carrier) pairs with DX7 operator numbers.
+        // The feedback route is listed last and modulates its target with the feedback index.
+        let (routes, feedback_route): (&[(usize, usize)], (usize, usize)) = match algorithm {
+            1 => (&[(2, 1), (4, 3), (5, 4), (6, 5)], (6, 6)),
+            2 => (&[(2, 1), (4, 3), (5, 4), (6, 5)], (2, 2)),
+            3 => (&[(2, 1), (3, 2), (5, 4), (6, 5)], (6, 6)),
+            4 => (&[(2, 1), (3, 2), (5, 4), (6, 5)], (4, 6)),
+            5 => (&[(2, 1), (4, 3), (6, 5)], (6, 6)),
+            6 => (&[(2, 1), (4, 3), (6, 5)], (5, 6)),
+            7 => (&[(2, 1), (4, 3), (5, 3), (6, 5)], (6, 6)),
+            8 => (&[(2, 1), (4, 3), (5, 3), (6, 5)], (4, 4)),
+            9 => (&[(2, 1), (4, 3), (5, 3), (6, 5)], (2, 2)),
+            10 => (&[(2, 1), (3, 2), (5, 4), (6, 4)], (3, 3)),
+            11 => (&[(2, 1), (3, 2), (5, 4), (6, 4)], (6, 6)),
+            12 => (&[(2, 1), (4, 3), (5, 3), (6, 3)], (2, 2)),
+            13 => (&[(2, 1), (4, 3), (5, 3), (6, 3)], (6, 6)),
+            14 => (&[(2, 1), (4, 3), (5, 4), (6, 4)], (6, 6)),
+            15 => (&[(2, 1), (4, 3), (5, 4), (6, 4)], (2, 2)),
+            16 => (&[(2, 1), (3, 1), (4, 3), (5, 1), (6, 5)], (6, 6)),
+            17 => (&[(2, 1), (3, 1), (4, 3), (5, 1), (6, 5)], (2, 2)),
+            18 => (&[(2, 1), (3, 1), (4, 1), (5, 4), (6, 5)], (3, 3)),
+            19 => (&[(2, 1), (3, 2), (6, 4), (6, 5)], (6, 6)),
+            20 => (&[(3, 1), (3, 2), (5, 4), (6, 4)], (3, 3)),
+            21 => (&[(3, 1), (3, 2), (6, 4), (6, 5)], (3, 3)),
+            22 => (&[(2, 1), (6, 3), (6, 4), (6, 5)], (6, 6)),
+            23 => (&[(3, 2), (6, 4), (6, 5)], (6, 6)),
+            24 => (&[(6, 3), (6, 4), (6, 5)], (6, 6)),
+            25 => (&[(6, 4), (6, 5)], (6, 6)),
+            26 => (&[(3, 2), (5, 4), (6, 4)], (6, 6)),
+            27 => (&[(3, 2), (5, 4), (6, 4)], (3, 3)),
+            28 => (&[(2, 1), (4, 3), (5, 4)], (5, 5)),
+            29 => (&[(4, 3), (6, 5)], (6, 6)),
+            30 => (&[(4, 3), (5, 4)], (5, 5)),
+            31 => (&[(6, 5)], (6, 6)),
+            _ => (&[], (6, 6)),
+        };
+        let mut patch = Self::new(operators);
+        let mut carrier = [true; 6];
+        for &(from, to) in routes {
+            patch.set_modulation(from - 1, to - 1, Self::DX7_INDEX);
+            carrier[from - 1] = false;
+        }
+        patch.set_modulation(feedback_route.0 - 1, feedback_route.1 - 1, feedback);
+        let carriers = carrier.iter().filter(|&&x| x).count();
+        for (i, &is_carrier) in carrier.iter().enumerate() {
+            if is_carrier {
+                patch.set_output(i, 1.0 / carriers as f64);
+            }
+        }
+        patch
+    }
+
+    /// Create new patch from a 128-byte packed DX7 voice.
+    /// Pitch envelope, LFO, keyboard scaling and velocity sensitivity are not supported.
+    pub fn from_dx7_voice(data: &[u8]) -> std::io::Result<Self> {
+        if data.len() < 128 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "DX7 voice is too short",
+            ));
+        }
+        let level = |x: u8| -> f64 { min(x, 99) as f64 / 99.0 };
+        let time = |rate: u8| -> f64 { 0.002 * pow(2.0, (99.0 - min(rate, 99) as f64) / 7.1) };
+        let mut operators = Vec::new();
+        // Operators are stored in reverse order, starting from operator 6.
+        for i in (0..6).rev() {
+            let op = &data[i * 17..i * 17 + 17];
+            let envelope = FmEnvelope::new(
+                [time(op[0]), time(op[1]), time(op[2]), time(op[3])],
+                [level(op[4]), level(op[5]), level(op[6]), level(op[7])],
+            );
+            let output_level = min(op[14], 99);
+            let amplitude = if output_level == 0 {
+                0.0
+            } else {
+                db_amp((output_level as f64 - 99.0) * 0.75)
+            };
+            let coarse = (op[15] >> 1) & 0x1f;
+            let fine = min(op[16], 99) as f64;
+            let mut operator = if op[15] & 1 == 1 {
+                let frequency = pow(10.0, (coarse & 3) as f64 + fine / 100.0);
+                FmOperator::fixed(frequency, amplitude, envelope)
+            } else {
+                let coarse = if coarse == 0 { 0.5 } else { coarse as f64 };
+                FmOperator::new(coarse * (1.0 + fine / 100.0), amplitude, envelope)
+            };
+            operator.detune = ((op[12] >> 3) & 0xf) as f64 - 7.0;
+            operators.push(operator);
+        }
+        let algorithm = (data[110] & 0x1f) as usize + 1;
+        let feedback = data[111] & 7;
+        let feedback = if feedback == 0 {
+            0.0
+        } else {
+            PI * pow(2.0, feedback as f64 - 7.0)
+        };
+        let mut patch = Self::dx7(operators, algorithm, feedback);
+        patch.key_sync = (data[111] >> 3) & 1 == 1;
+        patch.transpose = min(data[117], 48) as f64 - 24.0;
+        patch.name = data[118..128]
+            .iter()
+            .map(|&c| {
+                if (32..127).contains(&c) {
+                    c as char
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>()
+            .trim_end()
+            .to_string();
+        Ok(patch)
+    }
+
+    /// Load 32 voices from a DX7 bulk dump (SysEx) in memory.
+    /// The header and checksum are optional.
+    pub fn from_dx7_bank(data: &[u8]) -> std::io::Result<Vec<Self>> {
+        let data = if data.len() >= 4104 && data[0] == 0xf0 && data[1] == 0x43 {
+            &data[6..4102]
+        } else {
+            data
+        };
+        if data.len() < 4096 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "DX7 bank is too short",
+            ));
+        }
+        (0..32)
+            .map(|i| Self::from_dx7_voice(&data[i * 128..i * 128 + 128]))
+            .collect()
+    }
+
+    /// Load 32 voices from a DX7 bulk dump (SysEx) file.
+    pub fn load_dx7_bank<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<Self>> {
+        Self::from_dx7_bank(&std::fs::read(path)?)
+    }
+}
+
+/// Envelope state of an operator.
+#[derive(Clone, Default)]
+struct FmEnvelopeState<T: Real> {
+    /// Current segment: 0 to 2 while the note is held, 3 after release.
+    stage: usize,
+    /// Current level in 0...1.
+    level: T,
+}
+
+/// Runtime state of an operator.
+#[derive(Clone, Default)]
+struct FmOperatorState<T: Real> {
+    /// Phase in 0...1. Operators do not use `Sine`, which outputs only the sine of its phase:
+    /// phase modulation is added inside the sine and key sync resets the phase at note on.
+    phase: T,
+    /// Frequency ratio to pitch, including detune. Zero if the frequency is fixed.
+    ratio: T,
+    /// Fixed frequency in Hz including detune, or zero.
+    fixed: T,
+    level: T,
+    /// Per-sample level steps of envelope segments.
+    speed: [T; 4],
+    targets: [T; 4],
+    envelope: FmEnvelopeState<T>,
+    /// Latest output.
+    y1: T,
+    /// Previous output.
+    y2: T,
+}
+
+/// FM operator synth with a configurable modulation matrix.
+/// Operators are sine oscillators that modulate each other's phases.
+/// Operators are evaluated from the highest index to the lowest;
+/// modulation from lower to higher index operators is delayed by one sample.
+/// - Input 0: pitch in Hz
+/// - Input 1: gate. The note is held while gate is positive.
+/// - Output 0: synth output
+#[derive(Clone)]
+pub struct Fm<T: Real> {
+    patch: FmPatch,
+    operators: Vec<FmOperatorState<T>>,
+    modulation: Vec<Vec<T>>,
+    output: Vec<T>,
+    transpose: T,
+    gate: bool,
+    sample_rate: f64,
+    sample_duration: T,
+}
+
+impl<T: Real> Fm<T> {
+    /// Create new FM synth from `patch`.
+    pub fn new(sample_rate: f64, patch: &FmPatch) -> Self {
+        let mut node = Self {
+            patch: patch.clone(),
+            operators: Vec::new(),
+            modulation: Vec::new(),
+            output: Vec::new(),
+            transpose: T::one(),
+            gate: false,
+            sample_rate,
+            sample_duration: T::from_f64(1.0 / sample_rate),
+        };
+        node.set_patch(patch);
+        node
+    }
+
+    /// Current patch.
+    pub fn patch(&self) -> &FmPatch {
+        &self.patch
+    }
+
+    /// Set patch. Operator states are kept if the number of operators does not change.
+    pub fn set_patch(&mut self, patch: &FmPatch) {
+        self.patch = patch.clone();
+        let n = patch.size();
+        assert!(patch.modulation.len() == n && patch.output.len() == n);
+        self.operators.resize(n, FmOperatorState::default());
+        self.modulation = patch
+            .modulation
+            .iter()
+            .map(|row| row.iter().map(|&x| T::from_f64(x)).collect())
+            .collect();
+        self.output = patch.output.iter().map(|&x| T::from_f64(x)).collect();
+        self.transpose = T::from_f64(pow(2.0, patch.transpose / 12.0));
+        for (state, operator) in self.operators.iter_mut().zip(patch.operators.iter()) {
+            let detune = pow(2.0, operator.detune / 1200.0);
+            match operator.fixed {
+                Some(frequency) => {
+                    state.ratio = T::zero();
+                    state.fixed = T::from_f64(frequency * detune);
+                }
+                None => {
+                    state.ratio = T::from_f64(operator.ratio * detune);
+                    state.fixed = T::zero();
+                }
+            }
+            state.level = T::from_f64(operator.level);
+            for i in 0..4 {
+                let time = operator.envelope.times[i] * self.sample_rate;
+                state.speed[i] = T::from_f64(if time > 1.0 { 1.0 / time } else { 1.0 });
+                state.targets[i] = T::from_f64(clamp01(operator.envelope.levels[i]));
+            }
+        }
+    }
+
+    /// Whether any operator envelope is still moving or above zero.
+    pub fn is_active(&self) -> bool {
+        self.gate
+            || self
+                .operators
+                .iter()
+                .any(|state| state.envelope.level > T::zero())
+    }
+
+    /// Start a note.
+    fn note_on(&mut self) {
+        for state in self.operators.iter_mut() {
+            state.envelope.stage = 0;
+            if self.patch.key_sync {
+                state.phase = T::zero();
+            }
+        }
+    }
+
+    /// Release a note.
+    fn note_off(&mut self) {
+        for state in self.operators.iter_mut() {
+            state.envelope.stage = 3;
+        }
+    }
+}
+
+impl<T: Real> AudioNode for Fm<T> {
+    const ID: u64 = 94;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.gate = false;
+        for state in self.operators.iter_mut() {
+            state.phase = T::zero();
+            state.y1 = T::zero();
+            state.y2 = T::zero();
+            state.envelope.stage = 3;
+            state.envelope.level = state.targets[3];
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.sample_duration = T::from_f64(1.0 / sample_rate);
+        let patch = self.patch.clone();
+        self.set_patch(&patch);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let gate = input[1] > T::zero();
+        if gate && !self.gate {
+            self.note_on();
+        } else if !gate && self.gate {
+            self.note_off();
+        }
+        self.gate = gate;
+        let pitch = input[0] * self.transpose;
+        let half = T::from_f64(0.5);
+        let mut output = T::zero();
+        for i in (0..self.operators.len()).rev() {
+            let mut modulation = T::zero();
+            for (j, &index) in self.modulation[i].iter().enumerate() {
+                if index != T::zero() {
+                    let y = if j == i {
+                        // Self-feedback uses the average of the two latest outputs.
+                        (self.operators[j].y1 + self.operators[j].y2) * half
+                    } else {
+                        self.operators[j].y1
+                    };
+                    modulation += index * y;
+                }
+            }
+            let state = &mut self.operators[i];
+            let envelope = &mut state.envelope;
+            let target = state.targets[envelope.stage];
+            let speed = state.speed[envelope.stage];
+            if envelope.level < target {
+                envelope.level = min(target, envelope.level + speed);
+            } else if envelope.level > target {
+                envelope.level = max(target, envelope.level - speed);
+            } else if envelope.stage < 2 {
+                envelope.stage += 1;
+            }
+            let amplitude = FmEnvelope::amplitude(envelope.level) * state.level;
+            let frequency = state.fixed + state.ratio * pitch;
+            let y = sin(state.phase * T::from_f64(TAU) + modulation) * amplitude;
+            state.phase += frequency * self.sample_duration;
+            state.phase -= state.phase.floor();
+            state.y2 = state.y1;
+            state.y1 = y;
+            output += y * self.output[i];
+        }
+        [output].into()
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output
+    }
+}
@@ -1,4 +1,4 @@
-//! Parameter smoothing filter.
+//! Parameter smoothing filters and envelope followers.
 
 use super::audionode::*;
 use super::combinator::*;
@@ -22,6 +22,15 @@ fn halfway_coeff(samples: f64) -> f64 {
     1.0 - min(0.9999999, r2)
 }
 
+/// 1-pole smoothing coefficient for a halfway response time of `time` seconds.
+pub(crate) fn halfway_coefficient<T: Real>(sample_rate: f64, time: f64) -> T {
+    if time <= 0.0 {
+        T::zero()
+    } else {
+        T::from_f64(pow(0.5, 1.0 / (time * sample_rate)))
+    }
+}
+
 /// Smoothing filter with adjustable halfway response time (in seconds).
 /// Setting: response time.
 /// - Input 0: input signal
@@ -263,3 +272,174 @@ impl<T: Float, F: Real, S: ScalarOrPair<Sample = F>> AudioNode for AFollow<T, F,
         output
     }
 }
+
+/// Level detection modes for envelope followers and dynamics processors.
+#[derive(Copy, Clone, Default)]
+pub enum Detector {
+    /// Detect instantaneous peak amplitude.
+    #[default]
+    Peak,
+    /// Detect RMS level with smoothing timescale in seconds.
+    /// Smoothing timescale is the time it takes for level estimation to move halfway to a new level.
+    Rms(f64),
+}
+
+/// Exponential envelope follower. Smooths the detected level in the log domain (dB-linear)
+/// with adjustable halfway response times for attack and release.
+/// Setting: (attack time, release time) in seconds.
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+#[derive(Clone)]
+pub struct EFollow<T: Float, F: Real> {
+    detector: Detector,
+    attack: F,
+    release: F,
+    hold: F,
+    sample_rate: f64,
+    acoeff: F,
+    rcoeff: F,
+    dcoeff: F,
+    hold_samples: usize,
+    hold_counter: usize,
+    /// Mean square in RMS mode.
+    power: F,
+    /// Current level in dB.
+    level: F,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Float, F: Real> EFollow<T, F> {
+    /// Level of silence in dB.
+    const FLOOR: f64 = -200.0;
+
+    /// Create new exponential follower with `(attack, release)` times in seconds.
+    /// Response time is how long it takes for the follower to reach halfway to the new level in dB.
+    /// The follower starts in peak detection mode without hold.
+    pub fn new<S: ScalarOrPair<Sample = F>>(sample_rate: f64, time: S) -> Self {
+        let (attack, release) = time.broadcast();
+        let mut node = Self {
+            detector: Detector::Peak,
+            attack,
+            release,
+            hold: F::zero(),
+            sample_rate,
+            acoeff: F::zero(),
+            rcoeff: F::zero(),
+            dcoeff: F::zero(),
+            hold_samples: 0,
+            hold_counter: 0,
+            power: F::zero(),
+            level: F::from_f64(Self::FLOOR),
+            _marker: std::marker::PhantomData,
+        };
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set attack and release times in seconds.
+    pub fn set_time(&mut self, attack: F, release: F) {
+        self.attack = attack;
+        self.release = release;
+        self.acoeff = halfway_coefficient(self.sample_rate, attack.to_f64());
+        self.rcoeff = halfway_coefficient(self.sample_rate, release.to_f64());
+    }
+
+    /// Set level detection mode.
+    pub fn set_detector(&mut self, detector: Detector) {
+        self.detector = detector;
+        if let Detector::Rms(timescale) = detector {
+            self.dcoeff = halfway_coefficient(self.sample_rate, timescale);
+        }
+    }
+
+    /// Set peak hold time in seconds. After a new peak, release is delayed by the hold time.
+    pub fn set_hold(&mut self, hold: F) {
+        self.hold = hold;
+        self.hold_samples = round(max(0.0, hold.to_f64()) * self.sample_rate) as usize;
+    }
+
+    /// Current envelope as amplitude.
+    #[inline]
+    pub fn value(&self) -> F {
+        if self.level.to_f64() <= Self::FLOOR {
+            F::zero()
+        } else {
+            db_amp(self.level)
+        }
+    }
+
+    /// Current envelope in dB.
+    #[inline]
+    pub fn value_db(&self) -> F {
+        self.level
+    }
+
+    /// Jump to amplitude `x` immediately. Clears any pending peak hold.
+    pub fn set_value(&mut self, x: F) {
+        self.power = x * x;
+        self.level = max(F::from_f64(Self::FLOOR), amp_db(abs(x)));
+        self.hold_counter = 0;
+    }
+
+    /// Process an input sample and return the new level in dB.
+    #[inline]
+    pub fn follow(&mut self, x: F) -> F {
+        let amplitude = match self.detector {
+            Detector::Peak => abs(x),
+            Detector::Rms(_) => {
+                self.power = self.power * self.dcoeff + x * x * (F::one() - self.dcoeff);
+                sqrt(self.power)
+            }
+        };
+        let target = max(F::from_f64(Self::FLOOR), amp_db(amplitude));
+        if target >= self.level {
+            self.level = target + (self.level - target) * self.acoeff;
+            self.hold_counter = self.hold_samples;
+        } else if self.hold_counter > 0 {
+            self.hold_counter -= 1;
+        } else {
+            self.level = target + (self.level - target) * self.rcoeff;
+        }
+        self.level
+    }
+}
+
+impl<T: Float, F: Real> AudioNode for EFollow<T, F> {
+    const ID: u64 = 90;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = (F, F);
+
+    fn set(&mut self, (attack, release): Self::Setting) {
+        self.set_time(attack, release);
+    }
+
+    fn reset(&mut self) {
+        self.power = F::zero();
+        self.level = F::from_f64(Self::FLOOR);
+        self.hold_counter = 0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.set_time(self.attack, self.release);
+        self.set_detector(self.detector);
+        self.set_hold(self.hold);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.follow(convert(input[0]));
+        [convert(self.value())].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = input[0].distort(0.0);
+        output
+    }
+}
@@ -1,5 +1,6 @@
 //! The hacker prelude, a fully 64-bit environment for audio processing.
 
+pub use super::additive::*;
 pub use super::audionode::*;
 pub use super::audiounit::*;
 pub use super::buffer::*;
@@ -10,21 +11,26 @@ pub use super::envelope::*;
 pub use super::feedback::*;
 pub use super::filter::*;
 pub use super::fir::*;
+pub use super::fm::*;
 pub use super::follow::*;
 pub use super::gen::*;
 pub use super::granular::*;
+pub use super::loudness::*;
 pub use super::math::*;
+pub use super::modulation::*;
 pub use super::moog::*;
 pub use super::net::*;
 pub use super::noise::*;
 pub use super::oscillator::*;
 pub use super::oversample::*;
 pub use super::pan::*;
+pub use super::physical::*;
 pub use super::realnet::*;
 pub use super::realseq::*;
 pub use super::resample::*;
 pub use super::resynth::*;
 pub use super::rez::*;
+pub use super::saturation::*;
 pub use super::sequencer::*;
 pub use super::setting::*;
 pub use super::shape::*;
@@ -34,6 +40,7 @@ pub use super::slot::*;
 pub use super::snoop::*;
 pub use super::svf::*;
 pub use super::system::*;
+pub use super::vocoder::*;
 pub use super::wave::*;
 pub use super::wavetable::*;
 pub use super::*;
@@ -987,6 +994,76 @@ where
     An(TapLinear::new(min_delay, max_delay))
 }
 
+/// Variable delay line with selectable fractional delay `interpolation` (see `Interpolation`).
+/// Minimum and maximum delay times are in seconds.
+/// Modulation-safe mode that smooths the delay time is enabled with the `set_smoothing` method.
+/// Setting: interpolation method.
+/// - Allocates: the delay line.
+/// - Input 0: signal.
+/// - Input 1: delay time in seconds.
+/// - Output 0: delayed signal.
+///
+/// ### Example: Modulated Delay With Lagrange Interpolation
+/// ```
+/// use fundsp::hacker::*;
+/// let mut line = variable_delay(0.001, 0.01, Interpolation::Lagrange(5));
+/// line.set_smoothing(0.005);
+/// pass() & (pass() | lfo(|t| lerp11(0.001, 0.01, sin_hz(0.2, t)))) >> line;
+/// ```
+pub fn variable_delay(
+    min_delay: f64,
+    max_delay: f64,
+    interpolation: Interpolation,
+) -> An<VariableDelay<f64>> {
+    super::prelude::variable_delay(min_delay, max_delay, interpolation)
+}
+
+/// Image-source early reflections in shoebox `room` from `source` to `listener`
+/// (positions in meters) up to reflection `order`, rendered as a multi-tap delay
+/// with per-tap gain, lowpass filtering and panning. The direct path is not included.
+/// Setting: (source position, listener position).
+/// - Allocates: the delay line.
+/// - Input 0: source signal
+/// - Output 0: left reflections
+/// - Output 1: right reflections
+///
+/// ### Example: Reflections In A 6 x 8 x 3 Meter Room
+/// ```
+/// use fundsp::hacker::*;
+/// let room = Room::new([6.0, 8.0, 3.0], 0.3);
+/// let mut node = reflections(&room, [2.0, 6.0, 1.5], [3.0, 2.0, 1.7], 3);
+/// node.set_source([4.0, 6.0, 1.5]);
+/// ```
+pub fn reflections(
+    room: &Room,
+    source: [f64; 3],
+    listener: [f64; 3],
+    order: usize,
+) -> An<Reflections<f64>> {
+    super::prelude::reflections(room, source, listener, order)
+}
+
+/// Tape delay with maximum delay time `max_time` seconds, delay time `time` seconds,
+/// `feedback` amount in 0...1.5 (above 1 self-oscillates into saturation) and wet `mix` in 0...1.
+/// Changes in delay time glide smoothly, bending pitch like real tape.
+/// Wow, flutter, saturation drive, feedback filters, glide time, tempo sync and
+/// playback heads at 1, 2, 3 and 4 times the delay time are available as methods.
+/// Setting: (delay time in seconds, feedback amount, mix).
+/// - Allocates: the delay line.
+/// - Input 0: input signal
+/// - Output 0: delayed signal mixed with input
+///
+/// ### Example: Dotted Eighth Tape Echo At 120 BPM
+/// ```
+/// use fundsp::hacker::*;
+/// let mut echo = tape_delay(1.0, 0.375, 0.5, 0.4);
+/// echo.set_tempo(120.0, 0.75);
+/// echo.set_wow_flutter(0.5, 0.3);
+/// ```
+pub fn tape_delay(max_time: f64, time: f64, feedback: f64, mix: f64) -> An<TapeDelay<f64>> {
+    super::prelude::tape_delay(max_time, time, feedback, mix)
+}
+
 /// 2x oversample enclosed `node`.
 /// - Inputs and outputs: from `node`.
 ///
@@ -1198,6 +1275,21 @@ pub fn shape(mode: Shape<f64>) -> An<Shaper<f64>> {
     super::prelude::shape(mode)
 }
 
+/// Shape signal according to shaping mode with antiderivative antialiasing (ADAA) of order `antialias`.
+/// Modes that do not support the requested order use the highest order they support.
+/// First order ADAA adds half a sample of latency and second order ADAA adds one sample.
+/// - Input 0: input signal
+/// - Output 0: shaped signal
+///
+/// ### Example: Antialiased Hard Clipping
+/// ```
+/// use fundsp::hacker::*;
+/// shape_adaa(Shape::Clip, Antialias::Second);
+/// ```
+pub fn shape_adaa(mode: Shape<f64>, antialias: Antialias) -> An<Shaper<f64>> {
+    super::prelude::shape_adaa(mode, antialias)
+}
+
 /// Clip signal to -1...1.
 /// - Input 0: input signal
 /// - Output 0: clipped signal
@@ -1255,8 +1347,43 @@ pub fn follow<S: ScalarOrPair<Sample = f64>>(t: S) -> An<AFollow<f64, f64, S>> {
     An(AFollow::new(DEFAULT_SR, t))
 }
 
+/// Exponential envelope follower with `(attack, release)` halfway response times in seconds.
+/// Detects peak amplitude and smooths it in the log domain (dB-linear).
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+///
+/// ### Example (Envelope Of Noise)
+/// ```
+/// use fundsp::hacker::*;
+/// noise() >> efollow((0.001, 0.1));
+/// ```
+pub fn efollow<S: ScalarOrPair<Sample = f64>>(time: S) -> An<EFollow<f64, f64>> {
+    An(EFollow::new(DEFAULT_SR, time))
+}
+
+/// Exponential envelope follower with `(attack, release)` halfway response times in seconds.
+/// Detects RMS level with smoothing `timescale` in seconds and smooths it in the log domain (dB-linear).
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+pub fn efollow_rms<S: ScalarOrPair<Sample = f64>>(
+    timescale: f64,
+    time: S,
+) -> An<EFollow<f64, f64>> {
+    super::prelude::efollow_rms(timescale, time)
+}
+
+/// Exponential peak hold envelope follower with `(attack, release)` halfway response times in seconds.
+/// After a new peak, release is delayed by `hold` seconds.
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+pub fn efollow_hold<S: ScalarOrPair<Sample = f64>>(hold: f64, time: S) -> An<EFollow<f64, f64>> {
+    super::prelude::efollow_hold(hold, time)
+}
+
 /// Look-ahead limiter with `(attack, release)` times in seconds.
 /// Look-ahead is equal to the attack time.
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
 /// - Allocates: look-ahead buffers.
 /// - Input 0: signal
 /// - Output 0: signal limited to -1...1
@@ -1266,6 +1393,8 @@ pub fn limiter<S: ScalarOrPair<Sample = f64>>(time: S) -> An<Limiter<f64, U1, S>
 
 /// Stereo look-ahead limiter with `(attack, release)` times in seconds.
 /// Look-ahead is equal to the attack time.
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
 /// - Allocates: look-ahead buffers.
 /// - Input 0: left signal
 /// - Input 1: right signal
@@ -1275,6 +1404,281 @@ pub fn limiter_stereo<S: ScalarOrPair<Sample = f64>>(time: S) -> An<Limiter<f64,
     An(Limiter::new(DEFAULT_SR, time))
 }
 
+/// Multichannel look-ahead limiter with `(attack, release)` times in seconds.
+/// Look-ahead is equal to the attack time. Channels are linked by default (see `set_link`).
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
+/// - Allocates: look-ahead buffers.
+/// - Input(s): signal
+/// - Output(s): signal limited to -1...1
+///
+/// ### Example: 4-Channel True Peak Limiter With -1 dBTP Ceiling
+/// ```
+/// use fundsp::hacker::*;
+/// let mut limiter = multilimiter::<U4, _>((0.005, 0.1));
+/// limiter.set_true_peak(true);
+/// limiter.set_ceiling(-1.0);
+/// ```
+pub fn multilimiter<N: Size<f64>, S: ScalarOrPair<Sample = f64>>(
+    time: S,
+) -> An<Limiter<f64, N, S>> {
+    An(Limiter::new(DEFAULT_SR, time))
+}
+
+/// Compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// Attack and release times are halfway response times of the gain.
+/// - Input 0: signal
+/// - Output 0: compressed signal
+///
+/// ### Example: Gentle Vocal Compression
+/// ```
+/// use fundsp::hacker::*;
+/// compressor(-20.0, 3.0, 6.0, (0.005, 0.1));
+/// ```
+pub fn compressor<S: ScalarOrPair<Sample = f64>>(
+    threshold: f64,
+    ratio: f64,
+    knee: f64,
+    time: S,
+) -> An<Compressor<f64, U1, U1>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Stereo linked compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: compressed left signal
+/// - Output 1: compressed right signal
+pub fn compressor_stereo<S: ScalarOrPair<Sample = f64>>(
+    threshold: f64,
+    ratio: f64,
+    knee: f64,
+    time: S,
+) -> An<Compressor<f64, U2, U2>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Sidechain compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// - Input 0: signal
+/// - Input 1: sidechain signal
+/// - Output 0: compressed signal
+pub fn compressor_sidechain<S: ScalarOrPair<Sample = f64>>(
+    threshold: f64,
+    ratio: f64,
+    knee: f64,
+    time: S,
+) -> An<Compressor<f64, U2, U1>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, true,
+    ))
+}
+
+/// Compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// Outputs gain reduction for metering.
+/// - Input 0: signal
+/// - Output 0: compressed signal
+/// - Output 1: gain reduction in dB (zero or negative)
+pub fn compressor_meter<S: ScalarOrPair<Sample = f64>>(
+    threshold: f64,
+    ratio: f64,
+    knee: f64,
+    time: S,
+) -> An<Compressor<f64, U1, U2>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: signal
+/// - Output 0: gated signal
+///
+/// ### Example: Drum Gate
+/// ```
+/// use fundsp::hacker::*;
+/// gate(-40.0, -80.0, 0.0005, 0.05, 0.1);
+/// ```
+pub fn gate(
+    threshold: f64,
+    range: f64,
+    attack: f64,
+    hold: f64,
+    release: f64,
+) -> An<Gate<f64, U1, U1>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Stereo linked noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: gated left signal
+/// - Output 1: gated right signal
+pub fn gate_stereo(
+    threshold: f64,
+    range: f64,
+    attack: f64,
+    hold: f64,
+    release: f64,
+) -> An<Gate<f64, U2, U2>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Noise gate keyed from a sidechain with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: signal
+/// - Input 1: sidechain signal
+/// - Output 0: gated signal
+pub fn gate_sidechain(
+    threshold: f64,
+    range: f64,
+    attack: f64,
+    hold: f64,
+    release: f64,
+) -> An<Gate<f64, U2, U1>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, true,
+    ))
+}
+
+/// Noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// Outputs gate state as a control signal.
+/// - Input 0: signal
+/// - Output 0: gated signal
+/// - Output 1: gate gain in 0...1
+pub fn gate_meter(
+    threshold: f64,
+    range: f64,
+    attack: f64,
+    hold: f64,
+    release: f64,
+) -> An<Gate<f64, U1, U2>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Downward expander with `threshold` in dB, expansion `ratio` (1 or greater),
+/// `range` in dB (maximum attenuation, negative) and `(attack, release)` times in seconds.
+/// - Input 0: signal
+/// - Output 0: expanded signal
+pub fn expander<S: ScalarOrPair<Sample = f64>>(
+    threshold: f64,
+    ratio: f64,
+    range: f64,
+    time: S,
+) -> An<Gate<f64, U1, U1>> {
+    super::prelude::expander(threshold, ratio, range, time)
+}
+
+/// Adaptive normalizer (automatic gain control) without lookahead with `target` RMS level in dB,
+/// `max_gain` in dB, noise `floor` in dB and `(attack, release)` times in seconds.
+/// The gain is not adjusted while the input level is below the noise floor.
+/// - Input 0: signal
+/// - Output 0: normalized signal
+///
+/// ### Example: Speech Leveler
+/// ```
+/// use fundsp::hacker::*;
+/// normalizer(-20.0, 24.0, -50.0, (0.5, 2.0));
+/// ```
+pub fn normalizer<S: ScalarOrPair<Sample = f64>>(
+    target: f64,
+    max_gain: f64,
+    floor: f64,
+    time: S,
+) -> An<Normalizer<f64, U1>> {
+    An(Normalizer::new(DEFAULT_SR, target, max_gain, floor, time))
+}
+
+/// Stereo adaptive normalizer (automatic gain control) without lookahead with `target` RMS level in dB,
+/// `max_gain` in dB, noise `floor` in dB and `(attack, release)` times in seconds.
+/// The gain is not adjusted while the input level is below the noise floor.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: normalized left signal
+/// - Output 1: normalized right signal
+pub fn normalizer_stereo<S: ScalarOrPair<Sample = f64>>(
+    target: f64,
+    max_gain: f64,
+    floor: f64,
+    time: S,
+) -> An<Normalizer<f64, U2>> {
+    An(Normalizer::new(DEFAULT_SR, target, max_gain, floor, time))
+}
+
+/// Transient shaper with `attack` and `sustain` gains in dB.
+/// Positive gains emphasize and negative gains suppress attacks or sustains.
+/// - Input 0: signal
+/// - Output 0: shaped signal
+///
+/// ### Example: Tighten Drums
+/// ```
+/// use fundsp::hacker::*;
+/// transient_shaper(6.0, -6.0);
+/// ```
+pub fn transient_shaper(attack: f64, sustain: f64) -> An<TransientShaper<f64, U1>> {
+    An(TransientShaper::new(DEFAULT_SR, attack, sustain))
+}
+
+/// Stereo transient shaper with `attack` and `sustain` gains in dB.
+/// Positive gains emphasize and negative gains suppress attacks or sustains.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: shaped left signal
+/// - Output 1: shaped right signal
+pub fn transient_shaper_stereo(attack: f64, sustain: f64) -> An<TransientShaper<f64, U2>> {
+    An(TransientShaper::new(DEFAULT_SR, attack, sustain))
+}
+
+/// Split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative). The band above the split frequency is compressed
+/// when its level exceeds the threshold.
+/// - Input 0: signal
+/// - Output 0: de-essed signal
+///
+/// ### Example: Dialogue De-Esser
+/// ```
+/// use fundsp::hacker::*;
+/// deesser(6000.0, -30.0, -12.0);
+/// ```
+pub fn deesser(frequency: f64, threshold: f64, range: f64) -> An<DeEsser<f64, U1, U1>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
+/// Stereo split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative). Channels are linked.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: de-essed left signal
+/// - Output 1: de-essed right signal
+pub fn deesser_stereo(frequency: f64, threshold: f64, range: f64) -> An<DeEsser<f64, U2, U2>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
+/// Split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative) and a sidechain output for listening.
+/// - Input 0: signal
+/// - Output 0: de-essed signal
+/// - Output 1: sidechain (high band) signal
+pub fn deesser_listen(frequency: f64, threshold: f64, range: f64) -> An<DeEsser<f64, U1, U2>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
 /// Pinking filter.
 /// - Input 0: input signal
 /// - Output 0: filtered signal
@@ -1656,6 +2060,62 @@ pub fn reverb4_stereo_delays(
     super::prelude::reverb4_stereo_delays::<f64>(delays, time)
 }
 
+/// Stereo reverb with pre-delay, early reflections and a modulated 16-channel FDN late tail.
+/// `room_size` is in meters (1 to 50 meters). An average room size is 10 meters.
+/// `time` is approximate reverberation time to -60 dB in seconds.
+/// Setting: (room size in meters, reverberation time in seconds, stereo width in 0...1).
+/// Pre-delay, low and high damping, modulation and early reflection level are available as methods.
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: reverberated left signal
+/// - Output 1: reverberated right signal
+///
+/// ### Example: Add 20% Reverb
+/// ```
+/// use fundsp::hacker::*;
+/// multipass() & 0.2 * hall_stereo(20.0, 2.0);
+/// ```
+pub fn hall_stereo(room_size: f64, time: f64) -> An<super::reverb::Hall<f64>> {
+    super::prelude::hall_stereo::<f64>(room_size, time)
+}
+
+/// Plate reverb (Dattorro style figure-of-eight tank) with `decay` in 0...1,
+/// high frequency `damping` in 0...1 and wet `mix` in 0...1.
+/// Setting: (decay, damping, mix).
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Plate Reverb With 30% Mix
+/// ```
+/// use fundsp::hacker::*;
+/// plate_stereo(0.5, 0.1, 0.3);
+/// ```
+pub fn plate_stereo(decay: f64, damping: f64, mix: f64) -> An<super::reverb::Plate<f64>> {
+    super::prelude::plate_stereo(decay, damping, mix)
+}
+
+/// Spring reverb with two dispersive springs with `decay` in 0...1,
+/// high frequency `damping` in 0...1 and wet `mix` in 0...1.
+/// Setting: (decay, damping, mix).
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Spring Reverb With 40% Mix
+/// ```
+/// use fundsp::hacker::*;
+/// spring_stereo(0.7, 0.3, 0.4);
+/// ```
+pub fn spring_stereo(decay: f64, damping: f64, mix: f64) -> An<super::reverb::SpringReverb<f64>> {
+    super::prelude::spring_stereo(decay, damping, mix)
+}
+
 /// Saw-like discrete summation formula oscillator.
 /// - Input 0: frequency in Hz
 /// - Input 1: roughness in 0...1 is the attenuation of successive partials.
@@ -1707,6 +2167,95 @@ pub fn pluck(frequency: f64, gain_per_second: f64, high_frequency_damping: f64)
     ))
 }
 
+/// Digital waveguide string tuned to `frequency` Hz.
+/// `gain_per_second` is the amplitude remaining after one second of free vibration.
+/// High frequency damping is in 0...1.
+/// Excitation position, pickup position and dispersion can be set with
+/// `set_position`, `set_pickup` and `set_dispersion`.
+/// - Allocates: string buffers.
+/// - Input 0: string excitation
+/// - Output 0: string displacement at the pickup
+///
+/// ### Example: Plucked String
+/// ```
+/// use fundsp::hacker::*;
+/// impulse::<U1>() >> waveguide(220.0, 0.1, 0.5);
+/// ```
+pub fn waveguide(
+    frequency: f64,
+    gain_per_second: f64,
+    high_frequency_damping: f64,
+) -> An<Waveguide<f64, U1>> {
+    An(Waveguide::new(
+        DEFAULT_SR,
+        frequency,
+        gain_per_second,
+        high_frequency_damping,
+    ))
+}
+
+/// Bowed digital waveguide string tuned to `frequency` Hz.
+/// `gain_per_second` is the amplitude remaining after one second of free vibration.
+/// High frequency damping is in 0...1. Bow pressure can be set with `set_pressure`.
+/// - Allocates: string buffers.
+/// - Input 0: string excitation
+/// - Input 1: bow velocity (for example, 0.1)
+/// - Output 0: string displacement at the pickup
+///
+/// ### Example: Bowed String
+/// ```
+/// use fundsp::hacker::*;
+/// (zero() | constant(0.1)) >> waveguide_bow(220.0, 0.5, 0.2);
+/// ```
+pub fn waveguide_bow(
+    frequency: f64,
+    gain_per_second: f64,
+    high_frequency_damping: f64,
+) -> An<Waveguide<f64, U2>> {
+    An(Waveguide::new(
+        DEFAULT_SR,
+        frequency,
+        gain_per_second,
+        high_frequency_damping,
+    ))
+}
+
+/// Modal resonator bank with modes of `body` (see `ModalBody`).
+/// `decay` is the -60 dB decay time of the fundamental in seconds.
+/// - Input 0: excitation
+/// - Input 1: fundamental frequency in Hz
+/// - Output 0: resonator output
+///
+/// ### Example: Struck Bell
+/// ```
+/// use fundsp::hacker::*;
+/// (impulse::<U1>() | constant(220.0)) >> modal(ModalBody::Bell, 4.0);
+/// ```
+pub fn modal(body: ModalBody, decay: f64) -> An<Modal<f64, U2>> {
+    An(Modal::new(DEFAULT_SR, 440.0, &body.modes(decay.to_f64())))
+}
+
+/// Modal resonator bank with modes of `body` (see `ModalBody`) and fundamental frequency `f` Hz.
+/// `decay` is the -60 dB decay time of the fundamental in seconds.
+/// - Input 0: excitation
+/// - Output 0: resonator output
+pub fn modal_hz(body: ModalBody, f: f64, decay: f64) -> An<Modal<f64, U1>> {
+    An(Modal::new(DEFAULT_SR, f, &body.modes(decay.to_f64())))
+}
+
+/// Modal resonator bank with fundamental frequency `f` Hz and custom `modes`.
+/// - Input 0: excitation
+/// - Output 0: resonator output
+///
+/// ### Example: Two Mode Wood Block
+/// ```
+/// use fundsp::hacker::*;
+/// impulse::<U1>() >> modal_modes(800.0, &[Mode::new(1.0, 0.5, 0.1), Mode::new(2.7, 0.3, 0.05)]);
+/// ```
+pub fn modal_modes(f: f64, modes: &[Mode]) -> An<Modal<f64, U1>> {
+    An(Modal::new(DEFAULT_SR, f, modes))
+}
+
 /// Saw wavetable oscillator.
 /// - Allocates: global saw wavetable.
 /// - Input 0: frequency in Hz
@@ -1799,6 +2348,61 @@ pub fn hammond_hz(f: f64) -> An<Pipe<f64, Constant<U1, f64>, WaveSynth<'static,
     constant(f) >> hammond()
 }
 
+/// Morphing wavetable oscillator. Interpolates between the frames of `table`.
+/// - Input 0: frequency in Hz
+/// - Input 1: morph position in 0...1
+/// - Output 0: oscillator output
+///
+/// ### Example: Morph From Saw To Square
+/// ```
+/// use fundsp::hacker::*;
+/// use std::sync::Arc;
+/// let saw: Vec<f32> = (0..256).map(|i| i as f32 / 128.0 - 1.0).collect();
+/// let square: Vec<f32> = (0..256).map(|i| if i < 128 { 1.0 } else { -1.0 }).collect();
+/// let table = Arc::new(MorphTable::from_waves(20.0, 20_000.0, 4.0, &[&saw, &square]));
+/// (constant(110.0) | lfo(|t| sin_hz(0.5, t) * 0.5 + 0.5)) >> morph_synth(&table);
+/// ```
+pub fn morph_synth(table: &Arc<MorphTable>) -> An<MorphSynth<f64>> {
+    An(MorphSynth::new(DEFAULT_SR, table))
+}
+
+/// Supersaw oscillator with `voices` detuned saw waves (1 to 16) spread across the stereo field.
+/// Voices are detuned up to 0.2 semitones and gathered near the center.
+/// - Allocates: global saw wavetable.
+/// - Input 0: frequency in Hz
+/// - Output 0: left output
+/// - Output 1: right output
+///
+/// ### Example: Seven Voice Supersaw
+/// ```
+/// use fundsp::hacker::*;
+/// constant(220.0) >> supersaw(7);
+/// ```
+pub fn supersaw(voices: usize) -> An<Unison<f64, U1>> {
+    let mut node = Unison::new(DEFAULT_SR, &SHARED_SAW_TABLE, voices, 0.2, 0.7);
+    node.set_curve(1.5);
+    An(node)
+}
+
+/// Unison oscillator with `voices` detuned copies (1 to 16) of wavetable `table`
+/// spread across the stereo field. Voices are spaced evenly in pitch (see `Unison::set_curve`).
+/// - Input 0: frequency in Hz
+/// - Input 1: detune of the outermost voices in semitones
+/// - Input 2: mix in 0...1 between center (0) and side (1) voices
+/// - Output 0: left output
+/// - Output 1: right output
+///
+/// ### Example: Detuned Square Pad
+/// ```
+/// use fundsp::hacker::*;
+/// use std::sync::Arc;
+/// let table = Arc::new(SQUARE_TABLE.clone());
+/// (constant(110.0) | lfo(|t| 0.1 + 0.1 * sin_hz(0.2, t)) | constant(0.5)) >> unison(&table, 5);
+/// ```
+pub fn unison(table: &Arc<Wavetable>, voices: usize) -> An<Unison<f64, U3>> {
+    An(Unison::new(DEFAULT_SR, table, voices, 0.0, 0.5))
+}
+
 /// Lowpass filter.
 /// - Input 0: audio
 /// - Input 1: cutoff frequency (Hz)
@@ -2112,6 +2716,119 @@ pub fn pulse() -> An<super::prelude::PulseWave<f64>> {
     super::prelude::pulse()
 }
 
+/// PolyBLEP saw oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: saw wave
+pub fn blep_saw() -> An<Blep<f64, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Saw, 0.5))
+}
+
+/// PolyBLEP square oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: square wave
+pub fn blep_square() -> An<Blep<f64, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Pulse, 0.5))
+}
+
+/// PolyBLEP pulse oscillator with audio rate pulse width.
+/// - Input 0: frequency in Hz
+/// - Input 1: pulse width in 0...1
+/// - Output 0: pulse wave
+pub fn blep_pulse() -> An<Blep<f64, U2>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Pulse, 0.5))
+}
+
+/// PolyBLEP triangle oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: triangle wave
+pub fn blep_triangle() -> An<Blep<f64, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Triangle, 0.5))
+}
+
+/// PolyBLEP oscillator that morphs from triangle (0) to saw (0.5) to square (1).
+/// - Input 0: frequency in Hz
+/// - Input 1: morph in 0...1
+/// - Output 0: morphed wave
+pub fn blep_morph() -> An<Blep<f64, U2>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Morph, 0.5))
+}
+
+/// PolyBLEP oscillator with waveform `wave`, hard sync and phase modulation.
+/// The sync input resets the phase when it crosses zero upwards.
+/// - Input 0: frequency in Hz
+/// - Input 1: shape (see `BlepWave`)
+/// - Input 2: sync
+/// - Input 3: phase modulation in cycles
+/// - Output 0: oscillator output
+///
+/// ### Example: Hard Synced Saw
+/// ```
+/// use fundsp::hacker::*;
+/// (dc((220.0, 0.5)) | sine_hz(110.0) | zero()) >> blep(BlepWave::Saw);
+/// ```
+pub fn blep(wave: BlepWave) -> An<Blep<f64, U4>> {
+    An(Blep::new(DEFAULT_SR, wave, 0.5))
+}
+
+/// FM operator synth playing `patch` (see `FmPatch`).
+/// A rising gate starts a note and a falling gate releases it.
+/// - Input 0: pitch in Hz
+/// - Input 1: gate
+/// - Output 0: synth output
+///
+/// ### Example: DX7 Algorithm 5
+/// ```
+/// use fundsp::hacker::*;
+/// let operator = FmOperator::new(1.0, 0.5, FmEnvelope::adsr(0.01, 1.0, 0.5, 0.2));
+/// let patch = FmPatch::dx7(vec![operator; 6], 5, 1.0);
+/// (constant(220.0) | lfo(|t| if t < 1.0 { 1.0 } else { 0.0 })) >> fm(&patch);
+/// ```
+pub fn fm(patch: &FmPatch) -> An<Fm<f64>> {
+    An(Fm::new(DEFAULT_SR, patch))
+}
+
+/// Additive oscillator bank with partials read from the atomic partial table `partials`,
+/// which can be modified on the fly. Partial parameters are updated every 64 samples.
+/// - Input 0: fundamental frequency in Hz
+/// - Output 0: sum of partials
+///
+/// ### Example: Organ Drawbars
+/// ```
+/// use fundsp::hacker::*;
+/// use std::sync::Arc;
+/// let partials = Arc::new(AtomicPartials::harmonic(8));
+/// partials.set_amplitude(0, 0.5);
+/// partials.set_amplitude(1, 0.25);
+/// partials.set_amplitude(3, 0.125);
+/// constant(220.0) >> additive(&partials);
+/// ```
+pub fn additive(partials: &Arc<AtomicPartials>) -> An<Additive<f64, Arc<AtomicPartials>>> {
+    An(Additive::new(DEFAULT_SR, partials.clone()))
+}
+
+/// Additive oscillator bank with `n` partials. Closure `f(t, partials)` writes partial parameters
+/// at time `t` seconds into `partials` every 64 samples.
+/// - Input 0: fundamental frequency in Hz
+/// - Output 0: sum of partials
+///
+/// ### Example: Sweeping Saw Spectrum
+/// ```
+/// use fundsp::hacker::*;
+/// constant(110.0) >> additive_fn(200, |t, partials| {
+///     for (i, partial) in partials.iter_mut().enumerate() {
+///         let k = (i + 1) as f32;
+///         partial.ratio = k;
+///         partial.amplitude = 0.5 / k * exp(-k * (1.0 - sin_hz(0.25, t as f32)) * 0.1);
+///     }
+/// });
+/// ```
+pub fn additive_fn<F>(n: usize, f: F) -> An<Additive<f64, PartialFn<F>>>
+where
+    F: Fn(f64, &mut [Partial]) + Clone + Send + Sync,
+{
+    An(Additive::new(DEFAULT_SR, PartialFn::new(n, f)))
+}
+
 /// Play back a channel of a Wave64.
 /// Optional loop point is the index to jump to at the end of the wave.
 /// - Output 0: wave
@@ -2262,6 +2979,231 @@ pub fn phaser<X: Fn(f64) -> f64 + Clone + Send + Sync>(
     super::prelude::phaser::<f64, _>(feedback_amount, phase_f)
 }
 
+/// Stereo chorus with LFO `rate` in Hz, modulation `depth` in 0...1 and wet `mix` in 0...1.
+/// The right channel LFO is offset by a quarter cycle.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Chorused Saw Wave
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> split::<U2>() >> chorus_stereo(0.3, 0.5, 0.5);
+/// ```
+pub fn chorus_stereo(rate: f64, depth: f64, mix: f64) -> An<Chorus<f64>> {
+    super::prelude::chorus_stereo::<f64>(rate, depth, mix)
+}
+
+/// Multi-voice stereo chorus (ensemble) with `voices` voices per channel (1 to 8),
+/// LFO `rate` in Hz, modulation `depth` in 0...1 and wet `mix` in 0...1.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: String Ensemble
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> split::<U2>() >> ensemble_stereo(4, 0.5, 0.7, 0.6);
+/// ```
+pub fn ensemble_stereo(voices: usize, rate: f64, depth: f64, mix: f64) -> An<Chorus<f64>> {
+    super::prelude::ensemble_stereo::<f64>(voices, rate, depth, mix)
+}
+
+/// Stereo flanger with LFO `rate` in Hz, sweep `depth` in 0...1,
+/// `feedback` in -1...1 and wet `mix` in 0...1. The delay sweeps between 0.5 and 10 ms.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Flanged Saw Wave
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> split::<U2>() >> flanger_stereo(0.1, 0.8, 0.7, 0.5);
+/// ```
+pub fn flanger_stereo(rate: f64, depth: f64, feedback: f64, mix: f64) -> An<Flanger<f64>> {
+    super::prelude::flanger_stereo::<f64>(rate, depth, feedback, mix)
+}
+
+/// Stereo phaser with `stages` first order allpass stages (1 to 24), LFO `rate` in Hz,
+/// sweep `depth` in 0...1, `feedback` in -1...1 and wet `mix` in 0...1.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Phased Saw Wave
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> split::<U2>() >> phaser_stereo(8, 0.2, 1.0, 0.5, 0.5);
+/// ```
+pub fn phaser_stereo(
+    stages: usize,
+    rate: f64,
+    depth: f64,
+    feedback: f64,
+    mix: f64,
+) -> An<Phaser<f64>> {
+    super::prelude::phaser_stereo::<f64>(stages, rate, depth, feedback, mix)
+}
+
+/// Channel vocoder with `bands` bands (8 to 64) spaced logarithmically between `low` and `high` Hz.
+/// The spectral envelope of the modulator is imposed on the carrier.
+/// Formant shift, unvoiced noise injection, emphasis and envelope response time are available as methods.
+/// Setting: (formant shift ratio, unvoiced noise amount in 0...1, emphasis in 0...1).
+/// - Input 0: modulator
+/// - Input 1: carrier
+/// - Output 0: vocoded signal
+///
+/// ### Example: Vocode Noise With A Saw Wave
+/// ```
+/// use fundsp::hacker::*;
+/// let mut node = vocoder(32, 100.0, 8000.0);
+/// node.set_formant_shift(1.5);
+/// (noise() | saw_hz(110.0)) >> node;
+/// ```
+pub fn vocoder(bands: usize, low: f64, high: f64) -> An<Vocoder<f64>> {
+    super::prelude::vocoder(bands, low, high)
+}
+
+/// Wideband Hilbert transformer (allpass pair). Produces an analytic signal whose
+/// quadrature component lags the in-phase component by 90 degrees.
+/// - Input 0: input signal
+/// - Output 0: in-phase component
+/// - Output 1: quadrature component
+///
+/// ### Example: Instantaneous Amplitude
+/// ```
+/// use fundsp::hacker::*;
+/// sine_hz(440.0) >> hilbert() >> map(|x| (x[0] * x[0] + x[1] * x[1]).sqrt());
+/// ```
+pub fn hilbert() -> An<Hilbert<f64>> {
+    super::prelude::hilbert()
+}
+
+/// Single sideband frequency shifter.
+/// Setting: shift in Hz.
+/// - Input 0: input signal
+/// - Input 1: shift in Hz
+/// - Output 0: signal shifted up
+/// - Output 1: signal shifted down
+///
+/// ### Example: Shift Up By An LFO Controlled Amount
+/// ```
+/// use fundsp::hacker::*;
+/// (saw_hz(110.0) | lfo(|t| 100.0 * sin_hz(0.1, t))) >> freq_shift() >> join();
+/// ```
+pub fn freq_shift() -> An<FrequencyShifter<f64, U2>> {
+    super::prelude::freq_shift()
+}
+
+/// Single sideband frequency shifter with shift `f` Hz.
+/// Setting: shift in Hz.
+/// - Input 0: input signal
+/// - Output 0: signal shifted up
+/// - Output 1: signal shifted down
+///
+/// ### Example: Shift Down By 50 Hz
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> freq_shift_hz(50.0) >> (sink() | pass());
+/// ```
+pub fn freq_shift_hz(f: f64) -> An<FrequencyShifter<f64, U1>> {
+    super::prelude::freq_shift_hz(f)
+}
+
+/// Ring modulator with an external carrier.
+/// - Input 0: input signal
+/// - Input 1: carrier signal
+/// - Output 0: modulated signal
+///
+/// ### Example: Ring Modulate With A Triangle Wave
+/// ```
+/// use fundsp::hacker::*;
+/// (saw_hz(110.0) | triangle_hz(300.0)) >> ring_mod();
+/// ```
+pub fn ring_mod() -> An<RingMod<f64, U2>> {
+    super::prelude::ring_mod()
+}
+
+/// Ring modulator with a built-in sine carrier at `f` Hz.
+/// Setting: carrier frequency in Hz.
+/// - Input 0: input signal
+/// - Output 0: modulated signal
+///
+/// ### Example: Ring Modulated Saw Wave
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> ring_mod_hz(300.0);
+/// ```
+pub fn ring_mod_hz(f: f64) -> An<RingMod<f64, U1>> {
+    super::prelude::ring_mod_hz(f)
+}
+
+/// Triode stage with `drive` and `output` amplitudes and grid `bias` voltage in -1...1
+/// (plate current is cut off at -1). Positive grid voltages are limited by grid current,
+/// which also shifts the operating point down after loud passages.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Warm Triode
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> triode(2.0, -0.2, 0.5);
+/// ```
+pub fn triode(drive: f64, bias: f64, output: f64) -> An<Triode<f64>> {
+    super::prelude::triode(drive, bias, output)
+}
+
+/// Tape saturation with Jiles-Atherton magnetic hysteresis, `drive` and `output` amplitudes
+/// and DC `bias` of the magnetizing field.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Saturated Tape
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> tape_saturation(1.5, 0.0, 1.0);
+/// ```
+pub fn tape_saturation(drive: f64, bias: f64, output: f64) -> An<TapeSaturation<f64>> {
+    super::prelude::tape_saturation(drive, bias, output)
+}
+
+/// Transformer saturation with frequency dependent distortion, `drive` and `output` amplitudes
+/// and DC magnetization `bias` of the core. Low frequencies saturate the core
+/// while high frequencies pass through with little distortion.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Saturated Transformer
+/// ```
+/// use fundsp::hacker::*;
+/// saw_hz(110.0) >> transformer_saturation(3.0, 0.1, 0.5);
+/// ```
+pub fn transformer_saturation(
+    drive: f64,
+    bias: f64,
+    output: f64,
+) -> An<TransformerSaturation<f64>> {
+    super::prelude::transformer_saturation(drive, bias, output)
+}
+
 /// Shared float variable. Can be read from and written to from multiple threads.
 ///
 /// ### Example: Add Chorus With Wetness Control
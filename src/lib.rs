@@ -252,6 +252,7 @@ macro_rules! impl_real {
 }
 impl_real! { f32, f64 }
 
+pub mod additive;
 pub mod adsr;
 pub mod audionode;
 pub mod audiounit;
@@ -263,18 +264,22 @@ pub mod envelope;
 pub mod feedback;
 pub mod filter;
 pub mod fir;
+pub mod fm;
 pub mod follow;
 pub mod gen;
 pub mod granular;
 pub mod hacker;
 pub mod hacker32;
+pub mod loudness;
 pub mod math;
+pub mod modulation;
 pub mod moog;
 pub mod net;
 pub mod noise;
 pub mod oscillator;
 pub mod oversample;
 pub mod pan;
+pub mod physical;
 pub mod prelude;
 pub mod realnet;
 pub mod realseq;
@@ -282,6 +287,7 @@ pub mod resample;
 pub mod resynth;
 pub mod reverb;
 pub mod rez;
+pub mod saturation;
 pub mod sequencer;
 pub mod setting;
 pub mod shape;
@@ -292,6 +298,7 @@ pub mod snoop;
 pub mod sound;
 pub mod svf;
 pub mod system;
+pub mod vocoder;
 pub mod wave;
 pub mod wavetable;
 
@@ -0,0 +1,504 @@
+//! Loudness metering according to ITU-R BS.1770 and EBU R128.
+
+use super::math::*;
+
+/// Length of a gating block step (75% overlap of 400 ms blocks) in seconds.
+const STEP_TIME: f64 = 0.1;
+/// Number of steps in a momentary (400 ms) window.
+const MOMENTARY_STEPS: usize = 4;
+/// Number of steps in a short-term (3 s) window.
+const SHORT_TERM_STEPS: usize = 30;
+/// Absolute gate in LUFS.
+const ABSOLUTE_GATE: f64 = -70.0;
+/// Histogram resolution in LU.
+const BIN_WIDTH: f64 = 0.1;
+/// Number of histogram bins. The histogram covers -70...+30 LUFS.
+const BINS: usize = 1000;
+/// Oversampling factor of true peak detection.
+const TRUE_PEAK_PHASES: usize = 4;
+/// Number of taps per phase in the true peak interpolator.
+const TRUE_PEAK_TAPS: usize = 12;
+
+/// Convert mean square energy to loudness in LUFS.
+#[inline]
+fn energy_lufs(energy: f64) -> f64 {
+    if energy > 0.0 {
+        -0.691 + 10.0 * log10(energy)
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
+/// Biquad in transposed direct form II.
+#[derive(Clone, Default)]
+struct KBiquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    s1: f64,
+    s2: f64,
+}
+
+impl KBiquad {
+    #[inline]
+    fn tick(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.s1;
+        self.s1 = self.b1 * x - self.a1 * y + self.s2;
+        self.s2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    fn reset(&mut self) {
+        self.s1 = 0.0;
+        self.s2 = 0.0;
+    }
+}
+
+/// K-weighting filter: a high shelf followed by the RLB highpass.
+#[derive(Clone, Default)]
+struct KWeighting {
+    shelf: KBiquad,
+    highpass: KBiquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: f64) -> Self {
+        // The analog prototypes match the 48 kHz coefficients given in BS.1770.
+        let k = tan(PI * 1681.974450955533 / sample_rate);
+        let q = 0.7071752369554196;
+        let vh = pow(10.0, 3.999843853973347 / 20.0);
+        let vb = pow(vh, 0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = KBiquad {
+            b0: (vh + vb * k / q + k * k) / a0,
+            b1: 2.0 * (k * k - vh) / a0,
+            b2: (vh - vb * k / q + k * k) / a0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+            ..KBiquad::default()
+        };
+        let k = tan(PI * 38.13547087602444 / sample_rate);
+        let q = 0.5003270373238773;
+        let a0 = 1.0 + k / q + k * k;
+        let highpass = KBiquad {
+            b0: 1.0,
+            b1: -2.0,
+            b2: 1.0,
+            a1: 2.0 * (k * k - 1.0) / a0,
+            a2: (1.0 - k / q + k * k) / a0,
+            ..KBiquad::default()
+        };
+        Self { shelf, highpass }
+    }
+
+    #[inline]
+    fn tick(&mut self, x: f64) -> f64 {
+        self.highpass.tick(self.shelf.tick(x))
+    }
+
+    fn reset(&mut self) {
+        self.shelf.reset();
+        self.highpass.reset();
+    }
+}
+
+/// Loudness histogram with 0.1 LU resolution for gated measurements.
+#[derive(Clone)]
+struct Histogram {
+    count: Vec<u64>,
+    energy: Vec<f64>,
+    total: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            count: vec![0; BINS],
+            energy: vec![0.0; BINS],
+            total: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.count.fill(0);
+        self.energy.fill(0.0);
+        self.total = 0;
+    }
+
+    fn bin(lufs: f64) -> usize {
+        min(
+            BINS - 1,
+            max(0.0, (lufs - ABSOLUTE_GATE) / BIN_WIDTH) as usize,
+        )
+    }
+
+    /// Add a block with the given energy. Blocks below the absolute gate are ignored.
+    fn add(&mut self, energy: f64) {
+        let lufs = energy_lufs(energy);
+        if lufs > ABSOLUTE_GATE {
+            let bin = Self::bin(lufs);
+            self.count[bin] += 1;
+            self.energy[bin] += energy;
+            self.total += 1;
+        }
+    }
+
+    /// Bin index of the relative gate that is `offset` LU below the mean loudness
+    /// of blocks above the absolute gate.
+    fn relative_gate(&self, offset: f64) -> Option<usize> {
+        if self.total == 0 {
+            return None;
+        }
+        let energy: f64 = self.energy.iter().sum();
+        let gate = energy_lufs(energy / self.total as f64) + offset;
+        Some(if gate > ABSOLUTE_GATE {
+            Self::bin(gate)
+        } else {
+            0
+        })
+    }
+
+    /// Gated mean loudness in LUFS.
+    fn gated_mean(&self, offset: f64) -> f64 {
+        match self.relative_gate(offset) {
+            Some(gate) => {
+                let count: u64 = self.count[gate..].iter().sum();
+                let energy: f64 = self.energy[gate..].iter().sum();
+                if count > 0 {
+                    energy_lufs(energy / count as f64)
+                } else {
+                    f64::NEG_INFINITY
+                }
+            }
+            None => f64::NEG_INFINITY,
+        }
+    }
+
+    /// Difference between the `high` and `low` percentiles (in 0...1) of gated loudness in LU.
+    fn range(&self, offset: f64, low: f64, high: f64) -> f64 {
+        let gate = match self.relative_gate(offset) {
+            Some(gate) => gate,
+            None => return 0.0,
+        };
+        let count: u64 = self.count[gate..].iter().sum();
+        if count == 0 {
+            return 0.0;
+        }
+        let percentile = |p: f64| -> f64 {
+            let target = (p * (count - 1) as f64).round() as u64;
+            let mut cumulative = 0;
+            for bin in gate..BINS {
+                cumulative += self.count[bin];
+                if cumulative > target {
+                    return ABSOLUTE_GATE + (bin as f64 + 0.5) * BIN_WIDTH;
+                }
+            }
+            ABSOLUTE_GATE + BINS as f64 * BIN_WIDTH
+        };
+        percentile(high) - percentile(low)
+    }
+}
+
+/// Single channel true peak detector according to ITU-R BS.1770-4.
+/// Interpolates the signal with a 4x oversampling polyphase filter.
+/// Interpolated peaks are delayed by about 6 samples.
+#[derive(Clone)]
+pub struct TruePeak {
+    /// Interpolation history.
+    history: [f64; TRUE_PEAK_TAPS],
+    /// Polyphase interpolation filter.
+    interpolator: [[f64; TRUE_PEAK_TAPS]; TRUE_PEAK_PHASES],
+}
+
+impl TruePeak {
+    /// Create a new true peak detector.
+    pub fn new() -> Self {
+        let mut interpolator = [[0.0; TRUE_PEAK_TAPS]; TRUE_PEAK_PHASES];
+        let length = TRUE_PEAK_PHASES * TRUE_PEAK_TAPS;
+        let center = (length - 1) as f64 * 0.5;
+        for (phase, taps) in interpolator.iter_mut().enumerate() {
+            for (tap, c) in taps.iter_mut().enumerate() {
+                let n = (tap * TRUE_PEAK_PHASES + phase) as f64;
+                let t = (n - center) / TRUE_PEAK_PHASES as f64;
+                let sinc = if t == 0.0 {
+                    1.0
+                } else {
+                    sin(PI * t) / (PI * t)
+                };
+                // Blackman window.
+                let w = (n + 0.5) / length as f64;
+                let window = 0.42 - 0.5 * cos(TAU * w) + 0.08 * cos(2.0 * TAU * w);
+                *c = sinc * window;
+            }
+            // Normalize each phase to unity gain at DC.
+            let sum: f64 = taps.iter().sum();
+            for c in taps.iter_mut() {
+                *c /= sum;
+            }
+        }
+        Self {
+            history: [0.0; TRUE_PEAK_TAPS],
+            interpolator,
+        }
+    }
+
+    /// Reset the detector.
+    pub fn reset(&mut self) {
+        self.history.fill(0.0);
+    }
+
+    /// Process the next sample. Returns the maximum absolute value
+    /// of the interpolated signal in the latest sample interval.
+    #[inline]
+    pub fn tick(&mut self, x: f64) -> f64 {
+        self.history.copy_within(0..TRUE_PEAK_TAPS - 1, 1);
+        self.history[0] = x;
+        let mut peak = 0.0;
+        for taps in self.interpolator.iter() {
+            let mut z = 0.0;
+            for (c, h) in taps.iter().zip(self.history.iter()) {
+                z += c * h;
+            }
+            peak = max(peak, abs(z));
+        }
+        peak
+    }
+}
+
+impl Default for TruePeak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Multichannel loudness meter according to ITU-R BS.1770-4 and EBU R128.
+/// Provides momentary, short-term and integrated loudness (LUFS),
+/// loudness range (LU) and true peak level.
+/// All channels are weighted equally.
+#[derive(Clone)]
+pub struct LoudnessMeter {
+    sample_rate: f64,
+    filter: Vec<KWeighting>,
+    /// True peak detectors, per channel.
+    detector: Vec<TruePeak>,
+    /// Whether true peak detection is enabled.
+    detect_true_peak: bool,
+    step_length: usize,
+    step_samples: usize,
+    step_energy: f64,
+    /// Mean square energies of the latest steps.
+    steps: [f64; SHORT_TERM_STEPS],
+    step_index: usize,
+    steps_done: usize,
+    momentary: f64,
+    short_term: f64,
+    max_momentary: f64,
+    max_short_term: f64,
+    true_peak: f64,
+    integrated: Histogram,
+    range: Histogram,
+    /// Integrated loudness, updated every step.
+    integrated_lufs: f64,
+    /// Loudness range, updated every step.
+    range_lu: f64,
+}
+
+impl LoudnessMeter {
+    /// Create a new loudness meter for `channels` channels.
+    pub fn new(sample_rate: f64, channels: usize) -> Self {
+        assert!(channels > 0);
+        let mut meter = Self {
+            sample_rate,
+            filter: vec![KWeighting::new(sample_rate); channels],
+            detector: vec![TruePeak::new(); channels],
+            detect_true_peak: true,
+            step_length: 1,
+            step_samples: 0,
+            step_energy: 0.0,
+            steps: [0.0; SHORT_TERM_STEPS],
+            step_index: 0,
+            steps_done: 0,
+            momentary: f64::NEG_INFINITY,
+            short_term: f64::NEG_INFINITY,
+            max_momentary: f64::NEG_INFINITY,
+            max_short_term: f64::NEG_INFINITY,
+            true_peak: 0.0,
+            integrated: Histogram::new(),
+            range: Histogram::new(),
+            integrated_lufs: f64::NEG_INFINITY,
+            range_lu: 0.0,
+        };
+        meter.set_sample_rate(sample_rate);
+        meter
+    }
+
+    /// Sample rate in Hz.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Number of channels.
+    pub fn channels(&self) -> usize {
+        self.filter.len()
+    }
+
+    /// Enable or disable true peak detection (enabled by default).
+    /// When disabled, true peak measurements report the sample peak instead,
+    /// which saves the cost of the oversampling interpolator.
+    pub fn set_true_peak(&mut self, enabled: bool) {
+        self.detect_true_peak = enabled;
+    }
+
+    /// Set sample rate. Resets the meter.
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        let channels = self.channels();
+        self.filter = vec![KWeighting::new(sample_rate); channels];
+        self.step_length = max(1, round(STEP_TIME * sample_rate) as usize);
+        self.reset();
+    }
+
+    /// Reset the meter, discarding all measurements.
+    pub fn reset(&mut self) {
+        for filter in self.filter.iter_mut() {
+            filter.reset();
+        }
+        for detector in self.detector.iter_mut() {
+            detector.reset();
+        }
+        self.step_samples = 0;
+        self.step_energy = 0.0;
+        self.steps.fill(0.0);
+        self.step_index = 0;
+        self.steps_done = 0;
+        self.momentary = f64::NEG_INFINITY;
+        self.short_term = f64::NEG_INFINITY;
+        self.max_momentary = f64::NEG_INFINITY;
+        self.max_short_term = f64::NEG_INFINITY;
+        self.true_peak = 0.0;
+        self.integrated.reset();
+        self.range.reset();
+        self.integrated_lufs = f64::NEG_INFINITY;
+        self.range_lu = 0.0;
+    }
+
+    /// Mean energy of the latest `n` steps.
+    fn window_energy(&self, n: usize) -> f64 {
+        let mut energy = 0.0;
+        for i in 0..n {
+            energy += self.steps[(self.step_index + SHORT_TERM_STEPS - 1 - i) % SHORT_TERM_STEPS];
+        }
+        energy / n as f64
+    }
+
+    /// Process one frame with one sample per channel.
+    #[inline]
+    pub fn tick(&mut self, frame: &[f64]) {
+        debug_assert!(frame.len() == self.channels());
+        for (channel, &x) in frame.iter().enumerate() {
+            let y = self.filter[channel].tick(x);
+            self.step_energy += y * y;
+            let peak = if self.detect_true_peak {
+                max(self.detector[channel].tick(x), abs(x))
+            } else {
+                abs(x)
+            };
+            self.true_peak = max(self.true_peak, peak);
+        }
+        self.step_samples += 1;
+        if self.step_samples == self.step_length {
+            self.steps[self.step_index] = self.step_energy / self.step_length as f64;
+            self.step_index = (self.step_index + 1) % SHORT_TERM_STEPS;
+            self.steps_done += 1;
+            self.step_samples = 0;
+            self.step_energy = 0.0;
+            if self.steps_done >= MOMENTARY_STEPS {
+                let energy = self.window_energy(MOMENTARY_STEPS);
+                self.momentary = energy_lufs(energy);
+                self.max_momentary = max(self.max_momentary, self.momentary);
+                self.integrated.add(energy);
+                self.integrated_lufs = self.integrated.gated_mean(-10.0);
+            }
+            if self.steps_done >= SHORT_TERM_STEPS {
+                let energy = self.window_energy(SHORT_TERM_STEPS);
+                self.short_term = energy_lufs(energy);
+                self.max_short_term = max(self.max_short_term, self.short_term);
+                self.range.add(energy);
+                self.range_lu = self.range.range(-20.0, 0.10, 0.95);
+            }
+        }
+    }
+
+    /// Momentary loudness (400 ms window) in LUFS, updated every 100 ms.
+    /// Negative infinity if nothing has been measured yet.
+    pub fn momentary(&self) -> f64 {
+        self.momentary
+    }
+
+    /// Short-term loudness (3 s window) in LUFS, updated every 100 ms.
+    /// Negative infinity if nothing has been measured yet.
+    pub fn short_term(&self) -> f64 {
+        self.short_term
+    }
+
+    /// Maximum momentary loudness in LUFS.
+    pub fn max_momentary(&self) -> f64 {
+        self.max_momentary
+    }
+
+    /// Maximum short-term loudness in LUFS.
+    pub fn max_short_term(&self) -> f64 {
+        self.max_short_term
+    }
+
+    /// Gated integrated loudness in LUFS since reset, updated every 100 ms.
+    /// Negative infinity if there is no signal above the absolute gate.
+    pub fn integrated(&self) -> f64 {
+        self.integrated_lufs
+    }
+
+    /// Loudness range in LU since reset, updated every 100 ms.
+    pub fn loudness_range(&self) -> f64 {
+        self.range_lu
+    }
+
+    /// Maximum true peak (4x oversampled) amplitude since reset.
+    pub fn true_peak(&self) -> f64 {
+        self.true_peak
+    }
+
+    /// Maximum true peak level in dBTP since reset.
+    pub fn true_peak_db(&self) -> f64 {
+        amp_db(self.true_peak)
+    }
+}
+
+/// Summary of an offline loudness analysis.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Loudness {
+    /// Gated integrated loudness in LUFS.
+    pub integrated: f64,
+    /// Loudness range in LU.
+    pub range: f64,
+    /// Maximum momentary loudness in LUFS.
+    pub max_momentary: f64,
+    /// Maximum short-term loudness in LUFS.
+    pub max_short_term: f64,
+    /// Maximum true peak level in dBTP.
+    pub true_peak: f64,
+}
+
+impl Loudness {
+    /// Summarize the measurements of a loudness meter.
+    pub fn from_meter(meter: &LoudnessMeter) -> Self {
+        Self {
+            integrated: meter.integrated(),
+            range: meter.loudness_range(),
+            max_momentary: meter.max_momentary(),
+            max_short_term: meter.max_short_term(),
+            true_peak: meter.true_peak_db(),
+        }
+    }
+}
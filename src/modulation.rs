@@ -0,0 +1,799 @@
+//! Modulation effects: chorus, flanger, phaser, frequency shifter and ring modulator.
+
+use super::audionode::*;
+use super::delay::DelayLine;
+use super::math::*;
+use super::signal::*;
+use super::*;
+use num_complex::Complex64;
+use numeric_array::typenum::*;
+
+/// Maximum number of chorus voices per channel.
+pub const MAX_CHORUS_VOICES: usize = 8;
+
+/// Maximum number of phaser allpass stages.
+pub const MAX_PHASER_STAGES: usize = 24;
+
+/// Maximum chorus modulation depth in seconds.
+const CHORUS_DEPTH: f64 = 0.005;
+
+/// Maximum chorus base delay in seconds.
+const CHORUS_MAX_DELAY: f64 = 0.05;
+
+/// Minimum flanger delay in seconds.
+const FLANGER_MIN_DELAY: f64 = 0.0005;
+
+/// Maximum flanger delay in seconds.
+const FLANGER_MAX_DELAY: f64 = 0.01;
+
+/// Phaser sweep range in Hz.
+const PHASER_MIN_FREQUENCY: f64 = 100.0;
+const PHASER_MAX_FREQUENCY: f64 = 8000.0;
+
+/// Largest feedback amount magnitude.
+const MAX_FEEDBACK: f64 = 0.95;
+
+/// Clamp LFO rate in Hz to be non-negative. Non-finite rates stop the LFO.
+fn lfo_rate(rate: f64) -> f64 {
+    if rate.is_finite() {
+        max(rate, 0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Stereo sine LFO with a phase offset between channels.
+#[derive(Clone)]
+struct StereoLfo {
+    phase: f64,
+    rate: f64,
+    /// Phase offset of the right channel in cycles.
+    offset: f64,
+}
+
+impl StereoLfo {
+    fn new(rate: f64) -> Self {
+        Self {
+            phase: 0.0,
+            rate,
+            offset: 0.25,
+        }
+    }
+
+    /// LFO value in -1...1 for `channel` (0 or 1).
+    #[inline]
+    fn value(&self, channel: usize) -> f64 {
+        sin((self.phase + self.offset * channel as f64) * TAU)
+    }
+
+    #[inline]
+    fn advance(&mut self, sample_rate: f64) {
+        self.phase += self.rate / sample_rate;
+        self.phase -= floor(self.phase);
+    }
+}
+
+/// Stereo chorus with up to 8 voices per channel. With more than one voice
+/// the voices are spread evenly in LFO phase and slightly in rate, producing an ensemble effect.
+/// Setting: (LFO rate in Hz, depth in 0...1, feedback in -1...1, mix in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+#[derive(Clone)]
+pub struct Chorus<T: Real> {
+    lines: [DelayLine<T>; 2],
+    /// LFO rate in Hz.
+    rate: f64,
+    /// LFO phase offset of the right channel in cycles.
+    offset: f64,
+    /// LFO phases of voices.
+    phases: [f64; MAX_CHORUS_VOICES],
+    voices: usize,
+    /// Base delay in seconds.
+    delay: f64,
+    depth: T,
+    feedback: T,
+    mix: T,
+    wet: [T; 2],
+    sample_rate: f64,
+}
+
+impl<T: Real> Chorus<T> {
+    /// Create new chorus with `voices` voices per channel (1 to 8),
+    /// LFO `rate` in Hz, `depth` in 0...1 and wet `mix` in 0...1.
+    pub fn new(sample_rate: f64, voices: usize, rate: T, depth: T, mix: T) -> Self {
+        let mut node = Self {
+            lines: Default::default(),
+            rate: 0.0,
+            offset: 0.25,
+            phases: [0.0; MAX_CHORUS_VOICES],
+            voices: 1,
+            delay: 0.015,
+            depth: T::zero(),
+            feedback: T::zero(),
+            mix: T::zero(),
+            wet: [T::zero(); 2],
+            sample_rate: 0.0,
+        };
+        node.set_voices(voices);
+        node.set((rate, depth, T::zero(), mix));
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set number of voices per channel (1 to 8).
+    /// Voices are spread evenly in LFO phase.
+    pub fn set_voices(&mut self, voices: usize) {
+        self.voices = voices.clamp(1, MAX_CHORUS_VOICES);
+        self.reset_phases();
+    }
+
+    fn reset_phases(&mut self) {
+        for (voice, phase) in self.phases.iter_mut().enumerate() {
+            *phase = voice as f64 / self.voices as f64;
+        }
+    }
+
+    /// Set LFO rate in Hz.
+    pub fn set_rate(&mut self, rate: T) {
+        self.rate = lfo_rate(rate.to_f64());
+    }
+
+    /// Set LFO rate to one cycle per `beats` beats at tempo `bpm`.
+    pub fn set_tempo(&mut self, bpm: f64, beats: f64) {
+        self.rate = lfo_rate(bpm_hz(bpm) / beats);
+    }
+
+    /// Set modulation depth in 0...1. Full depth modulates the delay by 5 ms.
+    pub fn set_depth(&mut self, depth: T) {
+        self.depth = clamp01(depth);
+    }
+
+    /// Set feedback amount in -1...1. The magnitude is limited to 0.95.
+    pub fn set_feedback(&mut self, feedback: T) {
+        self.feedback = clamp(
+            T::from_f64(-MAX_FEEDBACK),
+            T::from_f64(MAX_FEEDBACK),
+            feedback,
+        );
+    }
+
+    /// Set wet mix in 0...1.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+
+    /// Set base delay in seconds (at most 50 ms). The default is 15 ms.
+    pub fn set_delay(&mut self, delay: f64) {
+        self.delay = clamp(0.0, CHORUS_MAX_DELAY, delay);
+    }
+
+    /// Set LFO phase offset of the right channel in cycles. The default is 0.25.
+    pub fn set_stereo_phase(&mut self, offset: f64) {
+        self.offset = offset;
+    }
+}
+
+impl<T: Real> AudioNode for Chorus<T> {
+    const ID: u64 = 105;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T, T);
+
+    fn set(&mut self, (rate, depth, feedback, mix): Self::Setting) {
+        self.set_rate(rate);
+        self.set_depth(depth);
+        self.set_feedback(feedback);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        for line in self.lines.iter_mut() {
+            line.reset();
+        }
+        self.reset_phases();
+        self.wet = [T::zero(); 2];
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            let length = ((CHORUS_MAX_DELAY + CHORUS_DEPTH) * sample_rate) as usize + 2;
+            for line in self.lines.iter_mut() {
+                line.resize(length);
+            }
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let depth = self.depth.to_f64() * CHORUS_DEPTH * self.sample_rate;
+        let base = self.delay * self.sample_rate;
+        let z = T::from_f64(1.0 / sqrt(self.voices as f64));
+        let mut output = [T::zero(); 2];
+        for channel in 0..2 {
+            self.lines[channel].write(input[channel] + self.wet[channel] * self.feedback);
+            let mut wet = T::zero();
+            for voice in 0..self.voices {
+                let lfo = sin((self.phases[voice] + self.offset * channel as f64) * TAU);
+                let delay = base + depth * 0.5 * (1.0 + lfo);
+                wet += self.lines[channel].read(T::from_f64(delay));
+            }
+            // Feed back the voice average to keep the loop gain below unity.
+            self.wet[channel] = wet / T::new(self.voices as i64);
+            output[channel] = input[channel] * (T::one() - self.mix) + wet * z * self.mix;
+        }
+        // Ensemble voices drift against each other in rate.
+        for voice in 0..self.voices {
+            let phase =
+                self.phases[voice] + self.rate * (1.0 + 0.07 * voice as f64) / self.sample_rate;
+            self.phases[voice] = phase - floor(phase);
+        }
+        output.into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Stereo flanger. The delay sweeps between 0.5 and 10 milliseconds.
+/// Setting: (LFO rate in Hz, depth in 0...1, feedback in -1...1, mix in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+#[derive(Clone)]
+pub struct Flanger<T: Real> {
+    lines: [DelayLine<T>; 2],
+    lfo: StereoLfo,
+    depth: T,
+    feedback: T,
+    mix: T,
+    wet: [T; 2],
+    sample_rate: f64,
+}
+
+impl<T: Real> Flanger<T> {
+    /// Create new flanger with LFO `rate` in Hz, `depth` in 0...1,
+    /// `feedback` in -1...1 and wet `mix` in 0...1.
+    pub fn new(sample_rate: f64, rate: T, depth: T, feedback: T, mix: T) -> Self {
+        let mut node = Self {
+            lines: Default::default(),
+            lfo: StereoLfo::new(0.0),
+            depth: T::zero(),
+            feedback: T::zero(),
+            mix: T::zero(),
+            wet: [T::zero(); 2],
+            sample_rate: 0.0,
+        };
+        node.set((rate, depth, feedback, mix));
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set LFO rate in Hz.
+    pub fn set_rate(&mut self, rate: T) {
+        self.lfo.rate = lfo_rate(rate.to_f64());
+    }
+
+    /// Set LFO rate to one cycle per `beats` beats at tempo `bpm`.
+    pub fn set_tempo(&mut self, bpm: f64, beats: f64) {
+        self.lfo.rate = lfo_rate(bpm_hz(bpm) / beats);
+    }
+
+    /// Set sweep depth in 0...1.
+    pub fn set_depth(&mut self, depth: T) {
+        self.depth = clamp01(depth);
+    }
+
+    /// Set feedback amount in -1...1. The magnitude is limited to 0.95.
+    pub fn set_feedback(&mut self, feedback: T) {
+        self.feedback = clamp(
+            T::from_f64(-MAX_FEEDBACK),
+            T::from_f64(MAX_FEEDBACK),
+            feedback,
+        );
+    }
+
+    /// Set wet mix in 0...1.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+
+    /// Set LFO phase offset of the right channel in cycles. The default is 0.25.
+    pub fn set_stereo_phase(&mut self, offset: f64) {
+        self.lfo.offset = offset;
+    }
+}
+
+impl<T: Real> AudioNode for Flanger<T> {
+    const ID: u64 = 106;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T, T);
+
+    fn set(&mut self, (rate, depth, feedback, mix): Self::Setting) {
+        self.set_rate(rate);
+        self.set_depth(depth);
+        self.set_feedback(feedback);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        for line in self.lines.iter_mut() {
+            line.reset();
+        }
+        self.lfo.phase = 0.0;
+        self.wet = [T::zero(); 2];
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            let length = (FLANGER_MAX_DELAY * sample_rate) as usize + 2;
+            for line in self.lines.iter_mut() {
+                line.resize(length);
+            }
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let range = (FLANGER_MAX_DELAY - FLANGER_MIN_DELAY) * self.depth.to_f64();
+        let mut output = [T::zero(); 2];
+        for channel in 0..2 {
+            self.lines[channel].write(input[channel] + self.wet[channel] * self.feedback);
+            let lfo = self.lfo.value(channel);
+            let delay = (FLANGER_MIN_DELAY + range * 0.5 * (1.0 + lfo)) * self.sample_rate;
+            self.wet[channel] = self.lines[channel].read(T::from_f64(delay));
+            output[channel] = input[channel] * (T::one() - self.mix) + self.wet[channel] * self.mix;
+        }
+        self.lfo.advance(self.sample_rate);
+        output.into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Stereo phaser with up to 24 first order allpass stages.
+/// The allpass break frequency sweeps exponentially between 100 Hz and 8 kHz.
+/// Setting: (LFO rate in Hz, depth in 0...1, feedback in -1...1, mix in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+#[derive(Clone)]
+pub struct Phaser<T: Real> {
+    /// Allpass states.
+    state: [[T; MAX_PHASER_STAGES]; 2],
+    stages: usize,
+    lfo: StereoLfo,
+    depth: T,
+    feedback: T,
+    mix: T,
+    wet: [T; 2],
+    sample_rate: f64,
+}
+
+impl<T: Real> Phaser<T> {
+    /// Create new phaser with `stages` allpass stages (1 to 24), LFO `rate` in Hz,
+    /// `depth` in 0...1, `feedback` in -1...1 and wet `mix` in 0...1.
+    pub fn new(sample_rate: f64, stages: usize, rate: T, depth: T, feedback: T, mix: T) -> Self {
+        let mut node = Self {
+            state: [[T::zero(); MAX_PHASER_STAGES]; 2],
+            stages: 1,
+            lfo: StereoLfo::new(0.0),
+            depth: T::zero(),
+            feedback: T::zero(),
+            mix: T::zero(),
+            wet: [T::zero(); 2],
+            sample_rate,
+        };
+        node.set_stages(stages);
+        node.set((rate, depth, feedback, mix));
+        node
+    }
+
+    /// Set number of allpass stages (1 to 24). Each pair of stages adds a notch.
+    pub fn set_stages(&mut self, stages: usize) {
+        self.stages = stages.clamp(1, MAX_PHASER_STAGES);
+    }
+
+    /// Set LFO rate in Hz.
+    pub fn set_rate(&mut self, rate: T) {
+        self.lfo.rate = lfo_rate(rate.to_f64());
+    }
+
+    /// Set LFO rate to one cycle per `beats` beats at tempo `bpm`.
+    pub fn set_tempo(&mut self, bpm: f64, beats: f64) {
+        self.lfo.rate = lfo_rate(bpm_hz(bpm) / beats);
+    }
+
+    /// Set sweep depth in 0...1.
+    pub fn set_depth(&mut self, depth: T) {
+        self.depth = clamp01(depth);
+    }
+
+    /// Set feedback amount in -1...1. The magnitude is limited to 0.95.
+    pub fn set_feedback(&mut self, feedback: T) {
+        self.feedback = clamp(
+            T::from_f64(-MAX_FEEDBACK),
+            T::from_f64(MAX_FEEDBACK),
+            feedback,
+        );
+    }
+
+    /// Set wet mix in 0...1. A mix of 0.5 produces the deepest notches.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+
+    /// Set LFO phase offset of the right channel in cycles. The default is 0.25.
+    pub fn set_stereo_phase(&mut self, offset: f64) {
+        self.lfo.offset = offset;
+    }
+}
+
+impl<T: Real> AudioNode for Phaser<T> {
+    const ID: u64 = 107;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T, T);
+
+    fn set(&mut self, (rate, depth, feedback, mix): Self::Setting) {
+        self.set_rate(rate);
+        self.set_depth(depth);
+        self.set_feedback(feedback);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        self.state = [[T::zero(); MAX_PHASER_STAGES]; 2];
+        self.lfo.phase = 0.0;
+        self.wet = [T::zero(); 2];
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let depth = self.depth.to_f64();
+        let nyquist = 0.45 * self.sample_rate;
+        let mut output = [T::zero(); 2];
+        for channel in 0..2 {
+            let lfo = self.lfo.value(channel);
+            let sweep = 0.5 + 0.5 * depth * lfo;
+            let f = min(
+                xerp(PHASER_MIN_FREQUENCY, PHASER_MAX_FREQUENCY, sweep),
+                nyquist,
+            );
+            let t = tan(PI * f / self.sample_rate);
+            let a = T::from_f64((t - 1.0) / (t + 1.0));
+            let mut x = input[channel] + self.wet[channel] * self.feedback;
+            for z in self.state[channel][..self.stages].iter_mut() {
+                let y = a * x + *z;
+                *z = x - a * y;
+                x = y;
+            }
+            self.wet[channel] = x;
+            output[channel] = input[channel] * (T::one() - self.mix) + x * self.mix;
+        }
+        self.lfo.advance(self.sample_rate);
+        output.into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Allpass coefficients of the in-phase path of the Hilbert transformer.
+const HILBERT_A: [f64; 4] = [0.6923878, 0.9360654322959, 0.9882295226860, 0.9987488452737];
+
+/// Allpass coefficients of the quadrature path of the Hilbert transformer.
+const HILBERT_B: [f64; 4] = [
+    0.4021921162426,
+    0.8561710882420,
+    0.9722909545651,
+    0.9952884791278,
+];
+
+/// Chain of second order allpass sections `y[n] = a^2 * (x[n] + y[n-2]) - x[n-2]`.
+#[derive(Clone, Default)]
+struct AllpassChain {
+    /// Squared coefficients.
+    a: [f64; 4],
+    /// Input and output histories of each section: x[n-1], x[n-2], y[n-1], y[n-2].
+    state: [[f64; 4]; 4],
+}
+
+impl AllpassChain {
+    fn new(coefficients: &[f64; 4]) -> Self {
+        Self {
+            a: coefficients.map(|a| a * a),
+            state: [[0.0; 4]; 4],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = [[0.0; 4]; 4];
+    }
+
+    #[inline]
+    fn filter(&mut self, x: f64) -> f64 {
+        let mut x = x;
+        for (a, state) in self.a.iter().zip(self.state.iter_mut()) {
+            let y = a * (x + state[3]) - state[1];
+            *state = [x, state[0], y, state[2]];
+            x = y;
+        }
+        x
+    }
+
+    /// Frequency response at `frequency` Hz.
+    fn response(&self, frequency: f64, sample_rate: f64) -> Complex64 {
+        let z2 = Complex64::from_polar(1.0, -2.0 * TAU * frequency / sample_rate);
+        self.a.iter().fold(Complex64::new(1.0, 0.0), |r, a| {
+            r * (a - z2) / (1.0 - a * z2)
+        })
+    }
+}
+
+/// Wideband Hilbert transformer made of two allpass chains with a phase difference of 90 degrees.
+/// The phase difference is within 1 degree of 90 degrees from 0.002 to 0.498 times the sample rate.
+#[derive(Clone)]
+struct HilbertPair {
+    a: AllpassChain,
+    b: AllpassChain,
+    /// One sample delay for the in-phase path.
+    delay: f64,
+}
+
+impl HilbertPair {
+    fn new() -> Self {
+        Self {
+            a: AllpassChain::new(&HILBERT_A),
+            b: AllpassChain::new(&HILBERT_B),
+            delay: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.delay = 0.0;
+    }
+
+    /// Process one sample, returning in-phase and quadrature components.
+    #[inline]
+    fn filter(&mut self, x: f64) -> (f64, f64) {
+        let i = self.delay;
+        self.delay = self.a.filter(x);
+        // The quadrature path leads by 90 degrees, so it is inverted.
+        (i, -self.b.filter(x))
+    }
+}
+
+/// Hilbert transformer that produces an analytic signal.
+/// The quadrature output lags the in-phase output by 90 degrees
+/// over most of the audio band. Both outputs have unity gain.
+/// - Input 0: input signal
+/// - Output 0: in-phase component
+/// - Output 1: quadrature component
+#[derive(Clone)]
+pub struct Hilbert<T: Real> {
+    pair: HilbertPair,
+    sample_rate: f64,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Real> Hilbert<T> {
+    /// Create new Hilbert transformer.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            pair: HilbertPair::new(),
+            sample_rate,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Real> AudioNode for Hilbert<T> {
+    const ID: u64 = 111;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U2;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.pair.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let (i, q) = self.pair.filter(input[0].to_f64());
+        [T::from_f64(i), T::from_f64(q)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        let delay = Complex64::from_polar(1.0, -TAU * frequency / self.sample_rate);
+        output[0] = input[0].filter(0.0, |r| {
+            r * delay * self.pair.a.response(frequency, self.sample_rate)
+        });
+        output[1] = input[0].filter(0.0, |r| {
+            -r * self.pair.b.response(frequency, self.sample_rate)
+        });
+        output
+    }
+}
+
+/// Single sideband frequency shifter. Shifts all frequencies of the input
+/// up and down by a fixed amount.
+/// Setting: shift in Hz.
+/// Number of inputs is `N`, either `U1` or `U2`.
+/// - Input 0: input signal
+/// - Input 1 (optional): shift in Hz
+/// - Output 0: signal shifted up
+/// - Output 1: signal shifted down
+#[derive(Clone)]
+pub struct FrequencyShifter<T: Real, N: Size<T>> {
+    pair: HilbertPair,
+    shift: f64,
+    phase: f64,
+    sample_rate: f64,
+    _marker: std::marker::PhantomData<(T, N)>,
+}
+
+impl<T: Real, N: Size<T>> FrequencyShifter<T, N> {
+    /// Create new frequency shifter with initial `shift` in Hz.
+    pub fn new(sample_rate: f64, shift: T) -> Self {
+        Self {
+            pair: HilbertPair::new(),
+            shift: shift.to_f64(),
+            phase: 0.0,
+            sample_rate,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Set frequency shift in Hz.
+    pub fn set_shift(&mut self, shift: T) {
+        self.shift = shift.to_f64();
+    }
+}
+
+impl<T: Real, N: Size<T>> AudioNode for FrequencyShifter<T, N> {
+    const ID: u64 = 112;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = U2;
+    type Setting = T;
+
+    fn set(&mut self, shift: Self::Setting) {
+        self.set_shift(shift);
+    }
+
+    fn reset(&mut self) {
+        self.pair.reset();
+        self.phase = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        if N::USIZE > 1 {
+            self.shift = input[1].to_f64();
+        }
+        let (i, q) = self.pair.filter(input[0].to_f64());
+        let (s, c) = (self.phase * TAU).sin_cos();
+        self.phase += self.shift / self.sample_rate;
+        self.phase -= floor(self.phase);
+        [T::from_f64(i * c - q * s), T::from_f64(i * c + q * s)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Ring modulator. Multiplies the input with a carrier signal,
+/// which is either a built-in sine wave or an external input.
+/// Setting: carrier frequency in Hz.
+/// Number of inputs is `N`, either `U1` or `U2`.
+/// - Input 0: input signal
+/// - Input 1 (optional): external carrier signal, which replaces the built-in carrier
+/// - Output 0: modulated signal
+#[derive(Clone)]
+pub struct RingMod<T: Real, N: Size<T>> {
+    frequency: f64,
+    phase: f64,
+    sample_rate: f64,
+    _marker: std::marker::PhantomData<(T, N)>,
+}
+
+impl<T: Real, N: Size<T>> RingMod<T, N> {
+    /// Create new ring modulator with built-in carrier `frequency` in Hz.
+    pub fn new(sample_rate: f64, frequency: T) -> Self {
+        Self {
+            frequency: frequency.to_f64(),
+            phase: 0.0,
+            sample_rate,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Set built-in carrier frequency in Hz.
+    pub fn set_frequency(&mut self, frequency: T) {
+        self.frequency = frequency.to_f64();
+    }
+}
+
+impl<T: Real, N: Size<T>> AudioNode for RingMod<T, N> {
+    const ID: u64 = 113;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = U1;
+    type Setting = T;
+
+    fn set(&mut self, frequency: Self::Setting) {
+        self.set_frequency(frequency);
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        if N::USIZE > 1 {
+            return [input[0] * input[1]].into();
+        }
+        let carrier = sin(self.phase * TAU);
+        self.phase += self.frequency / self.sample_rate;
+        self.phase -= floor(self.phase);
+        [input[0] * T::from_f64(carrier)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 1)
+    }
+}
@@ -449,3 +449,336 @@ impl<T: Float> AudioNode for Lorenz<T> {
         output
     }
 }
+
+/// PolyBLEP oscillator waveform.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum BlepWave {
+    /// Rising saw wave. Shape is ignored.
+    #[default]
+    Saw,
+    /// Pulse wave. Shape is the pulse width in 0...1. Square wave has width 0.5.
+    Pulse,
+    /// Triangle wave. Shape is the position of the peak in 0...1.
+    /// Symmetric triangle has shape 0.5. Shapes near 0 or 1 approach saw waves.
+    Triangle,
+    /// Morph between waveforms. Shape 0 is triangle, 0.5 is saw and 1 is square.
+    Morph,
+}
+
+/// Mixture of naive waveforms for the PolyBLEP oscillator.
+#[derive(Copy, Clone)]
+struct BlepMix<T: Real> {
+    saw: T,
+    pulse: T,
+    triangle: T,
+    width: T,
+    skew: T,
+}
+
+impl<T: Real> BlepMix<T> {
+    fn new(wave: BlepWave, shape: T) -> Self {
+        let half = T::from_f64(0.5);
+        let mut mix = Self {
+            saw: T::zero(),
+            pulse: T::zero(),
+            triangle: T::zero(),
+            width: half,
+            skew: half,
+        };
+        match wave {
+            BlepWave::Saw => mix.saw = T::one(),
+            BlepWave::Pulse => {
+                mix.pulse = T::one();
+                mix.width = clamp01(shape);
+            }
+            BlepWave::Triangle => {
+                mix.triangle = T::one();
+                mix.skew = clamp(T::from_f64(0.001), T::from_f64(0.999), shape);
+            }
+            BlepWave::Morph => {
+                let shape = clamp01(shape);
+                if shape < half {
+                    mix.saw = shape + shape;
+                    mix.triangle = T::one() - mix.saw;
+                } else {
+                    mix.pulse = shape + shape - T::one();
+                    mix.saw = T::one() - mix.pulse;
+                }
+            }
+        }
+        mix
+    }
+
+    /// Naive waveform value at phase `q`.
+    #[inline]
+    fn value(&self, q: T) -> T {
+        let x = q - q.floor();
+        let two = T::new(2);
+        let mut y = self.saw * (two * x - T::one());
+        if self.pulse != T::zero() {
+            y += if x < self.width {
+                self.pulse
+            } else {
+                -self.pulse
+            };
+        }
+        if self.triangle != T::zero() {
+            y += self.triangle
+                * if x < self.skew {
+                    two * x / self.skew - T::one()
+                } else {
+                    T::one() - two * (x - self.skew) / (T::one() - self.skew)
+                };
+        }
+        y
+    }
+
+    /// Naive waveform slope per cycle at phase `q`.
+    #[inline]
+    fn slope(&self, q: T) -> T {
+        let x = q - q.floor();
+        let two = T::new(2);
+        let triangle = if x < self.skew {
+            two / self.skew
+        } else {
+            -two / (T::one() - self.skew)
+        };
+        self.saw * two + self.triangle * triangle
+    }
+
+    /// Breakpoints as triples (phase, jump, slope change). Jumps and slope changes
+    /// are the differences between right and left limits.
+    #[inline]
+    fn breakpoints(&self) -> [(T, T, T); 3] {
+        let two = T::new(2);
+        let kink = self.triangle * (two / self.skew + two / (T::one() - self.skew));
+        [
+            (T::zero(), two * (self.pulse - self.saw), kink),
+            (self.width, -two * self.pulse, T::zero()),
+            (self.skew, T::zero(), -kink),
+        ]
+    }
+}
+
+/// Add PolyBLEP and PolyBLAMP residuals of a discontinuity at distance `a` in 0...1 samples
+/// before the current sample. `jump` is the step and `kink` is the slope change per sample.
+#[inline]
+fn blep_residual<T: Real>(a: T, jump: T, kink: T, before: &mut T, after: &mut T) {
+    let half = T::from_f64(0.5);
+    let sixth = T::from_f64(1.0 / 6.0);
+    let b = T::one() - a;
+    *before += jump * half * a * a + kink * sixth * a * a * a;
+    *after += -jump * half * b * b + kink * sixth * b * b * b;
+}
+
+/// Maximum number of periods per breakpoint corrected in one segment.
+/// Faster phase changes alias anyway and are not worth looping over.
+const MAX_BLEP_PERIODS: usize = 4;
+
+/// PolyBLEP oscillator with hard sync and phase modulation.
+/// Discontinuities in value and slope are corrected with polynomial band-limited steps and ramps.
+/// The output is delayed by one sample. Number of inputs is `N`, from 1 to 4.
+/// Setting: shape.
+/// - Input 0: frequency in Hz. Negative frequencies are supported for through-zero FM.
+/// - Input 1 (optional): shape (see `BlepWave`).
+/// - Input 2 (optional): sync. Phase is reset when sync crosses zero upwards.
+/// - Input 3 (optional): phase modulation in cycles.
+/// - Output 0: oscillator output
+#[derive(Clone)]
+pub struct Blep<T: Real, N: Size<T>> {
+    wave: BlepWave,
+    shape: T,
+    /// Phase without modulation in 0...1.
+    phase: T,
+    /// Previous modulated phase.
+    q: T,
+    /// Previous sync input.
+    sync: T,
+    /// Previous phase modulation input.
+    pm: T,
+    /// Next output sample.
+    pending: T,
+    sample_duration: T,
+    hash: u64,
+    initial_phase: Option<T>,
+    _marker: PhantomData<N>,
+}
+
+impl<T: Real, N: Size<T>> Blep<T, N> {
+    /// Create PolyBLEP oscillator with waveform `wave` and default `shape`.
+    pub fn new(sample_rate: f64, wave: BlepWave, shape: T) -> Self {
+        assert!(N::USIZE >= 1 && N::USIZE <= 4);
+        let mut node = Self {
+            wave,
+            shape,
+            phase: T::zero(),
+            q: T::zero(),
+            sync: T::zero(),
+            pm: T::zero(),
+            pending: T::zero(),
+            sample_duration: T::zero(),
+            hash: 0,
+            initial_phase: None,
+            _marker: PhantomData,
+        };
+        node.reset();
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Waveform.
+    pub fn wave(&self) -> BlepWave {
+        self.wave
+    }
+
+    /// Set waveform.
+    pub fn set_wave(&mut self, wave: BlepWave) {
+        self.wave = wave;
+    }
+
+    /// Set shape. Shape is used when there is no shape input.
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape;
+    }
+
+    /// Set initial phase in 0...1. If `None`, initial phase is pseudorandom.
+    pub fn set_initial_phase(&mut self, phase: Option<T>) {
+        self.initial_phase = phase;
+        self.reset();
+    }
+
+    /// Correct discontinuities while modulated phase moves from `q0` at `t0` samples
+    /// before the current sample to `q1` at `t1` samples before the current sample.
+    /// The phases should be near the unit interval for precision.
+    #[inline]
+    fn segment(mix: &BlepMix<T>, q0: T, q1: T, t0: T, t1: T, before: &mut T, after: &mut T) {
+        let dq = q1 - q0;
+        if dq == T::zero() || t0 <= t1 {
+            return;
+        }
+        let velocity = abs(dq) / (t0 - t1);
+        for (b, jump, kink) in mix.breakpoints() {
+            if jump == T::zero() && kink == T::zero() {
+                continue;
+            }
+            if dq > T::zero() {
+                let mut c = b + floor(q0 - b) + T::one();
+                let mut periods = 0;
+                while c <= q1 && periods < MAX_BLEP_PERIODS {
+                    let a = t0 + (c - q0) / dq * (t1 - t0);
+                    blep_residual(a, jump, kink * velocity, before, after);
+                    c += T::one();
+                    periods += 1;
+                }
+            } else {
+                let mut c = b + ceil(q0 - b) - T::one();
+                let mut periods = 0;
+                while c >= q1 && periods < MAX_BLEP_PERIODS {
+                    let a = t0 + (c - q0) / dq * (t1 - t0);
+                    blep_residual(a, -jump, kink * velocity, before, after);
+                    c -= T::one();
+                    periods += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<T: Real, N: Size<T>> AudioNode for Blep<T, N> {
+    const ID: u64 = 93;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = typenum::U1;
+    type Setting = T;
+
+    fn set(&mut self, setting: Self::Setting) {
+        self.set_shape(setting);
+    }
+
+    fn reset(&mut self) {
+        self.phase = match self.initial_phase {
+            Some(phase) => phase - phase.floor(),
+            None => T::from_f64(rnd(self.hash as i64)),
+        };
+        self.q = self.phase;
+        self.sync = T::zero();
+        self.pm = T::zero();
+        self.pending = BlepMix::new(self.wave, self.shape).value(self.phase);
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_duration = convert(1.0 / sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let dt = input[0] * self.sample_duration;
+        let shape = if N::USIZE > 1 { input[1] } else { self.shape };
+        let sync = if N::USIZE > 2 { input[2] } else { T::zero() };
+        let pm = if N::USIZE > 3 { input[3] } else { T::zero() };
+        let mix = BlepMix::new(self.wave, shape);
+        let mut before = T::zero();
+        let mut after = T::zero();
+        let q0 = self.q;
+        let mut q1;
+        if self.sync <= T::zero() && sync > T::zero() {
+            // Time from the sync event to the current sample.
+            let a = sync / (sync - self.sync);
+            let qc = q0 + (self.phase + dt + pm - q0) * (T::one() - a);
+            let v0 = if a < T::one() {
+                (qc - q0) / (T::one() - a)
+            } else {
+                dt
+            };
+            // Remove whole cycles of phase modulation to keep the phases near the unit interval.
+            let k = qc.floor();
+            let qc = qc - k;
+            Self::segment(&mix, q0 - k, qc, T::one(), a, &mut before, &mut after);
+            self.phase = a * dt;
+            q1 = self.phase + pm;
+            let k = q1.floor();
+            q1 -= k;
+            let qr = pm + (self.pm - pm) * a - k;
+            let v1 = if a > T::zero() { (q1 - qr) / a } else { dt };
+            let jump = mix.value(qr) - mix.value(qc);
+            let kink = mix.slope(qr) * v1 - mix.slope(qc) * v0;
+            blep_residual(a, jump, kink, &mut before, &mut after);
+            Self::segment(&mix, qr, q1, a, T::zero(), &mut before, &mut after);
+        } else {
+            self.phase += dt;
+            q1 = self.phase + pm;
+            let k = q1.floor();
+            q1 -= k;
+            Self::segment(
+                &mix,
+                q0 - k,
+                q1,
+                T::one(),
+                T::zero(),
+                &mut before,
+                &mut after,
+            );
+        }
+        self.phase -= self.phase.floor();
+        self.q = self.phase + pm;
+        self.sync = sync;
+        self.pm = pm;
+        let output = self.pending + before;
+        self.pending = mix.value(q1) + after;
+        [output].into()
+    }
+
+    fn set_hash(&mut self, hash: u64) {
+        self.hash = hash;
+        self.reset();
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output
+    }
+}
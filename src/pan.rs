@@ -9,7 +9,7 @@ use std::marker::PhantomData;
 
 /// Return equal power pan weights for pan value in -1...1.
 #[inline]
-fn pan_weights<T: Real>(value: T) -> (T, T) {
+pub(crate) fn pan_weights<T: Real>(value: T) -> (T, T) {
     let angle = (clamp11(value) + T::one()) * T::from_f64(PI * 0.25);
     (cos(angle), sin(angle))
 }
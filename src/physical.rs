@@ -0,0 +1,453 @@
+//! Physical models: digital waveguide string and modal resonator bank.
+
+use super::audionode::*;
+use super::combinator::*;
+use super::filter::*;
+use super::fir::*;
+use super::math::*;
+use super::signal::*;
+use super::*;
+use num_complex::Complex64;
+use numeric_array::typenum::*;
+use std::marker::PhantomData;
+
+/// Number of allpass stages in the waveguide dispersion filter.
+const DISPERSION_STAGES: usize = 4;
+
+/// Minimum waveguide frequency in Hz. Limits the length of the delay lines.
+const MIN_WAVEGUIDE_FREQUENCY: f64 = 1.0;
+
+/// Bow friction curve. Returns the reflection coefficient for
+/// velocity difference `dv` between bow and string. `slope` controls bow pressure.
+#[inline]
+fn bow_table<T: Float>(dv: T, slope: T) -> T {
+    let x = abs(dv * slope) + T::from_f64(0.75);
+    let x = x * x;
+    min(T::one(), T::one() / (x * x))
+}
+
+/// Bidirectional digital waveguide string with fixed ends.
+/// The string is excited at an adjustable position and listened to at another.
+/// The number of inputs `N` is 1 or 2.
+/// - Input 0: excitation (for example, a pluck or a strike impulse)
+/// - Input 1 (optional): bow velocity. Bowing is off when the velocity is zero.
+/// - Output 0: string displacement at the pickup position
+#[derive(Clone)]
+pub struct Waveguide<T: Float, N: Size<T>> {
+    /// Right-going rail from nut to bridge.
+    upper: Vec<T>,
+    /// Left-going rail from bridge to nut.
+    lower: Vec<T>,
+    /// Write index of both rails.
+    pos: usize,
+    damping: An<Fir<T, U3>>,
+    dispersion: Vec<An<Allpole<T, T, U1>>>,
+    tuning: An<Allpole<T, T, U1>>,
+    frequency: T,
+    gain_per_second: T,
+    /// Reflection gain at the bridge.
+    gain: T,
+    /// Excitation and bow position in 0...1 from the nut.
+    position: T,
+    /// Pickup position in 0...1 from the nut.
+    pickup: T,
+    /// Dispersion (stiffness) amount in 0...1.
+    stiffness: T,
+    /// Bow pressure in 0...1.
+    pressure: T,
+    sample_rate: f64,
+    _marker: PhantomData<N>,
+}
+
+impl<T: Float, N: Size<T>> Waveguide<T, N> {
+    /// Create new waveguide string tuned to `frequency` Hz (minimum 1 Hz).
+    /// `gain_per_second` is the amplitude remaining after one second of free vibration.
+    /// High frequency damping is in 0...1.
+    pub fn new(
+        sample_rate: f64,
+        frequency: T,
+        gain_per_second: T,
+        high_frequency_damping: T,
+    ) -> Self {
+        assert!(N::USIZE >= 1 && N::USIZE <= 2);
+        let mut node = Self {
+            upper: Vec::new(),
+            lower: Vec::new(),
+            pos: 0,
+            damping: An(super::prelude::fir3(T::one() - high_frequency_damping)),
+            dispersion: Vec::new(),
+            tuning: An(Allpole::new(T::one())),
+            frequency,
+            gain_per_second,
+            gain: T::one(),
+            position: T::from_f64(0.2),
+            pickup: T::from_f64(0.1),
+            stiffness: T::zero(),
+            pressure: T::from_f64(0.5),
+            sample_rate,
+            _marker: PhantomData,
+        };
+        node.initialize();
+        node
+    }
+
+    /// Set excitation and bow position in 0...1 from the nut. The default is 0.2.
+    /// Partials that have a node at the position are not excited.
+    pub fn set_position(&mut self, position: T) {
+        self.position = clamp01(position);
+    }
+
+    /// Set pickup position in 0...1 from the nut. The default is 0.1.
+    pub fn set_pickup(&mut self, pickup: T) {
+        self.pickup = clamp01(pickup);
+    }
+
+    /// Set bow pressure in 0...1. The default is 0.5.
+    pub fn set_pressure(&mut self, pressure: T) {
+        self.pressure = clamp01(pressure);
+    }
+
+    /// Set dispersion (stiffness) amount in 0...1. The default is 0.
+    /// Dispersion makes higher partials progressively sharper, as in stiff strings.
+    /// Resets the string.
+    /// - Allocates: the string delay lines and dispersion filters.
+    pub fn set_dispersion(&mut self, dispersion: T) {
+        self.stiffness = clamp01(dispersion);
+        self.initialize();
+    }
+
+    /// Set frequency in Hz. The minimum frequency is 1 Hz. Resets the string.
+    /// - Allocates: the string delay lines and dispersion filters.
+    pub fn set_frequency(&mut self, frequency: T) {
+        self.frequency = frequency;
+        self.initialize();
+    }
+
+    /// Tune the string and clear it.
+    fn initialize(&mut self) {
+        let frequency = max(self.frequency.to_f64(), MIN_WAVEGUIDE_FREQUENCY);
+        self.gain = T::from_f64(pow(self.gain_per_second.to_f64(), 1.0 / frequency));
+        // Dispersion filter stages have a DC delay of up to 16 samples,
+        // limited to half the period in total.
+        let max_delay = clamp(
+            1.0,
+            16.0,
+            0.5 * self.sample_rate / frequency / DISPERSION_STAGES as f64,
+        );
+        let stage_delay = 1.0 + (max_delay - 1.0) * self.stiffness.to_f64();
+        let eta = (1.0 - stage_delay) / (1.0 + stage_delay);
+        let omega = TAU * frequency / self.sample_rate;
+        let z = Complex64::from_polar(1.0, -omega);
+        let phase = -((eta + z) / (1.0 + eta * z)).arg();
+        let dispersion_delay = if self.stiffness > T::zero() {
+            DISPERSION_STAGES as f64 * phase / omega
+        } else {
+            0.0
+        };
+        self.dispersion.clear();
+        if self.stiffness > T::zero() {
+            for _ in 0..DISPERSION_STAGES {
+                let mut stage = An(Allpole::new(T::from_f64(stage_delay)));
+                stage.set_sample_rate(self.sample_rate);
+                self.dispersion.push(stage);
+            }
+        }
+        // Allpass tuning filter delay is in epsilon ... epsilon + 2.
+        let epsilon = 0.2;
+        // Damping filter delay is 1 sample.
+        let total_delay = self.sample_rate / frequency - 1.0 - dispersion_delay;
+        let rail = max(1.0, floor((total_delay - epsilon) * 0.5));
+        self.tuning.set_sample_rate(self.sample_rate);
+        self.tuning
+            .set_delay(T::from_f64(max(epsilon, total_delay - 2.0 * rail)));
+        self.upper.clear();
+        self.lower.clear();
+        self.upper.resize(rail as usize, T::zero());
+        self.lower.resize(rail as usize, T::zero());
+        self.reset();
+    }
+
+    /// Index of rail samples at `position` in 0...1 from the nut.
+    #[inline]
+    fn indices(&self, position: T) -> (usize, usize) {
+        let m = self.upper.len();
+        let k = min(m - 1, (position * T::from_f64(m as f64)).to_f64() as usize);
+        // The upper rail sample at distance `k` from the nut entered `k` samples ago.
+        // The lower rail sample at distance `k` from the nut entered `m - 1 - k` samples ago.
+        ((self.pos + m - k) % m, (self.pos + 1 + k) % m)
+    }
+}
+
+impl<T: Float, N: Size<T>> AudioNode for Waveguide<T, N> {
+    const ID: u64 = 98;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.upper.fill(T::zero());
+        self.lower.fill(T::zero());
+        self.pos = 0;
+        self.damping.reset();
+        self.tuning.reset();
+        for stage in self.dispersion.iter_mut() {
+            stage.reset();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            self.damping.set_sample_rate(sample_rate);
+            self.initialize();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let m = self.upper.len();
+        // Waves arriving at the bridge and the nut.
+        let bridge = self.upper[self.pos];
+        let nut = self.lower[self.pos];
+        let mut reflection = self.tuning.filter_mono(bridge);
+        for stage in self.dispersion.iter_mut() {
+            reflection = stage.filter_mono(reflection);
+        }
+        reflection = self.damping.filter_mono(reflection);
+        self.upper[self.pos] = -nut;
+        self.lower[self.pos] = -reflection * self.gain;
+
+        let (i, j) = self.indices(self.position);
+        let mut excitation = input[0];
+        if N::USIZE > 1 && input[1] != T::zero() {
+            // Bow friction: the bow injects the velocity difference scaled by the friction curve.
+            let dv = input[1] - (self.upper[i] + self.lower[j]);
+            let slope = T::from_f64(5.0) - T::from_f64(4.0) * self.pressure;
+            excitation += dv * bow_table(dv, slope);
+        }
+        let half = T::from_f64(0.5);
+        self.upper[i] += excitation * half;
+        self.lower[j] += excitation * half;
+
+        let (i, j) = self.indices(self.pickup);
+        let output = self.upper[i] + self.lower[j];
+        self.pos += 1;
+        if self.pos == m {
+            self.pos = 0;
+        }
+        [output].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 1)
+    }
+}
+
+/// Vibrational mode of a modal resonator.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mode {
+    /// Frequency ratio to the fundamental.
+    pub ratio: f64,
+    /// Impulse response amplitude.
+    pub amplitude: f64,
+    /// Decay time (-60 dB) in seconds.
+    pub decay: f64,
+}
+
+impl Mode {
+    /// Create new mode with frequency `ratio` to the fundamental, `amplitude` and -60 dB `decay` time in seconds.
+    pub fn new(ratio: f64, amplitude: f64, decay: f64) -> Self {
+        Self {
+            ratio,
+            amplitude,
+            decay,
+        }
+    }
+}
+
+/// Preset bodies for modal resonators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModalBody {
+    /// Free-free uniform bar, as in a marimba or a glockenspiel.
+    #[default]
+    Bar,
+    /// Church bell.
+    Bell,
+    /// Simply supported square plate.
+    Plate,
+    /// Ideal circular membrane, as in a drum head.
+    Membrane,
+}
+
+impl ModalBody {
+    /// Modes of the body. `decay` is the -60 dB decay time of the fundamental in seconds.
+    /// Higher modes decay faster.
+    pub fn modes(&self, decay: f64) -> Vec<Mode> {
+        // Mode frequency ratios and the exponent of decay time falloff with ratio.
+        let (ratios, falloff): (&[f64], f64) = match self {
+            ModalBody::Bar => (&[1.0, 2.756, 5.404, 8.933, 13.344, 18.64], 1.0),
+            ModalBody::Bell => (
+                &[
+                    0.5, 1.0, 1.183, 1.506, 2.0, 2.514, 2.662, 3.011, 4.166, 5.433, 6.796, 8.215,
+                ],
+                0.5,
+            ),
+            ModalBody::Plate => (
+                &[
+                    1.0, 2.5, 4.0, 5.0, 6.5, 8.5, 9.0, 10.0, 12.5, 13.0, 16.0, 17.0,
+                ],
+                0.5,
+            ),
+            ModalBody::Membrane => (
+                &[
+                    1.0, 1.594, 2.136, 2.296, 2.653, 2.918, 3.156, 3.501, 3.6, 3.652, 4.06, 4.154,
+                ],
+                1.0,
+            ),
+        };
+        // Amplitudes fall off with ratio and sum to one, which bounds the strike transient.
+        let total: f64 = ratios.iter().map(|&ratio| 1.0 / sqrt(ratio)).sum();
+        ratios
+            .iter()
+            .map(|&ratio| {
+                Mode::new(
+                    ratio,
+                    1.0 / (sqrt(ratio) * total),
+                    decay / pow(ratio, falloff),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Modal resonator bank: a set of tuned, decaying resonators, one per mode.
+/// The number of inputs `N` is 1 or 2.
+/// - Input 0: excitation
+/// - Input 1 (optional): fundamental frequency in Hz
+/// - Output 0: sum of modes
+#[derive(Clone)]
+pub struct Modal<T: Float, N: Size<T>> {
+    modes: Vec<Mode>,
+    filters: Vec<Resonator<T, f64, U1>>,
+    /// Input gain of each resonator.
+    gains: Vec<T>,
+    frequency: T,
+    sample_rate: f64,
+    _marker: PhantomData<N>,
+}
+
+impl<T: Float, N: Size<T>> Modal<T, N> {
+    /// Create new modal resonator bank with fundamental `frequency` in Hz and `modes`.
+    pub fn new(sample_rate: f64, frequency: T, modes: &[Mode]) -> Self {
+        assert!(N::USIZE >= 1 && N::USIZE <= 2);
+        let mut node = Self {
+            modes: Vec::new(),
+            filters: Vec::new(),
+            gains: Vec::new(),
+            frequency,
+            sample_rate,
+            _marker: PhantomData,
+        };
+        node.set_modes(modes);
+        node
+    }
+
+    /// Modes.
+    pub fn modes(&self) -> &[Mode] {
+        &self.modes
+    }
+
+    /// Set modes.
+    /// - Allocates: resonators if the number of modes grows.
+    pub fn set_modes(&mut self, modes: &[Mode]) {
+        self.modes = modes.to_vec();
+        let mut resonator = Resonator::new(1.0, 1.0);
+        resonator.set_sample_rate(self.sample_rate);
+        self.filters.resize(modes.len(), resonator);
+        self.gains.resize(modes.len(), T::zero());
+        self.set_frequency(self.frequency);
+    }
+
+    /// Set fundamental frequency in Hz.
+    pub fn set_frequency(&mut self, frequency: T) {
+        self.frequency = frequency;
+        for ((mode, filter), gain) in self
+            .modes
+            .iter()
+            .zip(self.filters.iter_mut())
+            .zip(self.gains.iter_mut())
+        {
+            let f = frequency.to_f64() * mode.ratio;
+            if f > 0.0 && f < 0.5 * self.sample_rate && mode.decay > 0.0 {
+                // Bandwidth that decays by 60 dB in `mode.decay` seconds.
+                let bandwidth = log(1000.0) / (PI * mode.decay);
+                filter.set_center_bandwidth(f, bandwidth);
+                // The resonator impulse response has an amplitude of `sqrt(1 - r * r)`,
+                // where `r` is the pole radius. Scale it to the mode amplitude.
+                let r = exp(-log(1000.0) / (mode.decay * self.sample_rate));
+                *gain = T::from_f64(mode.amplitude / sqrt(1.0 - r * r));
+            } else {
+                *gain = T::zero();
+            }
+        }
+    }
+}
+
+impl<T: Float, N: Size<T>> AudioNode for Modal<T, N> {
+    const ID: u64 = 99;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = U1;
+    type Setting = T;
+
+    fn set(&mut self, frequency: Self::Setting) {
+        self.set_frequency(frequency);
+    }
+
+    fn reset(&mut self) {
+        for filter in self.filters.iter_mut() {
+            filter.reset();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        for filter in self.filters.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+        self.set_frequency(self.frequency);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        if N::USIZE > 1 && input[1] != self.frequency {
+            self.set_frequency(input[1]);
+        }
+        let mut output = T::zero();
+        for (filter, gain) in self.filters.iter_mut().zip(self.gains.iter()) {
+            output += filter.filter_mono(input[0] * *gain);
+        }
+        [output].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, frequency: f64) -> SignalFrame {
+        let mut probe = new_signal_frame(1);
+        probe[0] = Signal::Response(Complex64::new(1.0, 0.0), 0.0);
+        let mut response = Complex64::new(0.0, 0.0);
+        for (filter, gain) in self.filters.iter_mut().zip(self.gains.iter()) {
+            if let Signal::Response(r, _) = filter.route(&probe, frequency)[0] {
+                response += r * gain.to_f64();
+            }
+        }
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = input[0].filter(0.0, |r| r * response);
+        output
+    }
+}
@@ -1,5 +1,6 @@
 //! Generic prelude.
 
+pub use super::additive::*;
 pub use super::audionode::*;
 pub use super::audiounit::*;
 pub use super::buffer::*;
@@ -10,21 +11,26 @@ pub use super::envelope::*;
 pub use super::feedback::*;
 pub use super::filter::*;
 pub use super::fir::*;
+pub use super::fm::*;
 pub use super::follow::*;
 pub use super::gen::*;
 pub use super::granular::*;
+pub use super::loudness::*;
 pub use super::math::*;
+pub use super::modulation::*;
 pub use super::moog::*;
 pub use super::net::*;
 pub use super::noise::*;
 pub use super::oscillator::*;
 pub use super::oversample::*;
 pub use super::pan::*;
+pub use super::physical::*;
 pub use super::realnet::*;
 pub use super::realseq::*;
 pub use super::resample::*;
 pub use super::resynth::*;
 pub use super::rez::*;
+pub use super::saturation::*;
 pub use super::sequencer::*;
 pub use super::setting::*;
 pub use super::shape::*;
@@ -34,6 +40,7 @@ pub use super::slot::*;
 pub use super::snoop::*;
 pub use super::svf::*;
 pub use super::system::*;
+pub use super::vocoder::*;
 pub use super::wave::*;
 pub use super::wavetable::*;
 pub use super::*;
@@ -1023,6 +1030,76 @@ where
     An(TapLinear::new(min_delay, max_delay))
 }
 
+/// Variable delay line with selectable fractional delay `interpolation` (see `Interpolation`).
+/// Minimum and maximum delay times are in seconds.
+/// Modulation-safe mode that smooths the delay time is enabled with the `set_smoothing` method.
+/// Setting: interpolation method.
+/// - Allocates: the delay line.
+/// - Input 0: signal.
+/// - Input 1: delay time in seconds.
+/// - Output 0: delayed signal.
+///
+/// ### Example: Modulated Delay With Lagrange Interpolation
+/// ```
+/// use fundsp::prelude::*;
+/// let mut line = variable_delay::<f32>(0.001, 0.01, Interpolation::Lagrange(5));
+/// line.set_smoothing(0.005);
+/// pass::<f32>() & (pass() | lfo(|t| lerp11(0.001, 0.01, sin_hz(0.2, t)))) >> line;
+/// ```
+pub fn variable_delay<T: Real>(
+    min_delay: f64,
+    max_delay: f64,
+    interpolation: Interpolation,
+) -> An<VariableDelay<T>> {
+    An(VariableDelay::new(min_delay, max_delay, interpolation))
+}
+
+/// Image-source early reflections in shoebox `room` from `source` to `listener`
+/// (positions in meters) up to reflection `order`, rendered as a multi-tap delay
+/// with per-tap gain, lowpass filtering and panning. The direct path is not included.
+/// Setting: (source position, listener position).
+/// - Allocates: the delay line.
+/// - Input 0: source signal
+/// - Output 0: left reflections
+/// - Output 1: right reflections
+///
+/// ### Example: Reflections In A 6 x 8 x 3 Meter Room
+/// ```
+/// use fundsp::prelude::*;
+/// let room = Room::new([6.0, 8.0, 3.0], 0.3);
+/// let mut node = reflections::<f32>(&room, [2.0, 6.0, 1.5], [3.0, 2.0, 1.7], 3);
+/// node.set_source([4.0, 6.0, 1.5]);
+/// ```
+pub fn reflections<T: Real>(
+    room: &Room,
+    source: [f64; 3],
+    listener: [f64; 3],
+    order: usize,
+) -> An<Reflections<T>> {
+    An(Reflections::new(DEFAULT_SR, room, source, listener, order))
+}
+
+/// Tape delay with maximum delay time `max_time` seconds, delay time `time` seconds,
+/// `feedback` amount in 0...1.5 (above 1 self-oscillates into saturation) and wet `mix` in 0...1.
+/// Changes in delay time glide smoothly, bending pitch like real tape.
+/// Wow, flutter, saturation drive, feedback filters, glide time, tempo sync and
+/// playback heads at 1, 2, 3 and 4 times the delay time are available as methods.
+/// Setting: (delay time in seconds, feedback amount, mix).
+/// - Allocates: the delay line.
+/// - Input 0: input signal
+/// - Output 0: delayed signal mixed with input
+///
+/// ### Example: Dotted Eighth Tape Echo At 120 BPM
+/// ```
+/// use fundsp::prelude::*;
+/// let mut echo = tape_delay::<f32>(1.0, 0.375, 0.5, 0.4);
+/// echo.set_tempo(120.0, 0.75);
+/// echo.set_wow_flutter(0.5, 0.3);
+/// ```
+pub fn tape_delay<T: Real>(max_time: f64, time: T, feedback: T, mix: T) -> An<TapeDelay<T>> {
+    An(TapeDelay::new(DEFAULT_SR, max_time, time, feedback, mix))
+}
+
 /// 2x oversample enclosed `node`.
 /// - Inputs and outputs: from `node`.
 ///
@@ -1241,6 +1318,21 @@ pub fn shape<T: Real>(mode: Shape<T>) -> An<Shaper<T>> {
     An(Shaper::new(mode))
 }
 
+/// Shape signal with antiderivative antialiasing (ADAA) of order `antialias`.
+/// Modes that do not support the requested order use the highest order they support.
+/// First order ADAA adds half a sample of latency and second order ADAA adds one sample.
+/// - Input 0: input signal
+/// - Output 0: shaped signal
+///
+/// ### Example: Antialiased Hard Clipping
+/// ```
+/// use fundsp::prelude::*;
+/// shape_adaa::<f64>(Shape::Clip, Antialias::Second);
+/// ```
+pub fn shape_adaa<T: Real>(mode: Shape<T>, antialias: Antialias) -> An<Shaper<T>> {
+    An(Shaper::with_antialias(mode, antialias))
+}
+
 /// Clip signal to -1...1.
 /// - Input 0: input signal
 /// - Output 0: clipped signal
@@ -1298,8 +1390,50 @@ pub fn follow<T: Float, F: Real, S: ScalarOrPair<Sample = F>>(t: S) -> An<AFollo
     An(AFollow::new(DEFAULT_SR, t))
 }
 
+/// Exponential envelope follower with `(attack, release)` halfway response times in seconds.
+/// Detects peak amplitude and smooths it in the log domain (dB-linear).
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+///
+/// ### Example (Envelope Of Noise)
+/// ```
+/// use fundsp::prelude::*;
+/// noise::<f64>() >> efollow::<f64, f64, _>((0.001, 0.1));
+/// ```
+pub fn efollow<T: Float, F: Real, S: ScalarOrPair<Sample = F>>(time: S) -> An<EFollow<T, F>> {
+    An(EFollow::new(DEFAULT_SR, time))
+}
+
+/// Exponential envelope follower with `(attack, release)` halfway response times in seconds.
+/// Detects RMS level with smoothing `timescale` in seconds and smooths it in the log domain (dB-linear).
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+pub fn efollow_rms<T: Float, F: Real, S: ScalarOrPair<Sample = F>>(
+    timescale: f64,
+    time: S,
+) -> An<EFollow<T, F>> {
+    let mut node = EFollow::new(DEFAULT_SR, time);
+    node.set_detector(Detector::Rms(timescale));
+    An(node)
+}
+
+/// Exponential peak hold envelope follower with `(attack, release)` halfway response times in seconds.
+/// After a new peak, release is delayed by `hold` seconds.
+/// - Input 0: input signal
+/// - Output 0: envelope (amplitude)
+pub fn efollow_hold<T: Float, F: Real, S: ScalarOrPair<Sample = F>>(
+    hold: F,
+    time: S,
+) -> An<EFollow<T, F>> {
+    let mut node = EFollow::new(DEFAULT_SR, time);
+    node.set_hold(hold);
+    An(node)
+}
+
 /// Look-ahead limiter with `(attack, release)` times in seconds.
 /// Look-ahead is equal to the attack time.
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
 /// - Allocates: look-ahead buffers.
 /// - Input 0: signal
 /// - Output 0: signal limited to -1...1
@@ -1309,6 +1443,8 @@ pub fn limiter<T: Real, S: ScalarOrPair<Sample = T>>(time: S) -> An<Limiter<T, U
 
 /// Stereo look-ahead limiter with `(attack, release)` times in seconds.
 /// Look-ahead is equal to the attack time.
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
 /// - Allocates: look-ahead buffers.
 /// - Input 0: left signal
 /// - Input 1: right signal
@@ -1318,6 +1454,292 @@ pub fn limiter_stereo<T: Real, S: ScalarOrPair<Sample = T>>(time: S) -> An<Limit
     An(Limiter::new(DEFAULT_SR, time))
 }
 
+/// Multichannel look-ahead limiter with `(attack, release)` times in seconds.
+/// Look-ahead is equal to the attack time. Channels are linked by default (see `set_link`).
+/// Release slows down after sustained limiting.
+/// Use `set_true_peak` to enable true peak detection and `set_ceiling` to set the ceiling.
+/// - Allocates: look-ahead buffers.
+/// - Input(s): signal
+/// - Output(s): signal limited to -1...1
+///
+/// ### Example: 4-Channel True Peak Limiter With -1 dBTP Ceiling
+/// ```
+/// use fundsp::prelude::*;
+/// let mut limiter = multilimiter::<U4, f64, _>((0.005, 0.1));
+/// limiter.set_true_peak(true);
+/// limiter.set_ceiling(-1.0);
+/// ```
+pub fn multilimiter<N: Size<T>, T: Real, S: ScalarOrPair<Sample = T>>(
+    time: S,
+) -> An<Limiter<T, N, S>> {
+    An(Limiter::new(DEFAULT_SR, time))
+}
+
+/// Compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// Attack and release times are halfway response times of the gain.
+/// - Input 0: signal
+/// - Output 0: compressed signal
+///
+/// ### Example: Gentle Vocal Compression
+/// ```
+/// use fundsp::prelude::*;
+/// compressor::<f64, _>(-20.0, 3.0, 6.0, (0.005, 0.1));
+/// ```
+pub fn compressor<T: Real, S: ScalarOrPair<Sample = T>>(
+    threshold: T,
+    ratio: T,
+    knee: T,
+    time: S,
+) -> An<Compressor<T, U1, U1>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Stereo linked compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: compressed left signal
+/// - Output 1: compressed right signal
+pub fn compressor_stereo<T: Real, S: ScalarOrPair<Sample = T>>(
+    threshold: T,
+    ratio: T,
+    knee: T,
+    time: S,
+) -> An<Compressor<T, U2, U2>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Sidechain compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// - Input 0: signal
+/// - Input 1: sidechain signal
+/// - Output 0: compressed signal
+pub fn compressor_sidechain<T: Real, S: ScalarOrPair<Sample = T>>(
+    threshold: T,
+    ratio: T,
+    knee: T,
+    time: S,
+) -> An<Compressor<T, U2, U1>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, true,
+    ))
+}
+
+/// Compressor without lookahead with `threshold` in dB, `ratio` (1 or greater),
+/// soft `knee` width in dB and `(attack, release)` times in seconds.
+/// Outputs gain reduction for metering.
+/// - Input 0: signal
+/// - Output 0: compressed signal
+/// - Output 1: gain reduction in dB (zero or negative)
+pub fn compressor_meter<T: Real, S: ScalarOrPair<Sample = T>>(
+    threshold: T,
+    ratio: T,
+    knee: T,
+    time: S,
+) -> An<Compressor<T, U1, U2>> {
+    An(Compressor::new(
+        DEFAULT_SR, threshold, ratio, knee, time, false,
+    ))
+}
+
+/// Noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: signal
+/// - Output 0: gated signal
+///
+/// ### Example: Drum Gate
+/// ```
+/// use fundsp::prelude::*;
+/// gate::<f64>(-40.0, -80.0, 0.0005, 0.05, 0.1);
+/// ```
+pub fn gate<T: Real>(
+    threshold: T,
+    range: T,
+    attack: T,
+    hold: T,
+    release: T,
+) -> An<Gate<T, U1, U1>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Stereo linked noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: gated left signal
+/// - Output 1: gated right signal
+pub fn gate_stereo<T: Real>(
+    threshold: T,
+    range: T,
+    attack: T,
+    hold: T,
+    release: T,
+) -> An<Gate<T, U2, U2>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Noise gate keyed from a sidechain with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// - Input 0: signal
+/// - Input 1: sidechain signal
+/// - Output 0: gated signal
+pub fn gate_sidechain<T: Real>(
+    threshold: T,
+    range: T,
+    attack: T,
+    hold: T,
+    release: T,
+) -> An<Gate<T, U2, U1>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, true,
+    ))
+}
+
+/// Noise gate with `threshold` in dB, `range` in dB (maximum attenuation, negative),
+/// `attack` and `release` halfway response times in seconds and `hold` time in seconds.
+/// Outputs gate state as a control signal.
+/// - Input 0: signal
+/// - Output 0: gated signal
+/// - Output 1: gate gain in 0...1
+pub fn gate_meter<T: Real>(
+    threshold: T,
+    range: T,
+    attack: T,
+    hold: T,
+    release: T,
+) -> An<Gate<T, U1, U2>> {
+    An(Gate::new(
+        DEFAULT_SR, threshold, range, attack, hold, release, false,
+    ))
+}
+
+/// Downward expander with `threshold` in dB, expansion `ratio` (1 or greater),
+/// `range` in dB (maximum attenuation, negative) and `(attack, release)` times in seconds.
+/// - Input 0: signal
+/// - Output 0: expanded signal
+pub fn expander<T: Real, S: ScalarOrPair<Sample = T>>(
+    threshold: T,
+    ratio: T,
+    range: T,
+    time: S,
+) -> An<Gate<T, U1, U1>> {
+    let (attack, release) = time.broadcast();
+    let mut node = Gate::new(
+        DEFAULT_SR,
+        threshold,
+        range,
+        attack,
+        T::zero(),
+        release,
+        false,
+    );
+    node.set_ratio(ratio);
+    An(node)
+}
+
+/// Adaptive normalizer (automatic gain control) without lookahead with `target` RMS level in dB,
+/// `max_gain` in dB, noise `floor` in dB and `(attack, release)` times in seconds.
+/// The gain is not adjusted while the input level is below the noise floor.
+/// - Input 0: signal
+/// - Output 0: normalized signal
+///
+/// ### Example: Speech Leveler
+/// ```
+/// use fundsp::prelude::*;
+/// normalizer::<f32, _>(-20.0, 24.0, -50.0, (0.5, 2.0));
+/// ```
+pub fn normalizer<T: Real, S: ScalarOrPair<Sample = T>>(
+    target: T,
+    max_gain: T,
+    floor: T,
+    time: S,
+) -> An<Normalizer<T, U1>> {
+    An(Normalizer::new(DEFAULT_SR, target, max_gain, floor, time))
+}
+
+/// Stereo adaptive normalizer (automatic gain control) without lookahead with `target` RMS level in dB,
+/// `max_gain` in dB, noise `floor` in dB and `(attack, release)` times in seconds.
+/// The gain is not adjusted while the input level is below the noise floor.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: normalized left signal
+/// - Output 1: normalized right signal
+pub fn normalizer_stereo<T: Real, S: ScalarOrPair<Sample = T>>(
+    target: T,
+    max_gain: T,
+    floor: T,
+    time: S,
+) -> An<Normalizer<T, U2>> {
+    An(Normalizer::new(DEFAULT_SR, target, max_gain, floor, time))
+}
+
+/// Transient shaper with `attack` and `sustain` gains in dB.
+/// Positive gains emphasize and negative gains suppress attacks or sustains.
+/// - Input 0: signal
+/// - Output 0: shaped signal
+///
+/// ### Example: Tighten Drums
+/// ```
+/// use fundsp::prelude::*;
+/// transient_shaper(6.0, -6.0);
+/// ```
+pub fn transient_shaper<T: Real>(attack: T, sustain: T) -> An<TransientShaper<T, U1>> {
+    An(TransientShaper::new(DEFAULT_SR, attack, sustain))
+}
+
+/// Stereo transient shaper with `attack` and `sustain` gains in dB.
+/// Positive gains emphasize and negative gains suppress attacks or sustains.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: shaped left signal
+/// - Output 1: shaped right signal
+pub fn transient_shaper_stereo<T: Real>(attack: T, sustain: T) -> An<TransientShaper<T, U2>> {
+    An(TransientShaper::new(DEFAULT_SR, attack, sustain))
+}
+
+/// Split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative). The band above the split frequency is compressed
+/// when its level exceeds the threshold.
+/// - Input 0: signal
+/// - Output 0: de-essed signal
+///
+/// ### Example: Dialogue De-Esser
+/// ```
+/// use fundsp::prelude::*;
+/// deesser(6000.0, -30.0, -12.0);
+/// ```
+pub fn deesser<T: Real>(frequency: T, threshold: T, range: T) -> An<DeEsser<T, U1, U1>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
+/// Stereo split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative). Channels are linked.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: de-essed left signal
+/// - Output 1: de-essed right signal
+pub fn deesser_stereo<T: Real>(frequency: T, threshold: T, range: T) -> An<DeEsser<T, U2, U2>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
+/// Split-band de-esser with split `frequency` in Hz, `threshold` in dB and `range` in dB
+/// (maximum attenuation, negative) and a sidechain output for listening.
+/// - Input 0: signal
+/// - Output 0: de-essed signal
+/// - Output 1: sidechain (high band) signal
+pub fn deesser_listen<T: Real>(frequency: T, threshold: T, range: T) -> An<DeEsser<T, U1, U2>> {
+    An(DeEsser::new(DEFAULT_SR, frequency, threshold, range))
+}
+
 /// Pinking filter.
 /// - Input 0: input signal
 /// - Output 0: filtered signal
@@ -1914,6 +2336,64 @@ pub fn reverb4_stereo_delays<T: Real>(
             * dc((T::from_f64(1.0 / 4.0), T::from_f64(1.0 / 4.0)))
 }
 
+/// Stereo reverb with pre-delay, early reflections and a modulated 16-channel FDN late tail.
+/// `room_size` is in meters (1 to 50 meters). An average room size is 10 meters.
+/// `time` is approximate reverberation time to -60 dB in seconds.
+/// Setting: (room size in meters, reverberation time in seconds, stereo width in 0...1).
+/// Pre-delay, low and high damping, modulation and early reflection level are available as methods.
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: reverberated left signal
+/// - Output 1: reverberated right signal
+///
+/// ### Example: Add 20% Reverb
+/// ```
+/// use fundsp::prelude::*;
+/// multipass() & 0.2 * hall_stereo::<f32>(20.0, 2.0);
+/// ```
+pub fn hall_stereo<T: Real>(room_size: f64, time: f64) -> An<super::reverb::Hall<T>> {
+    An(super::reverb::Hall::new(DEFAULT_SR, room_size, time))
+}
+
+/// Plate reverb (Dattorro style figure-of-eight tank) with `decay` in 0...1,
+/// high frequency `damping` in 0...1 and wet `mix` in 0...1.
+/// Setting: (decay, damping, mix).
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Plate Reverb With 30% Mix
+/// ```
+/// use fundsp::prelude::*;
+/// plate_stereo::<f32>(0.5, 0.1, 0.3);
+/// ```
+pub fn plate_stereo<T: Real>(decay: T, damping: T, mix: T) -> An<super::reverb::Plate<T>> {
+    An(super::reverb::Plate::new(DEFAULT_SR, decay, damping, mix))
+}
+
+/// Spring reverb with two dispersive springs with `decay` in 0...1,
+/// high frequency `damping` in 0...1 and wet `mix` in 0...1.
+/// Setting: (decay, damping, mix).
+/// - Allocates: delay lines
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Spring Reverb With 40% Mix
+/// ```
+/// use fundsp::prelude::*;
+/// spring_stereo::<f32>(0.7, 0.3, 0.4);
+/// ```
+pub fn spring_stereo<T: Real>(decay: T, damping: T, mix: T) -> An<super::reverb::SpringReverb<T>> {
+    An(super::reverb::SpringReverb::new(
+        DEFAULT_SR, decay, damping, mix,
+    ))
+}
+
 /// Saw-like discrete summation formula oscillator.
 /// - Input 0: frequency in Hz
 /// - Input 1: roughness in 0...1 is the attenuation of successive partials.
@@ -1969,6 +2449,99 @@ pub fn pluck<T: Float>(
     ))
 }
 
+/// Digital waveguide string tuned to `frequency` Hz.
+/// `gain_per_second` is the amplitude remaining after one second of free vibration.
+/// High frequency damping is in 0...1.
+/// Excitation position, pickup position and dispersion can be set with
+/// `set_position`, `set_pickup` and `set_dispersion`.
+/// - Allocates: string buffers.
+/// - Input 0: string excitation
+/// - Output 0: string displacement at the pickup
+///
+/// ### Example: Plucked String
+/// ```
+/// use fundsp::prelude::*;
+/// impulse::<U1, f64>() >> waveguide(220.0, 0.1, 0.5);
+/// ```
+pub fn waveguide<T: Float>(
+    frequency: T,
+    gain_per_second: T,
+    high_frequency_damping: T,
+) -> An<Waveguide<T, U1>> {
+    An(Waveguide::new(
+        DEFAULT_SR,
+        frequency,
+        gain_per_second,
+        high_frequency_damping,
+    ))
+}
+
+/// Bowed digital waveguide string tuned to `frequency` Hz.
+/// `gain_per_second` is the amplitude remaining after one second of free vibration.
+/// High frequency damping is in 0...1. Bow pressure can be set with `set_pressure`.
+/// - Allocates: string buffers.
+/// - Input 0: string excitation
+/// - Input 1: bow velocity (for example, 0.1)
+/// - Output 0: string displacement at the pickup
+///
+/// ### Example: Bowed String
+/// ```
+/// use fundsp::prelude::*;
+/// (zero() | constant(0.1)) >> waveguide_bow(220.0, 0.5, 0.2);
+/// ```
+pub fn waveguide_bow<T: Float>(
+    frequency: T,
+    gain_per_second: T,
+    high_frequency_damping: T,
+) -> An<Waveguide<T, U2>> {
+    An(Waveguide::new(
+        DEFAULT_SR,
+        frequency,
+        gain_per_second,
+        high_frequency_damping,
+    ))
+}
+
+/// Modal resonator bank with modes of `body` (see `ModalBody`).
+/// `decay` is the -60 dB decay time of the fundamental in seconds.
+/// - Input 0: excitation
+/// - Input 1: fundamental frequency in Hz
+/// - Output 0: resonator output
+///
+/// ### Example: Struck Bell
+/// ```
+/// use fundsp::prelude::*;
+/// (impulse::<U1, f64>() | constant(220.0)) >> modal(ModalBody::Bell, 4.0);
+/// ```
+pub fn modal<T: Float>(body: ModalBody, decay: T) -> An<Modal<T, U2>> {
+    An(Modal::new(
+        DEFAULT_SR,
+        T::new(440),
+        &body.modes(decay.to_f64()),
+    ))
+}
+
+/// Modal resonator bank with modes of `body` (see `ModalBody`) and fundamental frequency `f` Hz.
+/// `decay` is the -60 dB decay time of the fundamental in seconds.
+/// - Input 0: excitation
+/// - Output 0: resonator output
+pub fn modal_hz<T: Float>(body: ModalBody, f: T, decay: T) -> An<Modal<T, U1>> {
+    An(Modal::new(DEFAULT_SR, f, &body.modes(decay.to_f64())))
+}
+
+/// Modal resonator bank with fundamental frequency `f` Hz and custom `modes`.
+/// - Input 0: excitation
+/// - Output 0: resonator output
+///
+/// ### Example: Two Mode Wood Block
+/// ```
+/// use fundsp::prelude::*;
+/// impulse::<U1, f64>() >> modal_modes(800.0, &[Mode::new(1.0, 0.5, 0.1), Mode::new(2.7, 0.3, 0.05)]);
+/// ```
+pub fn modal_modes<T: Float>(f: T, modes: &[Mode]) -> An<Modal<T, U1>> {
+    An(Modal::new(DEFAULT_SR, f, modes))
+}
+
 /// Saw wavetable oscillator.
 /// - Allocates: global saw wavetable.
 /// - Input 0: frequency in Hz
@@ -2061,6 +2634,61 @@ pub fn hammond_hz<T: Float>(f: T) -> An<Pipe<T, Constant<U1, T>, WaveSynth<'stat
     constant(f) >> hammond()
 }
 
+/// Morphing wavetable oscillator. Interpolates between the frames of `table`.
+/// - Input 0: frequency in Hz
+/// - Input 1: morph position in 0...1
+/// - Output 0: oscillator output
+///
+/// ### Example: Morph From Saw To Square
+/// ```
+/// use fundsp::prelude::*;
+/// use std::sync::Arc;
+/// let saw: Vec<f32> = (0..256).map(|i| i as f32 / 128.0 - 1.0).collect();
+/// let square: Vec<f32> = (0..256).map(|i| if i < 128 { 1.0 } else { -1.0 }).collect();
+/// let table = Arc::new(MorphTable::from_waves(20.0, 20_000.0, 4.0, &[&saw, &square]));
+/// (constant(110.0) | lfo(|t| sin_hz(0.5, t) * 0.5 + 0.5)) >> morph_synth(&table);
+/// ```
+pub fn morph_synth<T: Float>(table: &Arc<MorphTable>) -> An<MorphSynth<T>> {
+    An(MorphSynth::new(DEFAULT_SR, table))
+}
+
+/// Supersaw oscillator with `voices` detuned saw waves (1 to 16) spread across the stereo field.
+/// Voices are detuned up to 0.2 semitones and gathered near the center.
+/// - Allocates: global saw wavetable.
+/// - Input 0: frequency in Hz
+/// - Output 0: left output
+/// - Output 1: right output
+///
+/// ### Example: Seven Voice Supersaw
+/// ```
+/// use fundsp::prelude::*;
+/// constant(220.0) >> supersaw(7);
+/// ```
+pub fn supersaw<T: Float>(voices: usize) -> An<Unison<T, U1>> {
+    let mut node = Unison::new(DEFAULT_SR, &SHARED_SAW_TABLE, voices, 0.2, 0.7);
+    node.set_curve(1.5);
+    An(node)
+}
+
+/// Unison oscillator with `voices` detuned copies (1 to 16) of wavetable `table`
+/// spread across the stereo field. Voices are spaced evenly in pitch (see `Unison::set_curve`).
+/// - Input 0: frequency in Hz
+/// - Input 1: detune of the outermost voices in semitones
+/// - Input 2: mix in 0...1 between center (0) and side (1) voices
+/// - Output 0: left output
+/// - Output 1: right output
+///
+/// ### Example: Detuned Square Pad
+/// ```
+/// use fundsp::prelude::*;
+/// use std::sync::Arc;
+/// let table = Arc::new(SQUARE_TABLE.clone());
+/// (constant(110.0) | lfo(|t| 0.1 + 0.1 * sin_hz(0.2, t)) | constant(0.5)) >> unison(&table, 5);
+/// ```
+pub fn unison<T: Float>(table: &Arc<Wavetable>, voices: usize) -> An<Unison<T, U3>> {
+    An(Unison::new(DEFAULT_SR, table, voices, 0.0, 0.5))
+}
+
 /// Lowpass filter.
 /// - Input 0: audio
 /// - Input 1: cutoff frequency (Hz)
@@ -2664,6 +3292,120 @@ pub fn pulse<T: Float>() -> An<PulseWave<T>> {
     An(PulseWave::new())
 }
 
+/// PolyBLEP saw oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: saw wave
+pub fn blep_saw<T: Real>() -> An<Blep<T, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Saw, T::from_f64(0.5)))
+}
+
+/// PolyBLEP square oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: square wave
+pub fn blep_square<T: Real>() -> An<Blep<T, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Pulse, T::from_f64(0.5)))
+}
+
+/// PolyBLEP pulse oscillator with audio rate pulse width.
+/// - Input 0: frequency in Hz
+/// - Input 1: pulse width in 0...1
+/// - Output 0: pulse wave
+pub fn blep_pulse<T: Real>() -> An<Blep<T, U2>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Pulse, T::from_f64(0.5)))
+}
+
+/// PolyBLEP triangle oscillator.
+/// - Input 0: frequency in Hz
+/// - Output 0: triangle wave
+pub fn blep_triangle<T: Real>() -> An<Blep<T, U1>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Triangle, T::from_f64(0.5)))
+}
+
+/// PolyBLEP oscillator that morphs from triangle (0) to saw (0.5) to square (1).
+/// - Input 0: frequency in Hz
+/// - Input 1: morph in 0...1
+/// - Output 0: morphed wave
+pub fn blep_morph<T: Real>() -> An<Blep<T, U2>> {
+    An(Blep::new(DEFAULT_SR, BlepWave::Morph, T::from_f64(0.5)))
+}
+
+/// PolyBLEP oscillator with waveform `wave`, hard sync and phase modulation.
+/// The sync input resets the phase when it crosses zero upwards.
+/// - Input 0: frequency in Hz
+/// - Input 1: shape (see `BlepWave`)
+/// - Input 2: sync
+/// - Input 3: phase modulation in cycles
+/// - Output 0: oscillator output
+///
+/// ### Example: Hard Synced Saw
+/// ```
+/// use fundsp::prelude::*;
+/// (dc((220.0, 0.5)) | sine_hz(110.0) | zero()) >> blep(BlepWave::Saw);
+/// ```
+pub fn blep<T: Real>(wave: BlepWave) -> An<Blep<T, U4>> {
+    An(Blep::new(DEFAULT_SR, wave, T::from_f64(0.5)))
+}
+
+/// FM operator synth playing `patch` (see `FmPatch`).
+/// A rising gate starts a note and a falling gate releases it.
+/// - Input 0: pitch in Hz
+/// - Input 1: gate
+/// - Output 0: synth output
+///
+/// ### Example: DX7 Algorithm 5
+/// ```
+/// use fundsp::prelude::*;
+/// let operator = FmOperator::new(1.0, 0.5, FmEnvelope::adsr(0.01, 1.0, 0.5, 0.2));
+/// let patch = FmPatch::dx7(vec![operator; 6], 5, 1.0);
+/// (constant(220.0) | lfo(|t| if t < 1.0 { 1.0 } else { 0.0 })) >> fm(&patch);
+/// ```
+pub fn fm<T: Real>(patch: &FmPatch) -> An<Fm<T>> {
+    An(Fm::new(DEFAULT_SR, patch))
+}
+
+/// Additive oscillator bank with partials read from the atomic partial table `partials`,
+/// which can be modified on the fly. Partial parameters are updated every 64 samples.
+/// - Input 0: fundamental frequency in Hz
+/// - Output 0: sum of partials
+///
+/// ### Example: Organ Drawbars
+/// ```
+/// use fundsp::prelude::*;
+/// use std::sync::Arc;
+/// let partials = Arc::new(AtomicPartials::harmonic(8));
+/// partials.set_amplitude(0, 0.5);
+/// partials.set_amplitude(1, 0.25);
+/// partials.set_amplitude(3, 0.125);
+/// constant(220.0) >> additive(&partials);
+/// ```
+pub fn additive<T: Real>(partials: &Arc<AtomicPartials>) -> An<Additive<T, Arc<AtomicPartials>>> {
+    An(Additive::new(DEFAULT_SR, partials.clone()))
+}
+
+/// Additive oscillator bank with `n` partials. Closure `f(t, partials)` writes partial parameters
+/// at time `t` seconds into `partials` every 64 samples.
+/// - Input 0: fundamental frequency in Hz
+/// - Output 0: sum of partials
+///
+/// ### Example: Sweeping Saw Spectrum
+/// ```
+/// use fundsp::prelude::*;
+/// constant(110.0) >> additive_fn(200, |t, partials| {
+///     for (i, partial) in partials.iter_mut().enumerate() {
+///         let k = (i + 1) as f32;
+///         partial.ratio = k;
+///         partial.amplitude = 0.5 / k * exp(-k * (1.0 - sin_hz(0.25, t as f32)) * 0.1);
+///     }
+/// });
+/// ```
+pub fn additive_fn<T, F>(n: usize, f: F) -> An<Additive<T, PartialFn<F>>>
+where
+    T: Real,
+    F: Fn(f64, &mut [Partial]) + Clone + Send + Sync,
+{
+    An(Additive::new(DEFAULT_SR, PartialFn::new(n, f)))
+}
+
 /// Morphing filter that morphs between lowpass, peak and highpass modes.
 /// - Input 0: input signal
 /// - Input 1: center frequency (Hz)
@@ -2886,6 +3628,231 @@ pub fn phaser<T: Real, X: Fn(T) -> T + Clone + Send + Sync>(
         )
 }
 
+/// Stereo chorus with LFO `rate` in Hz, modulation `depth` in 0...1 and wet `mix` in 0...1.
+/// The right channel LFO is offset by a quarter cycle.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Chorused Saw Wave
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz(110.0) >> split::<U2, f32>() >> chorus_stereo::<f32>(0.3, 0.5, 0.5);
+/// ```
+pub fn chorus_stereo<T: Real>(rate: T, depth: T, mix: T) -> An<Chorus<T>> {
+    An(Chorus::new(DEFAULT_SR, 1, rate, depth, mix))
+}
+
+/// Multi-voice stereo chorus (ensemble) with `voices` voices per channel (1 to 8),
+/// LFO `rate` in Hz, modulation `depth` in 0...1 and wet `mix` in 0...1.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: String Ensemble
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz(110.0) >> split::<U2, f32>() >> ensemble_stereo::<f32>(4, 0.5, 0.7, 0.6);
+/// ```
+pub fn ensemble_stereo<T: Real>(voices: usize, rate: T, depth: T, mix: T) -> An<Chorus<T>> {
+    An(Chorus::new(DEFAULT_SR, voices, rate, depth, mix))
+}
+
+/// Stereo flanger with LFO `rate` in Hz, sweep `depth` in 0...1,
+/// `feedback` in -1...1 and wet `mix` in 0...1. The delay sweeps between 0.5 and 10 ms.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Flanged Saw Wave
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz(110.0) >> split::<U2, f32>() >> flanger_stereo::<f32>(0.1, 0.8, 0.7, 0.5);
+/// ```
+pub fn flanger_stereo<T: Real>(rate: T, depth: T, feedback: T, mix: T) -> An<Flanger<T>> {
+    An(Flanger::new(DEFAULT_SR, rate, depth, feedback, mix))
+}
+
+/// Stereo phaser with `stages` first order allpass stages (1 to 24), LFO `rate` in Hz,
+/// sweep `depth` in 0...1, `feedback` in -1...1 and wet `mix` in 0...1.
+/// Setting: (LFO rate in Hz, depth, feedback, mix).
+/// The LFO can be synchronized to tempo with the `set_tempo` method.
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+///
+/// ### Example: Phased Saw Wave
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz(110.0) >> split::<U2, f32>() >> phaser_stereo::<f32>(8, 0.2, 1.0, 0.5, 0.5);
+/// ```
+pub fn phaser_stereo<T: Real>(
+    stages: usize,
+    rate: T,
+    depth: T,
+    feedback: T,
+    mix: T,
+) -> An<Phaser<T>> {
+    An(Phaser::new(DEFAULT_SR, stages, rate, depth, feedback, mix))
+}
+
+/// Channel vocoder with `bands` bands (8 to 64) spaced logarithmically between `low` and `high` Hz.
+/// The spectral envelope of the modulator is imposed on the carrier.
+/// Formant shift, unvoiced noise injection, emphasis and envelope response time are available as methods.
+/// Setting: (formant shift ratio, unvoiced noise amount in 0...1, emphasis in 0...1).
+/// - Input 0: modulator
+/// - Input 1: carrier
+/// - Output 0: vocoded signal
+///
+/// ### Example: Vocode Noise With A Saw Wave
+/// ```
+/// use fundsp::prelude::*;
+/// let mut node = vocoder::<f32>(32, 100.0, 8000.0);
+/// node.set_formant_shift(1.5);
+/// (noise::<f32>() | saw_hz(110.0)) >> node;
+/// ```
+pub fn vocoder<T: Real>(bands: usize, low: f64, high: f64) -> An<Vocoder<T>> {
+    An(Vocoder::new(DEFAULT_SR, bands, low, high))
+}
+
+/// Wideband Hilbert transformer (allpass pair). Produces an analytic signal whose
+/// quadrature component lags the in-phase component by 90 degrees.
+/// - Input 0: input signal
+/// - Output 0: in-phase component
+/// - Output 1: quadrature component
+///
+/// ### Example: Instantaneous Amplitude
+/// ```
+/// use fundsp::prelude::*;
+/// sine_hz::<f32>(440.0) >> hilbert::<f32>() >> map(|x: &Frame<f32, U2>| (x[0] * x[0] + x[1] * x[1]).sqrt());
+/// ```
+pub fn hilbert<T: Real>() -> An<Hilbert<T>> {
+    An(Hilbert::new(DEFAULT_SR))
+}
+
+/// Single sideband frequency shifter.
+/// Setting: shift in Hz.
+/// - Input 0: input signal
+/// - Input 1: shift in Hz
+/// - Output 0: signal shifted up
+/// - Output 1: signal shifted down
+///
+/// ### Example: Shift Up By An LFO Controlled Amount
+/// ```
+/// use fundsp::prelude::*;
+/// (saw_hz::<f32>(110.0) | lfo(|t| 100.0 * sin_hz(0.1, t))) >> freq_shift::<f32>() >> join();
+/// ```
+pub fn freq_shift<T: Real>() -> An<FrequencyShifter<T, U2>> {
+    An(FrequencyShifter::new(DEFAULT_SR, T::zero()))
+}
+
+/// Single sideband frequency shifter with shift `f` Hz.
+/// Setting: shift in Hz.
+/// - Input 0: input signal
+/// - Output 0: signal shifted up
+/// - Output 1: signal shifted down
+///
+/// ### Example: Shift Down By 50 Hz
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz::<f32>(110.0) >> freq_shift_hz::<f32>(50.0) >> (sink() | pass());
+/// ```
+pub fn freq_shift_hz<T: Real>(f: T) -> An<FrequencyShifter<T, U1>> {
+    An(FrequencyShifter::new(DEFAULT_SR, f))
+}
+
+/// Ring modulator with an external carrier.
+/// - Input 0: input signal
+/// - Input 1: carrier signal
+/// - Output 0: modulated signal
+///
+/// ### Example: Ring Modulate With A Triangle Wave
+/// ```
+/// use fundsp::prelude::*;
+/// (saw_hz::<f32>(110.0) | triangle_hz(300.0)) >> ring_mod::<f32>();
+/// ```
+pub fn ring_mod<T: Real>() -> An<RingMod<T, U2>> {
+    An(RingMod::new(DEFAULT_SR, T::zero()))
+}
+
+/// Ring modulator with a built-in sine carrier at `f` Hz.
+/// Setting: carrier frequency in Hz.
+/// - Input 0: input signal
+/// - Output 0: modulated signal
+///
+/// ### Example: Ring Modulated Saw Wave
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz::<f32>(110.0) >> ring_mod_hz::<f32>(300.0);
+/// ```
+pub fn ring_mod_hz<T: Real>(f: T) -> An<RingMod<T, U1>> {
+    An(RingMod::new(DEFAULT_SR, f))
+}
+
+/// Triode stage with `drive` and `output` amplitudes and grid `bias` voltage in -1...1
+/// (plate current is cut off at -1). Positive grid voltages are limited by grid current,
+/// which also shifts the operating point down after loud passages.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Warm Triode
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz::<f32>(110.0) >> triode::<f32>(2.0, -0.2, 0.5);
+/// ```
+pub fn triode<T: Real>(drive: f64, bias: f64, output: f64) -> An<Triode<T>> {
+    An(Triode::new(DEFAULT_SR, drive, bias, output))
+}
+
+/// Tape saturation with Jiles-Atherton magnetic hysteresis, `drive` and `output` amplitudes
+/// and DC `bias` of the magnetizing field.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Saturated Tape
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz::<f32>(110.0) >> tape_saturation::<f32>(1.5, 0.0, 1.0);
+/// ```
+pub fn tape_saturation<T: Real>(drive: f64, bias: f64, output: f64) -> An<TapeSaturation<T>> {
+    An(TapeSaturation::new(DEFAULT_SR, drive, bias, output))
+}
+
+/// Transformer saturation with frequency dependent distortion, `drive` and `output` amplitudes
+/// and DC magnetization `bias` of the core. Low frequencies saturate the core
+/// while high frequencies pass through with little distortion.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+///
+/// ### Example: Saturated Transformer
+/// ```
+/// use fundsp::prelude::*;
+/// saw_hz::<f32>(110.0) >> transformer_saturation::<f32>(3.0, 0.1, 0.5);
+/// ```
+pub fn transformer_saturation<T: Real>(
+    drive: f64,
+    bias: f64,
+    output: f64,
+) -> An<TransformerSaturation<T>> {
+    An(TransformerSaturation::new(DEFAULT_SR, drive, bias, output))
+}
+
 /// Shared float variable. Can be read from and written to from multiple threads.
 ///
 /// ### Example: Add Chorus With Wetness Control
@@ -1,5 +1,6 @@
 //! Reverberation related code.
 
+use super::delay::DelayLine;
 use super::hacker32::*;
 use funutd::dna::*;
 use realfft::*;
@@ -276,3 +277,761 @@ impl<T: Real, F: AudioNode<Sample = T, Inputs = U1, Outputs = U1>> AudioNode for
         Routing::Arbitrary(0.0).propagate(input, 2)
     }
 }
+
+/// First order lowpass filter state for damping.
+#[derive(Clone, Default)]
+struct Damper<T: Float> {
+    /// Lowpass coefficient in 0...1.
+    c: T,
+    state: T,
+}
+
+impl<T: Float> Damper<T> {
+    /// Set cutoff `frequency` in Hz.
+    fn set(&mut self, frequency: f64, sample_rate: f64) {
+        self.c = T::from_f64(1.0 - exp(-TAU * frequency / sample_rate));
+    }
+
+    #[inline]
+    fn lowpass(&mut self, x: T) -> T {
+        self.state += (x - self.state) * self.c;
+        self.state
+    }
+}
+
+/// Base early reflection times in seconds for a 10 meter room.
+const HALL_EARLY_LEFT: [f64; 12] = [
+    0.0043, 0.0079, 0.0112, 0.0157, 0.0191, 0.0238, 0.0284, 0.0331, 0.0396, 0.0462, 0.0539, 0.0617,
+];
+const HALL_EARLY_RIGHT: [f64; 12] = [
+    0.0051, 0.0086, 0.0124, 0.0149, 0.0203, 0.0252, 0.0277, 0.0353, 0.0418, 0.0449, 0.0561, 0.0634,
+];
+
+/// Base late delay line lengths in seconds for a 10 meter room.
+const HALL_DELAYS: [f64; 16] = [
+    0.0297, 0.0313, 0.0339, 0.0367, 0.0383, 0.0411, 0.0437, 0.0463, 0.0481, 0.0517, 0.0539, 0.0571,
+    0.0593, 0.0619, 0.0647, 0.0673,
+];
+
+/// Maximum room size in meters.
+const HALL_MAX_SIZE: f64 = 50.0;
+/// Minimum room size in meters.
+const HALL_MIN_SIZE: f64 = 1.0;
+/// Maximum pre-delay in seconds.
+const HALL_MAX_PREDELAY: f64 = 0.5;
+/// Maximum delay line modulation depth in seconds.
+const HALL_MODULATION_DEPTH: f64 = 0.0008;
+/// Low damping crossover in Hz.
+const HALL_LOW_CROSSOVER: f64 = 250.0;
+
+/// Stereo reverb with pre-delay, tapped early reflections and
+/// a modulated 16-channel feedback delay network for the late tail.
+/// Low and high frequencies decay faster than midrange frequencies
+/// according to the damping settings.
+/// Setting: (room size in meters, reverberation time in seconds, stereo width in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: reverberated left signal
+/// - Output 1: reverberated right signal
+#[derive(Clone)]
+pub struct Hall<T: Real> {
+    sample_rate: f64,
+    size: f64,
+    time: f64,
+    width: T,
+    predelay: f64,
+    low_damping: f64,
+    high_damping: f64,
+    modulation: f64,
+    early_level: T,
+    input: [DelayLine<T>; 2],
+    lines: Vec<DelayLine<T>>,
+    /// Late delay line lengths in samples.
+    length: [T; 16],
+    /// Mid frequency gain per delay line.
+    gain: [T; 16],
+    /// Low frequency gain ratio per delay line.
+    low_ratio: [T; 16],
+    /// High frequency gain ratio per delay line.
+    high_ratio: [T; 16],
+    low: Vec<Damper<T>>,
+    high: Vec<Damper<T>>,
+    early_damper: [Damper<T>; 2],
+    /// Early reflection tap times in samples from the pre-delayed input.
+    early: [[T; 12]; 2],
+    early_gain: [T; 12],
+    predelay_samples: T,
+    /// Modulation phases and phase increments.
+    phase: [f64; 16],
+    rate: [f64; 16],
+    depth: T,
+}
+
+impl<T: Real> Hall<T> {
+    /// Create new reverb. `size` is room size in meters (1 to 50 meters)
+    /// and `time` is reverberation time to -60 dB in seconds.
+    pub fn new(sample_rate: f64, size: f64, time: f64) -> Self {
+        let mut rate = [0.0; 16];
+        for (i, r) in rate.iter_mut().enumerate() {
+            *r = 0.3 + 0.8 * rnd(i as i64);
+        }
+        let mut node = Self {
+            sample_rate: 0.0,
+            size: clamp(HALL_MIN_SIZE, HALL_MAX_SIZE, size),
+            time: max(time, 0.01),
+            width: T::one(),
+            predelay: 0.01,
+            low_damping: 0.2,
+            high_damping: 0.5,
+            modulation: 0.5,
+            early_level: T::from_f64(0.5),
+            input: Default::default(),
+            lines: vec![DelayLine::default(); 16],
+            length: [T::zero(); 16],
+            gain: [T::zero(); 16],
+            low_ratio: [T::one(); 16],
+            high_ratio: [T::one(); 16],
+            low: vec![Damper::default(); 16],
+            high: vec![Damper::default(); 16],
+            early_damper: Default::default(),
+            early: [[T::zero(); 12]; 2],
+            early_gain: std::array::from_fn(|k| T::from_f64(pow(1.0 - k as f64 / 14.0, 1.5))),
+            predelay_samples: T::zero(),
+            phase: [0.0; 16],
+            rate,
+            depth: T::zero(),
+        };
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Room size in meters.
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    /// Set room size in meters (1 to 50 meters).
+    pub fn set_size(&mut self, size: f64) {
+        self.size = clamp(HALL_MIN_SIZE, HALL_MAX_SIZE, size);
+        self.update();
+    }
+
+    /// Reverberation time to -60 dB in seconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Set reverberation time to -60 dB in seconds.
+    pub fn set_time(&mut self, time: f64) {
+        self.time = max(time, 0.01);
+        self.update();
+    }
+
+    /// Set stereo width in 0...1. The default is 1.
+    pub fn set_width(&mut self, width: T) {
+        self.width = clamp01(width);
+    }
+
+    /// Set pre-delay in seconds (0 to 0.5 seconds). The default is 0.01 seconds.
+    pub fn set_predelay(&mut self, predelay: f64) {
+        self.predelay = clamp(0.0, HALL_MAX_PREDELAY, predelay);
+        self.update();
+    }
+
+    /// Set `low` and `high` frequency damping in 0...1.
+    /// Damping shortens the reverberation time of low frequencies below 250 Hz
+    /// and of high frequencies, respectively. The defaults are 0.2 and 0.5.
+    pub fn set_damping(&mut self, low: f64, high: f64) {
+        self.low_damping = clamp01(low);
+        self.high_damping = clamp01(high);
+        self.update();
+    }
+
+    /// Set delay line modulation amount in 0...1. The default is 0.5.
+    pub fn set_modulation(&mut self, modulation: f64) {
+        self.modulation = clamp01(modulation);
+        self.update();
+    }
+
+    /// Set early reflection level. The default is 0.5.
+    pub fn set_early_level(&mut self, level: T) {
+        self.early_level = level;
+    }
+
+    /// Recompute delay lengths, gains and filters.
+    fn update(&mut self) {
+        let sr = self.sample_rate;
+        let scale = self.size / 10.0;
+        self.predelay_samples = T::from_f64(self.predelay * sr);
+        for i in 0..12 {
+            self.early[0][i] = T::from_f64(HALL_EARLY_LEFT[i] * scale * sr);
+            self.early[1][i] = T::from_f64(HALL_EARLY_RIGHT[i] * scale * sr);
+        }
+        let high_cutoff = xerp(20000.0, 1500.0, self.high_damping).min(0.45 * sr);
+        for damper in self.early_damper.iter_mut() {
+            damper.set(high_cutoff, sr);
+        }
+        // Shorter reverberation times at the extremes of the spectrum.
+        let low_time = self.time * (1.0 - 0.8 * self.low_damping);
+        let high_time = self.time * (1.0 - 0.9 * self.high_damping);
+        for i in 0..16 {
+            let length = HALL_DELAYS[i] * scale;
+            self.length[i] = T::from_f64(length * sr);
+            let gain = pow(db_amp(-60.0), length / self.time);
+            self.gain[i] = T::from_f64(gain);
+            self.low_ratio[i] = T::from_f64(pow(db_amp(-60.0), length / low_time) / gain);
+            self.high_ratio[i] = T::from_f64(pow(db_amp(-60.0), length / high_time) / gain);
+            self.low[i].set(HALL_LOW_CROSSOVER, sr);
+            self.high[i].set(high_cutoff, sr);
+        }
+        self.depth = T::from_f64(self.modulation * HALL_MODULATION_DEPTH * sr);
+    }
+}
+
+impl<T: Real> AudioNode for Hall<T> {
+    const ID: u64 = 101;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T);
+
+    fn set(&mut self, (size, time, width): Self::Setting) {
+        self.size = clamp(HALL_MIN_SIZE, HALL_MAX_SIZE, size.to_f64());
+        self.time = max(time.to_f64(), 0.01);
+        self.width = clamp01(width);
+        self.update();
+    }
+
+    fn reset(&mut self) {
+        for line in self.input.iter_mut().chain(self.lines.iter_mut()) {
+            line.reset();
+        }
+        for damper in self
+            .low
+            .iter_mut()
+            .chain(self.high.iter_mut())
+            .chain(self.early_damper.iter_mut())
+        {
+            damper.state = T::zero();
+        }
+        for (i, phase) in self.phase.iter_mut().enumerate() {
+            *phase = rnd(i as i64 + 16);
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            let predelay = (HALL_MAX_PREDELAY * sample_rate) as usize;
+            let early = (HALL_EARLY_RIGHT[11] * HALL_MAX_SIZE / 10.0 * sample_rate) as usize;
+            for line in self.input.iter_mut() {
+                line.resize(predelay + early + 1);
+            }
+            let late = (HALL_DELAYS[15] * HALL_MAX_SIZE / 10.0 * sample_rate) as usize;
+            let modulation = (2.0 * HALL_MODULATION_DEPTH * sample_rate) as usize;
+            for line in self.lines.iter_mut() {
+                line.resize(late + modulation + 1);
+            }
+            self.update();
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.input[0].write(input[0]);
+        self.input[1].write(input[1]);
+        let x = [
+            self.input[0].read(self.predelay_samples),
+            self.input[1].read(self.predelay_samples),
+        ];
+
+        // Early reflections. Odd taps are taken from the opposite channel.
+        let mut early = [T::zero(); 2];
+        for (channel, early) in early.iter_mut().enumerate() {
+            let mut sign = T::one();
+            for (k, &time) in self.early[channel].iter().enumerate() {
+                let source = (channel + k) & 1;
+                *early += sign
+                    * self.early_gain[k]
+                    * self.input[source].read(self.predelay_samples + time);
+                sign = -sign;
+            }
+            *early = self.early_damper[channel].lowpass(*early * T::from_f64(0.25));
+        }
+
+        // Late tail. Read modulated delay lines, apply damping and decay, then diffuse.
+        let mut y = [T::zero(); 16];
+        for i in 0..16 {
+            let lfo = T::from_f64(sin(self.phase[i] * TAU));
+            self.phase[i] += self.rate[i] / self.sample_rate;
+            if self.phase[i] >= 1.0 {
+                self.phase[i] -= 1.0;
+            }
+            let z = self.lines[i].read(self.length[i] + self.depth * (T::one() + lfo));
+            // High shelf: blend of the signal and its lowpassed version.
+            let lowpassed = self.high[i].lowpass(z);
+            let z = lowpassed + (z - lowpassed) * self.high_ratio[i];
+            // Low shelf: blend of the signal and its highpassed version.
+            let lows = self.low[i].lowpass(z);
+            y[i] = (z - lows + lows * self.low_ratio[i]) * self.gain[i];
+        }
+        let mixed = FrameHadamard::<U16, T>::new().unop(&Frame::from(y));
+        let injection = T::from_f64(0.5);
+        for i in 0..16 {
+            self.lines[i].write(mixed[i] + x[i & 1] * injection);
+        }
+
+        let mut late = [T::zero(); 2];
+        for (i, y) in y.iter().enumerate() {
+            let sign = if i & 2 == 0 { T::one() } else { -T::one() };
+            late[i & 1] += sign * *y;
+        }
+        let left = early[0] * self.early_level + late[0] * T::from_f64(0.25);
+        let right = early[1] * self.early_level + late[1] * T::from_f64(0.25);
+        // Adjust stereo width with mid/side processing.
+        let mid = (left + right) * T::from_f64(0.5);
+        let side = (left - right) * T::from_f64(0.5) * self.width;
+        [mid + side, mid - side].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Maximum feedback gain of the plate and spring reverbs.
+const MAX_DECAY: f64 = 0.99;
+
+/// Sample rate of the original plate reverb design.
+const PLATE_SR: f64 = 29761.0;
+
+/// Allpass diffuser with an interpolating delay line, for taps and modulation.
+#[derive(Clone, Default)]
+struct Diffuser<T: Float> {
+    line: DelayLine<T>,
+    /// Delay in samples.
+    length: T,
+    coefficient: T,
+}
+
+impl<T: Float> Diffuser<T> {
+    fn new(coefficient: f64) -> Self {
+        Self {
+            line: DelayLine::default(),
+            length: T::zero(),
+            coefficient: T::from_f64(coefficient),
+        }
+    }
+
+    /// Allocate buffer for a delay of `length` plate samples, with room for `excursion`.
+    fn resize(&mut self, length: f64, excursion: f64, sample_rate: f64) {
+        let scale = sample_rate / PLATE_SR;
+        self.length = T::from_f64(length * scale - 1.0);
+        self.line
+            .resize(((length + excursion) * scale) as usize + 2);
+    }
+
+    #[inline]
+    fn tick(&mut self, x: T, modulation: T) -> T {
+        let z = self.line.read(self.length + modulation);
+        let v = x - self.coefficient * z;
+        self.line.write(v);
+        self.coefficient * v + z
+    }
+
+    /// Read internal state at `tap` plate samples.
+    #[inline]
+    fn tap(&self, tap: T) -> T {
+        self.line.read(tap)
+    }
+}
+
+/// Plain delay line in plate samples.
+#[derive(Clone, Default)]
+struct PlateDelay<T: Float> {
+    line: DelayLine<T>,
+    length: T,
+}
+
+impl<T: Float> PlateDelay<T> {
+    fn resize(&mut self, length: f64, sample_rate: f64) {
+        let scale = sample_rate / PLATE_SR;
+        self.length = T::from_f64(length * scale - 1.0);
+        self.line.resize((length * scale) as usize + 2);
+    }
+
+    #[inline]
+    fn tick(&mut self, x: T) -> T {
+        let z = self.line.read(self.length);
+        self.line.write(x);
+        z
+    }
+}
+
+/// Plate reverb after Dattorro's figure-of-eight tank topology.
+/// Input diffusers feed two cross-coupled branches of modulated allpasses,
+/// delays and damping filters. Output is taken from multiple taps inside the tank.
+/// Setting: (decay in 0...1, damping in 0...1, mix in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+#[derive(Clone)]
+pub struct Plate<T: Real> {
+    sample_rate: f64,
+    decay: T,
+    damping: T,
+    mix: T,
+    /// Input bandwidth lowpass state.
+    bandwidth: T,
+    input: [Schroeder<T>; 4],
+    modulated: [Diffuser<T>; 2],
+    diffuser: [Diffuser<T>; 2],
+    delay0: [PlateDelay<T>; 2],
+    delay1: [PlateDelay<T>; 2],
+    damper: [T; 2],
+    feedback: [T; 2],
+    phase: f64,
+    /// Modulation excursion in samples.
+    excursion: T,
+    /// Output tap scale from plate samples.
+    scale: T,
+}
+
+impl<T: Real> Plate<T> {
+    /// Create new plate reverb with `decay` in 0...1, `damping` in 0...1 and wet `mix` in 0...1.
+    pub fn new(sample_rate: f64, decay: T, damping: T, mix: T) -> Self {
+        let mut node = Self {
+            sample_rate: 0.0,
+            decay: T::zero(),
+            damping: T::zero(),
+            mix: T::zero(),
+            bandwidth: T::zero(),
+            input: [(0.75, 142.0), (0.75, 107.0), (0.625, 379.0), (0.625, 277.0)].map(
+                |(coefficient, length)| {
+                    Schroeder::new(T::from_f64(coefficient), Delay::new(length / PLATE_SR))
+                },
+            ),
+            modulated: [Diffuser::new(-0.7), Diffuser::new(-0.7)],
+            diffuser: [Diffuser::new(0.5), Diffuser::new(0.5)],
+            delay0: Default::default(),
+            delay1: Default::default(),
+            damper: [T::zero(); 2],
+            feedback: [T::zero(); 2],
+            phase: 0.0,
+            excursion: T::zero(),
+            scale: T::one(),
+        };
+        node.set((decay, damping, mix));
+        node.set_sample_rate(sample_rate);
+        node
+    }
+
+    /// Set decay in 0...1. The feedback gain is limited to 0.99.
+    pub fn set_decay(&mut self, decay: T) {
+        self.decay = clamp(T::zero(), T::from_f64(MAX_DECAY), decay);
+    }
+
+    /// Set high frequency damping in 0...1.
+    pub fn set_damping(&mut self, damping: T) {
+        self.damping = clamp01(damping);
+    }
+
+    /// Set wet mix in 0...1.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+
+    /// Read a tank tap for the output.
+    #[inline]
+    fn tap(&self, line: &DelayLine<T>, tap: f64) -> T {
+        line.read(T::from_f64(tap) * self.scale)
+    }
+}
+
+impl<T: Real> AudioNode for Plate<T> {
+    const ID: u64 = 102;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T);
+
+    fn set(&mut self, (decay, damping, mix): Self::Setting) {
+        self.set_decay(decay);
+        self.set_damping(damping);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        for x in self.input.iter_mut() {
+            x.reset();
+        }
+        for x in self.modulated.iter_mut().chain(self.diffuser.iter_mut()) {
+            x.line.reset();
+        }
+        for x in self.delay0.iter_mut().chain(self.delay1.iter_mut()) {
+            x.line.reset();
+        }
+        self.bandwidth = T::zero();
+        self.damper = [T::zero(); 2];
+        self.feedback = [T::zero(); 2];
+        self.phase = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            for x in self.input.iter_mut() {
+                x.set_sample_rate(sample_rate);
+            }
+            self.modulated[0].resize(672.0, 16.0, sample_rate);
+            self.modulated[1].resize(908.0, 16.0, sample_rate);
+            self.diffuser[0].resize(1800.0, 0.0, sample_rate);
+            self.diffuser[1].resize(2656.0, 0.0, sample_rate);
+            self.delay0[0].resize(4453.0, sample_rate);
+            self.delay0[1].resize(4217.0, sample_rate);
+            self.delay1[0].resize(3720.0, sample_rate);
+            self.delay1[1].resize(3163.0, sample_rate);
+            self.scale = T::from_f64(sample_rate / PLATE_SR);
+            self.excursion = T::from_f64(8.0 * sample_rate / PLATE_SR);
+            self.reset();
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let x = (input[0] + input[1]) * T::from_f64(0.5);
+        self.bandwidth += (x - self.bandwidth) * T::from_f64(0.9995);
+        let mut x = self.bandwidth;
+        for diffuser in self.input.iter_mut() {
+            x = diffuser.filter_mono(x);
+        }
+
+        let lfo = T::from_f64(sin(self.phase * TAU));
+        self.phase += 1.0 / self.sample_rate;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+        let modulation = [
+            self.excursion * (T::one() + lfo),
+            self.excursion * (T::one() - lfo),
+        ];
+
+        // The two branches of the tank feed each other.
+        let feedback = [self.feedback[1], self.feedback[0]];
+        for i in 0..2 {
+            let v = self.modulated[i].tick(x + feedback[i], modulation[i]);
+            let v = self.delay0[i].tick(v);
+            self.damper[i] += (v - self.damper[i]) * (T::one() - self.damping);
+            let v = self.diffuser[i].tick(self.damper[i] * self.decay, T::zero());
+            self.feedback[i] = self.delay1[i].tick(v) * self.decay;
+        }
+
+        let left = self.tap(&self.delay0[1].line, 266.0) + self.tap(&self.delay0[1].line, 2974.0)
+            - self.diffuser[1].tap(T::from_f64(1913.0) * self.scale)
+            + self.tap(&self.delay1[1].line, 1996.0)
+            - self.tap(&self.delay0[0].line, 1990.0)
+            - self.diffuser[0].tap(T::from_f64(187.0) * self.scale)
+            - self.tap(&self.delay1[0].line, 1066.0);
+        let right = self.tap(&self.delay0[0].line, 353.0) + self.tap(&self.delay0[0].line, 3627.0)
+            - self.diffuser[0].tap(T::from_f64(1228.0) * self.scale)
+            + self.tap(&self.delay1[0].line, 2673.0)
+            - self.tap(&self.delay0[1].line, 2111.0)
+            - self.diffuser[1].tap(T::from_f64(335.0) * self.scale)
+            - self.tap(&self.delay1[1].line, 121.0);
+        let wet = self.mix * T::from_f64(0.6);
+        let dry = T::one() - self.mix;
+        [input[0] * dry + left * wet, input[1] * dry + right * wet].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
+
+/// Number of stretched allpass stages in a spring.
+const SPRING_STAGES: usize = 100;
+/// Dispersion allpass coefficient.
+const SPRING_DISPERSION: f64 = 0.6;
+/// Transition frequency of the spring chirp in Hz.
+const SPRING_CHIRP: f64 = 4300.0;
+
+/// Dispersive spring: a chain of stretched allpass filters in a delay loop.
+#[derive(Clone, Default)]
+struct Spring<T: Float> {
+    /// Input and output histories of the allpass stages.
+    x: Vec<T>,
+    y: Vec<T>,
+    /// Stretch factor of the allpass stages.
+    k: usize,
+    /// Index into the allpass histories.
+    j: usize,
+    line: DelayLine<T>,
+    /// Loop delay in samples.
+    length: T,
+    damper: T,
+    /// DC blocker state.
+    dc: (T, T),
+    feedback: T,
+}
+
+impl<T: Float> Spring<T> {
+    fn resize(&mut self, delay: f64, sample_rate: f64) {
+        self.k = max(1, (sample_rate / (2.0 * SPRING_CHIRP)) as usize);
+        self.x = vec![T::zero(); SPRING_STAGES * self.k];
+        self.y = vec![T::zero(); SPRING_STAGES * self.k];
+        // The allpass chain contributes to the loop delay at low frequencies.
+        let chain = SPRING_STAGES as f64 * self.k as f64 * (1.0 - SPRING_DISPERSION)
+            / (1.0 + SPRING_DISPERSION);
+        let length = max(1.0, delay * sample_rate - chain);
+        self.length = T::from_f64(length);
+        self.line.resize(length as usize + 2);
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.x.fill(T::zero());
+        self.y.fill(T::zero());
+        self.j = 0;
+        self.line.reset();
+        self.damper = T::zero();
+        self.dc = (T::zero(), T::zero());
+        self.feedback = T::zero();
+    }
+
+    #[inline]
+    fn tick(&mut self, input: T, decay: T, damping: T) -> T {
+        let a = T::from_f64(SPRING_DISPERSION);
+        let mut v = input + self.feedback;
+        let mut i = self.j;
+        for _ in 0..SPRING_STAGES {
+            let y = a * v + self.x[i] - a * self.y[i];
+            self.x[i] = v;
+            self.y[i] = y;
+            v = y;
+            i += self.k;
+        }
+        self.j += 1;
+        if self.j >= self.k {
+            self.j = 0;
+        }
+        // Remove DC and damp high frequencies.
+        let (x1, y1) = self.dc;
+        let y = v - x1 + T::from_f64(0.995) * y1;
+        self.dc = (v, y);
+        self.damper += (y - self.damper) * damping;
+        self.line.write(self.damper);
+        self.feedback = self.line.read(self.length) * decay;
+        self.damper
+    }
+}
+
+/// Spring reverb with two dispersive springs that produce the characteristic chirp.
+/// Setting: (decay in 0...1, damping in 0...1, mix in 0...1).
+/// - Input 0: left signal
+/// - Input 1: right signal
+/// - Output 0: left signal
+/// - Output 1: right signal
+#[derive(Clone)]
+pub struct SpringReverb<T: Real> {
+    sample_rate: f64,
+    decay: T,
+    damping: T,
+    mix: T,
+    /// Damping lowpass coefficient.
+    lowpass: T,
+    springs: [Spring<T>; 2],
+}
+
+impl<T: Real> SpringReverb<T> {
+    /// Create new spring reverb with `decay` in 0...1, `damping` in 0...1 and wet `mix` in 0...1.
+    pub fn new(sample_rate: f64, decay: T, damping: T, mix: T) -> Self {
+        let mut node = Self {
+            sample_rate: 0.0,
+            decay: T::zero(),
+            damping: T::zero(),
+            mix: T::zero(),
+            lowpass: T::zero(),
+            springs: Default::default(),
+        };
+        node.set_sample_rate(sample_rate);
+        node.set((decay, damping, mix));
+        node
+    }
+
+    /// Set decay in 0...1. The feedback gain is limited to 0.99.
+    pub fn set_decay(&mut self, decay: T) {
+        self.decay = clamp(T::zero(), T::from_f64(MAX_DECAY), decay);
+    }
+
+    /// Set high frequency damping in 0...1.
+    pub fn set_damping(&mut self, damping: T) {
+        self.damping = clamp01(damping);
+        let cutoff = min(
+            xerp(8000.0, 1000.0, self.damping.to_f64()),
+            0.45 * self.sample_rate,
+        );
+        self.lowpass = T::from_f64(1.0 - exp(-TAU * cutoff / self.sample_rate));
+    }
+
+    /// Set wet mix in 0...1.
+    pub fn set_mix(&mut self, mix: T) {
+        self.mix = clamp01(mix);
+    }
+}
+
+impl<T: Real> AudioNode for SpringReverb<T> {
+    const ID: u64 = 103;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U2;
+    type Setting = (T, T, T);
+
+    fn set(&mut self, (decay, damping, mix): Self::Setting) {
+        self.set_decay(decay);
+        self.set_damping(damping);
+        self.set_mix(mix);
+    }
+
+    fn reset(&mut self) {
+        for spring in self.springs.iter_mut() {
+            spring.reset();
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            self.springs[0].resize(0.056, sample_rate);
+            self.springs[1].resize(0.067, sample_rate);
+            self.set_damping(self.damping);
+        }
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let x = (input[0] + input[1]) * T::from_f64(0.5);
+        let left = self.springs[0].tick(x, self.decay, self.lowpass);
+        let right = self.springs[1].tick(x, self.decay, self.lowpass);
+        let dry = T::one() - self.mix;
+        [
+            input[0] * dry + left * self.mix,
+            input[1] * dry + right * self.mix,
+        ]
+        .into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 2)
+    }
+}
@@ -0,0 +1,542 @@
+//! Stateful saturation models with built-in oversampling: triode, tape and transformer.
+
+use super::audionode::*;
+use super::filter::*;
+use super::math::*;
+use super::oversample::*;
+use super::signal::*;
+use super::*;
+use numeric_array::typenum::*;
+
+/// Cutoff in Hz of the DC blocker that removes the offset introduced by bias.
+const SATURATION_DC_CUTOFF: f64 = 10.0;
+
+/// Saturation model. Models run at 4x the sample rate.
+trait Model: Clone + Send + Sync {
+    fn reset(&mut self);
+    fn set_sample_rate(&mut self, sample_rate: f64);
+    /// Saturate driven input `x` with DC `bias`.
+    fn saturate(&mut self, x: f64, bias: f64) -> f64;
+}
+
+/// Saturation model with drive, bias, output gain and DC blocking.
+#[derive(Clone)]
+struct Saturator<T: Real, M: Model> {
+    model: M,
+    drive: f64,
+    bias: f64,
+    output: f64,
+    dc: DCBlock<f64, f64>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Real, M: Model> Saturator<T, M> {
+    fn new(model: M, drive: f64, bias: f64, output: f64) -> Self {
+        let mut node = Self {
+            model,
+            drive: max(drive, 0.0),
+            bias,
+            output,
+            dc: DCBlock::new(SATURATION_DC_CUTOFF),
+            _marker: std::marker::PhantomData,
+        };
+        node.reset();
+        node
+    }
+}
+
+impl<T: Real, M: Model> AudioNode for Saturator<T, M> {
+    const ID: u64 = 117;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.model.reset();
+        self.dc.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.model.set_sample_rate(sample_rate);
+        self.dc.set_sample_rate(sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let y = self
+            .model
+            .saturate(input[0].to_f64() * self.drive, self.bias);
+        let y = self.dc.filter_mono(y);
+        [T::from_f64(y * self.output)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = input[0].distort(0.0);
+        output
+    }
+}
+
+/// Saturator that runs at 4x the sample rate.
+type Oversampled<T, M> = Oversampler<T, Oversampler<T, Saturator<T, M>>>;
+
+fn oversampled<T: Real, M: Model>(
+    sample_rate: f64,
+    model: M,
+    drive: f64,
+    bias: f64,
+    output: f64,
+) -> Oversampled<T, M> {
+    Oversampler::new(
+        sample_rate,
+        Oversampler::new(
+            sample_rate * 2.0,
+            Saturator::new(model, drive, bias, output),
+        ),
+    )
+}
+
+/// Access saturator inside oversamplers.
+fn saturator<T: Real, M: Model>(x: &mut Oversampled<T, M>) -> &mut Saturator<T, M> {
+    x.node_mut().node_mut()
+}
+
+/// Grid voltage at which plate current is cut off.
+const TRIODE_CUTOFF: f64 = -1.0;
+
+/// Sharpness of the knee at plate current cutoff.
+const TRIODE_KNEE: f64 = 4.0;
+
+/// Time constant in seconds of grid coupling capacitor charging during grid current.
+const TRIODE_CHARGE_TIME: f64 = 0.005;
+
+/// Time constant in seconds of grid coupling capacitor discharge.
+const TRIODE_DISCHARGE_TIME: f64 = 0.1;
+
+/// Plate current of the triode as a function of grid voltage,
+/// following the 3/2 power law with a smooth knee at cutoff.
+fn plate_current(grid: f64) -> f64 {
+    let x = (grid - TRIODE_CUTOFF) * TRIODE_KNEE;
+    let softplus = if x > 30.0 { x } else { x.exp().ln_1p() };
+    (softplus / TRIODE_KNEE).powf(1.5)
+}
+
+/// Slope of plate current at zero grid voltage.
+fn plate_slope() -> f64 {
+    let x = -TRIODE_CUTOFF * TRIODE_KNEE;
+    let softplus = x.exp().ln_1p();
+    1.5 * (softplus / TRIODE_KNEE).sqrt() / (1.0 + (-x).exp())
+}
+
+#[derive(Clone)]
+struct TriodeModel {
+    /// Voltage of the grid coupling capacitor.
+    grid: f64,
+    charge: f64,
+    discharge: f64,
+    /// Plate current slope normalization.
+    gain: f64,
+}
+
+impl TriodeModel {
+    fn new() -> Self {
+        Self {
+            grid: 0.0,
+            charge: 0.0,
+            discharge: 0.0,
+            gain: 1.0 / plate_slope(),
+        }
+    }
+}
+
+impl Model for TriodeModel {
+    fn reset(&mut self) {
+        self.grid = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.charge = 1.0 - exp(-1.0 / (TRIODE_CHARGE_TIME * sample_rate));
+        self.discharge = exp(-1.0 / (TRIODE_DISCHARGE_TIME * sample_rate));
+    }
+
+    #[inline]
+    fn saturate(&mut self, x: f64, bias: f64) -> f64 {
+        let grid = x + bias - self.grid;
+        // Grid current limits positive grid voltage and charges the coupling capacitor,
+        // shifting the operating point down (blocking distortion).
+        let limited = if grid > 0.0 {
+            grid / (1.0 + grid)
+        } else {
+            grid
+        };
+        self.grid = self.grid * self.discharge + (grid - limited) * self.charge;
+        (plate_current(limited) - plate_current(bias)) * self.gain
+    }
+}
+
+/// Triode stage with bias and grid current behavior.
+/// Positive grid voltages are limited by grid current, which also shifts the operating point
+/// down for a while after loud passages. Negative grid voltages are cut off.
+/// The stage is not inverting. Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+#[derive(Clone)]
+pub struct Triode<T: Real> {
+    x: Oversampled<T, TriodeModel>,
+}
+
+impl<T: Real> Triode<T> {
+    /// Create new triode stage. Drive and output are amplitude gains.
+    /// Bias is grid voltage in -1...1: plate current is cut off at -1.
+    pub fn new(sample_rate: f64, drive: f64, bias: f64, output: f64) -> Self {
+        let mut node = Self {
+            x: oversampled(sample_rate, TriodeModel::new(), 0.0, 0.0, 0.0),
+        };
+        node.set((drive, bias, output));
+        node
+    }
+
+    /// Set input drive amplitude.
+    pub fn set_drive(&mut self, drive: f64) {
+        saturator(&mut self.x).drive = max(drive, 0.0);
+    }
+
+    /// Set grid bias voltage in -1...1.
+    pub fn set_bias(&mut self, bias: f64) {
+        saturator(&mut self.x).bias = clamp11(bias);
+    }
+
+    /// Set output amplitude.
+    pub fn set_output(&mut self, output: f64) {
+        saturator(&mut self.x).output = output;
+    }
+}
+
+impl<T: Real> AudioNode for Triode<T> {
+    const ID: u64 = 114;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = (f64, f64, f64);
+
+    fn set(&mut self, (drive, bias, output): Self::Setting) {
+        self.set_drive(drive);
+        self.set_bias(bias);
+        self.set_output(output);
+    }
+
+    fn reset(&mut self) {
+        self.x.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.x.set_sample_rate(sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.x.tick(input)
+    }
+
+    fn route(&mut self, input: &SignalFrame, frequency: f64) -> SignalFrame {
+        self.x.route(input, frequency)
+    }
+}
+
+/// Saturation magnetization of the tape model.
+const TAPE_SATURATION: f64 = 1.0;
+
+/// Anhysteretic magnetization shape parameter. Gives unity small signal slope.
+const TAPE_SHAPE: f64 = TAPE_SATURATION / 3.0;
+
+/// Pinning parameter, which controls the width of the hysteresis loop.
+const TAPE_PINNING: f64 = 0.47875;
+
+/// Mean field parameter for interdomain coupling.
+const TAPE_COUPLING: f64 = 1.6e-3;
+
+/// Ratio of reversible to total magnetization.
+const TAPE_REVERSIBILITY: f64 = 0.7;
+
+/// Langevin function `coth(x) - 1/x`.
+fn langevin(x: f64) -> f64 {
+    if abs(x) < 1.0e-4 {
+        x / 3.0
+    } else {
+        1.0 / tanh(x) - 1.0 / x
+    }
+}
+
+/// Derivative of the Langevin function.
+fn langevin_derivative(x: f64) -> f64 {
+    if abs(x) < 1.0e-4 {
+        1.0 / 3.0
+    } else {
+        1.0 / (x * x) - 1.0 / squared(x.sinh())
+    }
+}
+
+#[derive(Clone)]
+struct TapeModel {
+    /// Magnetization.
+    m: f64,
+    /// Previous magnetizing field.
+    h: f64,
+}
+
+impl TapeModel {
+    fn new() -> Self {
+        Self { m: 0.0, h: 0.0 }
+    }
+
+    /// Jiles-Atherton magnetization slope at magnetization `m` and field `h`
+    /// when the field is moving in `direction` (-1 or 1).
+    fn slope(m: f64, h: f64, direction: f64) -> f64 {
+        let effective = (h + TAPE_COUPLING * m) / TAPE_SHAPE;
+        let anhysteretic = TAPE_SATURATION * langevin(effective);
+        let difference = anhysteretic - m;
+        let irreversible = if difference * direction > 0.0 {
+            let denominator =
+                (1.0 - TAPE_REVERSIBILITY) * direction * TAPE_PINNING - TAPE_COUPLING * difference;
+            (1.0 - TAPE_REVERSIBILITY) * difference / denominator
+        } else {
+            0.0
+        };
+        let reversible =
+            TAPE_REVERSIBILITY * TAPE_SATURATION / TAPE_SHAPE * langevin_derivative(effective);
+        (irreversible + reversible) / (1.0 - TAPE_COUPLING * reversible)
+    }
+}
+
+impl Model for TapeModel {
+    fn reset(&mut self) {
+        self.m = 0.0;
+        self.h = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, _sample_rate: f64) {}
+
+    #[inline]
+    fn saturate(&mut self, x: f64, bias: f64) -> f64 {
+        let h = x + bias;
+        let dh = h - self.h;
+        if dh != 0.0 {
+            // Second order Runge-Kutta step along the field.
+            let direction = signum(dh);
+            let k1 = dh * Self::slope(self.m, self.h, direction);
+            let k2 = dh * Self::slope(self.m + 0.5 * k1, self.h + 0.5 * dh, direction);
+            self.m += k2;
+            if !self.m.is_finite() {
+                self.m = 0.0;
+            }
+            self.m = clamp(-TAPE_SATURATION, TAPE_SATURATION, self.m);
+        }
+        self.h = h;
+        self.m
+    }
+}
+
+/// Tape saturation with Jiles-Atherton magnetic hysteresis.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+#[derive(Clone)]
+pub struct TapeSaturation<T: Real> {
+    x: Oversampled<T, TapeModel>,
+}
+
+impl<T: Real> TapeSaturation<T> {
+    /// Create new tape saturator. Drive and output are amplitude gains.
+    /// Bias is a DC offset added to the magnetizing field, which makes saturation asymmetric.
+    pub fn new(sample_rate: f64, drive: f64, bias: f64, output: f64) -> Self {
+        let mut node = Self {
+            x: oversampled(sample_rate, TapeModel::new(), 0.0, 0.0, 0.0),
+        };
+        node.set((drive, bias, output));
+        node
+    }
+
+    /// Set input drive amplitude.
+    pub fn set_drive(&mut self, drive: f64) {
+        saturator(&mut self.x).drive = max(drive, 0.0);
+    }
+
+    /// Set DC bias of the magnetizing field.
+    pub fn set_bias(&mut self, bias: f64) {
+        saturator(&mut self.x).bias = bias;
+    }
+
+    /// Set output amplitude.
+    pub fn set_output(&mut self, output: f64) {
+        saturator(&mut self.x).output = output;
+    }
+}
+
+impl<T: Real> AudioNode for TapeSaturation<T> {
+    const ID: u64 = 115;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = (f64, f64, f64);
+
+    fn set(&mut self, (drive, bias, output): Self::Setting) {
+        self.set_drive(drive);
+        self.set_bias(bias);
+        self.set_output(output);
+    }
+
+    fn reset(&mut self) {
+        self.x.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.x.set_sample_rate(sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.x.tick(input)
+    }
+
+    fn route(&mut self, input: &SignalFrame, frequency: f64) -> SignalFrame {
+        self.x.route(input, frequency)
+    }
+}
+
+/// Default corner frequency in Hz below which the transformer core saturates fully.
+const TRANSFORMER_CORNER: f64 = 100.0;
+
+#[derive(Clone)]
+struct TransformerModel {
+    corner: f64,
+    /// Core flux, a lowpass filtered version of the input.
+    flux: f64,
+    coeff: f64,
+    sample_rate: f64,
+}
+
+impl TransformerModel {
+    fn new() -> Self {
+        Self {
+            corner: TRANSFORMER_CORNER,
+            flux: 0.0,
+            coeff: 0.0,
+            sample_rate: DEFAULT_SR,
+        }
+    }
+
+    fn set_corner(&mut self, corner: f64) {
+        self.corner = max(corner, 1.0);
+        self.coeff = 1.0 - exp(-TAU * self.corner / self.sample_rate);
+    }
+}
+
+impl Model for TransformerModel {
+    fn reset(&mut self) {
+        self.flux = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.set_corner(self.corner);
+    }
+
+    #[inline]
+    fn saturate(&mut self, x: f64, bias: f64) -> f64 {
+        // Core flux is proportional to the integral of the voltage, so low frequencies
+        // saturate the core while high frequencies pass through with little distortion.
+        self.flux += (x - self.flux) * self.coeff;
+        let saturated = tanh(self.flux + bias) - tanh(bias);
+        x - self.flux + saturated
+    }
+}
+
+/// Transformer saturation with frequency dependent distortion.
+/// Low frequencies saturate the core while high frequencies pass through with little distortion.
+/// Runs internally at 4x the sample rate.
+/// Setting: (drive, bias, output).
+/// - Input 0: input signal
+/// - Output 0: saturated signal
+#[derive(Clone)]
+pub struct TransformerSaturation<T: Real> {
+    x: Oversampled<T, TransformerModel>,
+}
+
+impl<T: Real> TransformerSaturation<T> {
+    /// Create new transformer saturator. Drive and output are amplitude gains.
+    /// Bias is DC magnetization of the core, which adds even harmonics.
+    pub fn new(sample_rate: f64, drive: f64, bias: f64, output: f64) -> Self {
+        let mut node = Self {
+            x: oversampled(sample_rate, TransformerModel::new(), 0.0, 0.0, 0.0),
+        };
+        node.set((drive, bias, output));
+        node
+    }
+
+    /// Set input drive amplitude.
+    pub fn set_drive(&mut self, drive: f64) {
+        saturator(&mut self.x).drive = max(drive, 0.0);
+    }
+
+    /// Set DC magnetization of the core.
+    pub fn set_bias(&mut self, bias: f64) {
+        saturator(&mut self.x).bias = bias;
+    }
+
+    /// Set output amplitude.
+    pub fn set_output(&mut self, output: f64) {
+        saturator(&mut self.x).output = output;
+    }
+
+    /// Set corner frequency in Hz (default 100 Hz). Distortion decreases above the corner
+    /// at 6 dB per octave.
+    pub fn set_corner(&mut self, corner: f64) {
+        saturator(&mut self.x).model.set_corner(corner);
+    }
+}
+
+impl<T: Real> AudioNode for TransformerSaturation<T> {
+    const ID: u64 = 116;
+    type Sample = T;
+    type Inputs = U1;
+    type Outputs = U1;
+    type Setting = (f64, f64, f64);
+
+    fn set(&mut self, (drive, bias, output): Self::Setting) {
+        self.set_drive(drive);
+        self.set_bias(bias);
+        self.set_output(output);
+    }
+
+    fn reset(&mut self) {
+        self.x.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.x.set_sample_rate(sample_rate);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        self.x.tick(input)
+    }
+
+    fn route(&mut self, input: &SignalFrame, frequency: f64) -> SignalFrame {
+        self.x.route(input, frequency)
+    }
+}
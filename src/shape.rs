@@ -91,27 +91,329 @@ pub enum Shape<T: Real> {
     /// The argument to `tanh` is divided by the RMS level of the signal and multiplied by hardness.
     /// Minimum estimated signal level for adaptive distortion is approximately -60 dB.
     AdaptiveTanh(T, T),
+    /// Triangle wavefolder with configurable gain.
+    /// The input is multiplied by the gain and reflected back into -1...1 as many times as needed.
+    Fold(T),
+    /// Asymmetric tube curve with configurable hardness.
+    /// Positive inputs saturate like `tanh` while negative inputs saturate softly like `softsign`,
+    /// which adds even harmonics.
+    Tube(T),
+    /// Half-wave rectifier: negative inputs are set to zero.
+    HalfRectify,
+    /// Full-wave rectifier: absolute value of the input.
+    FullRectify,
+    /// Chebyshev polynomial of the first kind of the given order applied to input clipped to -1...1.
+    /// A full scale sine wave is converted into its harmonic of the same order.
+    Chebyshev(usize),
+    /// Chebyshev polynomial of the first kind of the given order applied to input saturated with `tanh`.
+    SoftChebyshev(usize),
 }
 
-/// Waveshaper with various shaping modes.
+/// Antiderivative antialiasing (ADAA) order for `Shaper`.
+/// Antialiasing reduces aliasing at high drive without oversampling.
+/// First order ADAA adds half a sample of latency and second order ADAA adds one sample.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Antialias {
+    /// No antialiasing.
+    #[default]
+    Off,
+    /// First order antiderivative antialiasing.
+    First,
+    /// Second order antiderivative antialiasing.
+    Second,
+}
+
+/// Highest ADAA order supported by shaping mode.
+/// Modes with state or without a closed form antiderivative are not antialiased.
+/// The antiderivatives divide by hardness or gain, so a zero parameter disables antialiasing.
+fn max_antialias_order<T: Real>(shape: &Shape<T>) -> usize {
+    match shape {
+        Shape::Tanh(h) | Shape::Atan(h) | Shape::Softsign(h) | Shape::Tube(h) | Shape::Fold(h)
+            if *h == T::zero() =>
+        {
+            0
+        }
+        Shape::Clip
+        | Shape::ClipTo(_, _)
+        | Shape::Atan(_)
+        | Shape::Softsign(_)
+        | Shape::HalfRectify
+        | Shape::FullRectify => 2,
+        Shape::Tanh(_) | Shape::Tube(_) | Shape::Fold(_) | Shape::Chebyshev(_) => 1,
+        Shape::Crush(_)
+        | Shape::SoftCrush(_)
+        | Shape::AdaptiveTanh(_, _)
+        | Shape::SoftChebyshev(_) => 0,
+    }
+}
+
+/// Chebyshev polynomial of the first kind of order `n`.
+fn chebyshev(n: usize, x: f64) -> f64 {
+    let (mut t0, mut t1) = (1.0, x);
+    if n == 0 {
+        return t0;
+    }
+    for _ in 1..n {
+        (t0, t1) = (t1, 2.0 * x * t1 - t0);
+    }
+    t1
+}
+
+/// Stable `ln(cosh(x))`.
+fn log_cosh(x: f64) -> f64 {
+    let x = abs(x);
+    x + (-2.0 * x).exp().ln_1p() - std::f64::consts::LN_2
+}
+
+/// Position of `x` inside the period of the triangle wavefolder, in 0...4.
+fn fold_phase(x: f64) -> f64 {
+    (x + 1.0).rem_euclid(4.0)
+}
+
+/// Memoryless shaping function. Adaptive `tanh` is treated as plain `tanh`.
+fn shape_value<T: Real>(shape: &Shape<T>, x: f64) -> f64 {
+    match *shape {
+        Shape::Clip => clamp11(x),
+        Shape::ClipTo(min, max) => clamp(min.to_f64(), max.to_f64(), x),
+        Shape::Tanh(hardness) => tanh(x * hardness.to_f64()),
+        Shape::Atan(hardness) => atan(x * hardness.to_f64()),
+        Shape::Softsign(hardness) => softsign(x * hardness.to_f64()),
+        Shape::Crush(levels) => {
+            let levels = levels.to_f64();
+            round(x * levels) / levels
+        }
+        Shape::SoftCrush(levels) => {
+            let levels = levels.to_f64();
+            let x = x * levels;
+            let y = floor(x);
+            (y + smooth9(smooth9(x - y))) / levels
+        }
+        Shape::AdaptiveTanh(_timescale, hardness) => tanh(x * hardness.to_f64()),
+        Shape::Fold(gain) => {
+            let v = fold_phase(x * gain.to_f64());
+            if v <= 2.0 {
+                v - 1.0
+            } else {
+                3.0 - v
+            }
+        }
+        Shape::Tube(hardness) => {
+            let u = x * hardness.to_f64();
+            if u >= 0.0 {
+                tanh(u)
+            } else {
+                u / (1.0 - u)
+            }
+        }
+        Shape::HalfRectify => max(x, 0.0),
+        Shape::FullRectify => abs(x),
+        Shape::Chebyshev(n) => chebyshev(n, clamp11(x)),
+        Shape::SoftChebyshev(n) => chebyshev(n, tanh(x)),
+    }
+}
+
+/// First antiderivative of the shaping function.
+fn antiderivative1<T: Real>(shape: &Shape<T>, x: f64) -> f64 {
+    match *shape {
+        Shape::Clip => {
+            let c = clamp11(x);
+            c * x - 0.5 * c * c
+        }
+        Shape::ClipTo(min, max) => {
+            let c = clamp(min.to_f64(), max.to_f64(), x);
+            c * x - 0.5 * c * c
+        }
+        Shape::Tanh(hardness) => {
+            let h = hardness.to_f64();
+            log_cosh(x * h) / h
+        }
+        Shape::Atan(hardness) => {
+            let h = hardness.to_f64();
+            let u = x * h;
+            (u * atan(u) - 0.5 * u.mul_add(u, 1.0).ln()) / h
+        }
+        Shape::Softsign(hardness) => {
+            let h = hardness.to_f64();
+            let v = abs(x * h);
+            (v - v.ln_1p()) / h
+        }
+        Shape::Fold(gain) => {
+            let h = gain.to_f64();
+            let v = fold_phase(x * h);
+            if v <= 2.0 {
+                (0.5 * v * v - v) / h
+            } else {
+                (3.0 * (v - 2.0) - 0.5 * (v * v - 4.0)) / h
+            }
+        }
+        Shape::Tube(hardness) => {
+            let h = hardness.to_f64();
+            let u = x * h;
+            if u >= 0.0 {
+                log_cosh(u) / h
+            } else {
+                (-u - (-u).ln_1p()) / h
+            }
+        }
+        Shape::HalfRectify => 0.5 * squared(max(x, 0.0)),
+        Shape::FullRectify => 0.5 * x * abs(x),
+        Shape::Chebyshev(n) => {
+            let c = clamp11(x);
+            let integral = match n {
+                0 => c,
+                1 => 0.5 * c * c,
+                _ => {
+                    chebyshev(n + 1, c) / (2 * (n + 1)) as f64
+                        - chebyshev(n - 1, c) / (2 * (n - 1)) as f64
+                }
+            };
+            integral + chebyshev(n, c) * (x - c)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Second antiderivative of the shaping function.
+fn antiderivative2<T: Real>(shape: &Shape<T>, x: f64) -> f64 {
+    match *shape {
+        Shape::Clip => {
+            let c = clamp11(x);
+            c * x * x / 2.0 - c * c * x / 2.0 + c * c * c / 6.0
+        }
+        Shape::ClipTo(min, max) => {
+            let c = clamp(min.to_f64(), max.to_f64(), x);
+            c * x * x / 2.0 - c * c * x / 2.0 + c * c * c / 6.0
+        }
+        Shape::Atan(hardness) => {
+            let h = hardness.to_f64();
+            let u = x * h;
+            0.5 * ((u * u - 1.0) * atan(u) + u - u * u.mul_add(u, 1.0).ln()) / (h * h)
+        }
+        Shape::Softsign(hardness) => {
+            let h = hardness.to_f64();
+            let u = x * h;
+            let v = abs(u);
+            signum(u) * (0.5 * v * v - (1.0 + v) * v.ln_1p() + v) / (h * h)
+        }
+        Shape::HalfRectify => max(x, 0.0).powi(3) / 6.0,
+        Shape::FullRectify => abs(x).powi(3) / 6.0,
+        _ => 0.0,
+    }
+}
+
+/// Threshold for input differences below which first order ADAA
+/// falls back to evaluating the shaping function at the midpoint.
+const ADAA1_EPSILON: f64 = 1.0e-6;
+
+/// Threshold for input differences in second order ADAA.
+/// This is larger than in first order ADAA due to cancellation in the divided differences.
+const ADAA2_EPSILON: f64 = 1.0e-4;
+
+/// Waveshaper with various shaping modes and optional antiderivative antialiasing.
 #[derive(Clone)]
 pub struct Shaper<T: Real> {
     shape: Shape<T>,
     /// Per-sample smoothing factor.
     smoothing: T,
     state: T,
+    /// Effective ADAA order (0, 1 or 2).
+    order: usize,
+    /// Previous two inputs.
+    x1: f64,
+    x2: f64,
+    /// Antiderivative of the shaping function at the previous input (first order ADAA uses
+    /// the first antiderivative and second order ADAA uses the second antiderivative).
+    f1: f64,
+    /// Divided difference of the second antiderivative over the previous two inputs.
+    d1: f64,
 }
 
 impl<T: Real> Shaper<T> {
+    /// Create new shaper without antialiasing.
     pub fn new(shape: Shape<T>) -> Self {
+        Self::with_antialias(shape, Antialias::Off)
+    }
+
+    /// Create new shaper with antiderivative antialiasing.
+    /// Modes that do not support the requested order use the highest order they support.
+    /// Antialiasing is disabled for modes with zero hardness or gain.
+    pub fn with_antialias(shape: Shape<T>, antialias: Antialias) -> Self {
+        let requested = match antialias {
+            Antialias::Off => 0,
+            Antialias::First => 1,
+            Antialias::Second => 2,
+        };
+        let order = min(requested, max_antialias_order(&shape));
         let mut shaper = Self {
             shape,
             smoothing: T::zero(),
             state: T::zero(),
+            order,
+            x1: 0.0,
+            x2: 0.0,
+            f1: 0.0,
+            d1: 0.0,
         };
         shaper.set_sample_rate(DEFAULT_SR);
+        shaper.reset();
         shaper
     }
+
+    /// Effective antialiasing order.
+    pub fn antialias(&self) -> Antialias {
+        match self.order {
+            0 => Antialias::Off,
+            1 => Antialias::First,
+            _ => Antialias::Second,
+        }
+    }
+
+    /// Shape one sample with first order ADAA.
+    #[inline]
+    fn adaa1(&mut self, x: f64) -> f64 {
+        let f1 = antiderivative1(&self.shape, x);
+        let dx = x - self.x1;
+        let y = if abs(dx) < ADAA1_EPSILON {
+            shape_value(&self.shape, 0.5 * (x + self.x1))
+        } else {
+            (f1 - self.f1) / dx
+        };
+        self.x1 = x;
+        self.f1 = f1;
+        y
+    }
+
+    /// Shape one sample with second order ADAA.
+    #[inline]
+    fn adaa2(&mut self, x: f64) -> f64 {
+        let f2 = antiderivative2(&self.shape, x);
+        let dx = x - self.x1;
+        let d = if abs(dx) < ADAA2_EPSILON {
+            antiderivative1(&self.shape, 0.5 * (x + self.x1))
+        } else {
+            (f2 - self.f1) / dx
+        };
+        let dx2 = x - self.x2;
+        let y = if abs(dx2) < ADAA2_EPSILON {
+            // The input returned to where it was two samples ago.
+            let mid = 0.5 * (x + self.x2);
+            let delta = mid - self.x1;
+            if abs(delta) < ADAA2_EPSILON {
+                shape_value(&self.shape, 0.5 * (mid + self.x1))
+            } else {
+                2.0 / delta
+                    * (antiderivative1(&self.shape, mid)
+                        + (self.f1 - antiderivative2(&self.shape, mid)) / delta)
+            }
+        } else {
+            2.0 * (d - self.d1) / dx2
+        };
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.f1 = f2;
+        self.d1 = d;
+        y
+    }
 }
 
 impl<T: Real> AudioNode for Shaper<T> {
@@ -123,6 +425,16 @@ impl<T: Real> AudioNode for Shaper<T> {
 
     fn reset(&mut self) {
         self.state = T::zero();
+        self.x1 = 0.0;
+        self.x2 = 0.0;
+        match self.order {
+            1 => self.f1 = antiderivative1(&self.shape, 0.0),
+            2 => {
+                self.f1 = antiderivative2(&self.shape, 0.0);
+                self.d1 = antiderivative1(&self.shape, 0.0);
+            }
+            _ => (),
+        }
     }
 
     fn set_sample_rate(&mut self, sample_rate: f64) {
@@ -137,6 +449,11 @@ impl<T: Real> AudioNode for Shaper<T> {
         input: &Frame<Self::Sample, Self::Inputs>,
     ) -> Frame<Self::Sample, Self::Outputs> {
         let input = input[0];
+        match self.order {
+            1 => return [T::from_f64(self.adaa1(input.to_f64()))].into(),
+            2 => return [T::from_f64(self.adaa2(input.to_f64()))].into(),
+            _ => (),
+        }
         match self.shape {
             Shape::Clip => [clamp11(input)].into(),
             Shape::ClipTo(min, max) => [clamp(min, max, input)].into(),
@@ -154,6 +471,7 @@ impl<T: Real> AudioNode for Shaper<T> {
                     + (T::one() - self.smoothing) * (T::from_f32(1.0e-6) + squared(input));
                 [tanh(input * hardness / sqrt(self.state))].into()
             }
+            _ => [T::from_f64(shape_value(&self.shape, input.to_f64()))].into(),
         }
     }
 
@@ -165,6 +483,12 @@ impl<T: Real> AudioNode for Shaper<T> {
     ) {
         let input = input[0];
         let output = &mut *output[0];
+        if self.order > 0 {
+            for (x, y) in output[0..size].iter_mut().zip(input[0..size].iter()) {
+                *x = self.tick(&[*y].into())[0];
+            }
+            return;
+        }
         match self.shape {
             Shape::Clip => {
                 for (x, y) in output[0..size].iter_mut().zip(input[0..size].iter()) {
@@ -210,12 +534,24 @@ impl<T: Real> AudioNode for Shaper<T> {
                     *x = tanh(*y * hardness / sqrt(self.state));
                 }
             }
+            _ => {
+                for (x, y) in output[0..size].iter_mut().zip(input[0..size].iter()) {
+                    *x = T::from_f64(shape_value(&self.shape, y.to_f64()));
+                }
+            }
         }
     }
 
     fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
         let mut output = new_signal_frame(self.outputs());
-        output[0] = input[0].distort(0.0);
+        output[0] = match input[0] {
+            // Constant inputs stay constant, also with antialiasing.
+            Signal::Value(x) if !matches!(self.shape, Shape::AdaptiveTanh(_, _)) => {
+                Signal::Value(shape_value(&self.shape, x))
+            }
+            // ADAA delays the signal by half a sample per order.
+            _ => input[0].distort(self.order as f64 * 0.5),
+        };
         output
     }
 }
@@ -141,3 +141,458 @@ pub fn cymbal(seed: i64) -> An<impl AudioNode<Sample = f64, Inputs = U0, Outputs
         >> highpass_hz(2500.0, 1.0)
         >> declick_s(0.001)
 }
+
+/// Analog style drum voices, modeled on classic drum machine circuits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DrumVoice {
+    /// Bass drum: a decaying sine with a pitch sweep, a click and saturation.
+    Kick,
+    /// Snare drum: two decaying sine modes and highpassed noise.
+    Snare,
+    /// Hand clap: a burst of bandpassed noise followed by a decaying tail.
+    Clap,
+    /// Low tom.
+    LowTom,
+    /// Mid tom.
+    MidTom,
+    /// High tom.
+    HighTom,
+    /// Closed hi-hat: six detuned square oscillators, band limited. Chokes the open hi-hat.
+    ClosedHat,
+    /// Open hi-hat. Chokes the closed hi-hat.
+    OpenHat,
+    /// Rim shot: two short sine modes and a click.
+    Rim,
+    /// Cowbell: two square oscillators through a bandpass filter.
+    Cowbell,
+}
+
+impl DrumVoice {
+    /// Choke group of the voice, if any. Voices in the same choke group cut each other off.
+    pub fn choke_group(&self) -> Option<usize> {
+        match self {
+            DrumVoice::ClosedHat | DrumVoice::OpenHat => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Main decay time constant in seconds at the default decay setting.
+    fn decay_time(&self) -> f64 {
+        match self {
+            DrumVoice::Kick => 0.35,
+            DrumVoice::Snare => 0.12,
+            DrumVoice::Clap => 0.12,
+            DrumVoice::LowTom | DrumVoice::MidTom | DrumVoice::HighTom => 0.25,
+            DrumVoice::ClosedHat => 0.025,
+            DrumVoice::OpenHat => 0.25,
+            DrumVoice::Rim => 0.01,
+            DrumVoice::Cowbell => 0.2,
+        }
+    }
+
+    /// Time in seconds for the voice to decay by 80 dB with decay setting `decay`.
+    pub fn duration(&self, decay: f64) -> f64 {
+        // The clap tail starts after the initial bursts.
+        let offset = if *self == DrumVoice::Clap { 0.03 } else { 0.0 };
+        offset + self.decay_time() * max(decay, 0.01) * 80.0 / 20.0 * LN_10
+    }
+}
+
+/// Frequencies of the six square wave oscillators of an 808 style hi-hat.
+const HAT_FREQUENCIES: [f64; 6] = [205.3, 304.4, 369.6, 522.7, 540.0, 800.0];
+
+/// Onset times of the noise bursts of a hand clap.
+const CLAP_BURSTS: [f64; 4] = [0.0, 0.011, 0.023, 0.031];
+
+/// Analog style drum voice. Plays one hit starting from reset.
+/// Parameters are read continuously from the inputs.
+/// - Input 0: tune in semitones relative to the default pitch (for example, 0.0)
+/// - Input 1: decay time multiplier (for example, 1.0)
+/// - Input 2: tone in 0...1 (for example, 0.5)
+/// - Input 3: velocity in 0...1
+/// - Output 0: drum sound
+#[derive(Clone)]
+pub struct Drum {
+    voice: DrumVoice,
+    hash: u64,
+    rnd: Rnd,
+    sample_rate: f64,
+    time: f64,
+    phase: [f64; 6],
+    hint: [usize; 6],
+    bandpass: FixedSvf<f64, f64, BandpassMode<f64>>,
+    highpass: FixedSvf<f64, f64, HighpassMode<f64>>,
+    /// Cached tune and tone inputs for filter updates.
+    tune: f64,
+    tone: f64,
+}
+
+impl Drum {
+    /// Create new drum voice.
+    pub fn new(sample_rate: f64, voice: DrumVoice) -> Self {
+        let params = SvfParams {
+            sample_rate,
+            cutoff: 1000.0,
+            q: 1.0,
+            gain: 1.0,
+        };
+        let mut node = Self {
+            voice,
+            hash: 0,
+            rnd: Rnd::new(),
+            sample_rate,
+            time: 0.0,
+            phase: [0.0; 6],
+            hint: [0; 6],
+            bandpass: FixedSvf::new(BandpassMode::new(), &params),
+            highpass: FixedSvf::new(HighpassMode::new(), &params),
+            tune: f64::NAN,
+            tone: f64::NAN,
+        };
+        node.reset();
+        node
+    }
+
+    /// Drum voice.
+    pub fn voice(&self) -> DrumVoice {
+        self.voice
+    }
+
+    /// Update filters from `tune` ratio and `tone` in 0...1.
+    fn update_filters(&mut self, tune: f64, tone: f64) {
+        let nyquist = 0.45 * self.sample_rate;
+        let (bandpass, q, highpass) = match self.voice {
+            DrumVoice::Kick => (4000.0, 0.7, 1000.0 + 4000.0 * tone),
+            DrumVoice::Snare => (5000.0, 0.5, xerp(600.0, 3000.0, tone) * tune),
+            DrumVoice::Clap => (xerp(700.0, 1800.0, tone) * tune, 2.0, 300.0),
+            DrumVoice::LowTom | DrumVoice::MidTom | DrumVoice::HighTom => {
+                (xerp(1000.0, 4000.0, tone) * tune, 0.7, 200.0)
+            }
+            DrumVoice::ClosedHat | DrumVoice::OpenHat => {
+                (xerp(6000.0, 11000.0, tone), 1.0, xerp(5000.0, 8000.0, tone))
+            }
+            DrumVoice::Rim => (3000.0, 0.7, xerp(1000.0, 5000.0, tone)),
+            DrumVoice::Cowbell => (xerp(1500.0, 3500.0, tone) * tune, 1.5, 300.0),
+        };
+        self.bandpass.set_cutoff_q(min(bandpass, nyquist), q);
+        self.highpass.set_cutoff_q(min(highpass, nyquist), 0.7);
+    }
+
+    /// Advance oscillator `i` at frequency `f` Hz and return a sine wave.
+    #[inline]
+    fn sine(&mut self, i: usize, f: f64) -> f64 {
+        let value = sin(self.phase[i] * TAU);
+        self.phase[i] = (self.phase[i] + f / self.sample_rate).fract();
+        value
+    }
+
+    /// Advance oscillator `i` at frequency `f` Hz and return a band limited square wave.
+    #[inline]
+    fn square(&mut self, i: usize, f: f64) -> f64 {
+        let (value, hint) = SQUARE_TABLE.read(self.hint[i], f as f32, self.phase[i] as f32);
+        self.hint[i] = hint;
+        self.phase[i] = (self.phase[i] + f / self.sample_rate).fract();
+        value as f64
+    }
+
+    /// White noise in -1...1.
+    #[inline]
+    fn noise(&mut self) -> f64 {
+        self.rnd.f64() * 2.0 - 1.0
+    }
+
+    /// Tom pitch sweep and body.
+    fn tom(&mut self, f: f64, d: f64, tone: f64, velocity: f64) -> f64 {
+        let t = self.time;
+        let f = f * (1.0 + 0.3 * velocity * exp(-t / 0.03));
+        let body = self.sine(0, f) * exp(-t / d);
+        let noise = self.noise();
+        let noise = self.bandpass.filter_mono(noise) * exp(-t / 0.02);
+        tanh(1.5 * body) / tanh(1.5) + noise * tone * 0.5
+    }
+}
+
+impl AudioNode for Drum {
+    const ID: u64 = 100;
+    type Sample = f64;
+    type Inputs = U4;
+    type Outputs = U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.rnd = Rnd::from_u64(self.hash);
+        self.time = 0.0;
+        self.phase = [0.0; 6];
+        self.hint = [0; 6];
+        self.bandpass.reset();
+        self.highpass.reset();
+        self.tune = f64::NAN;
+        self.tone = f64::NAN;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+        self.bandpass.set_sample_rate(sample_rate);
+        self.highpass.set_sample_rate(sample_rate);
+        self.tune = f64::NAN;
+    }
+
+    fn set_hash(&mut self, hash: u64) {
+        self.hash = hash;
+        self.reset();
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let tune = exp2(input[0] / 12.0);
+        let d = self.voice.decay_time() * max(input[1], 0.01);
+        let tone = clamp01(input[2]);
+        let velocity = clamp01(input[3]);
+        if tune != self.tune || tone != self.tone {
+            self.tune = tune;
+            self.tone = tone;
+            self.update_filters(tune, tone);
+        }
+        let t = self.time;
+        let output = match self.voice {
+            DrumVoice::Kick => {
+                let f = 50.0 * tune * (1.0 + (1.0 + 2.0 * tone) * velocity * exp(-t / 0.006));
+                let body = self.sine(0, f) * exp(-t / d);
+                let drive = 1.0 + 2.0 * tone;
+                let noise = self.noise();
+                let click = self.highpass.filter_mono(noise) * exp(-t / 0.002);
+                tanh(drive * body) / tanh(drive) + click * tone * velocity
+            }
+            DrumVoice::Snare => {
+                let bend = 1.0 + 0.1 * exp(-t / 0.01);
+                let body = 0.6 * self.sine(0, 185.0 * tune * bend) * exp(-t / 0.07)
+                    + 0.4 * self.sine(1, 330.0 * tune * bend) * exp(-t / 0.05);
+                let noise = self.noise();
+                let noise = self.highpass.filter_mono(noise) * exp(-t / d);
+                body * (1.0 - 0.5 * tone) + noise * (0.4 + 0.6 * tone)
+            }
+            DrumVoice::Clap => {
+                let tail = 0.03;
+                // Sawtooth envelope from the latest burst.
+                let onset = CLAP_BURSTS.iter().filter(|&&b| b <= t).fold(0.0, |_, &b| b);
+                let mut envelope = exp(-(t - onset) / 0.004);
+                if t >= tail {
+                    envelope += 0.5 * exp(-(t - tail) / d);
+                }
+                let noise = self.noise();
+                let noise = self.highpass.filter_mono(noise);
+                3.0 * self.bandpass.filter_mono(noise) * envelope
+            }
+            DrumVoice::LowTom => self.tom(90.0 * tune, d, tone, velocity),
+            DrumVoice::MidTom => self.tom(135.0 * tune, d, tone, velocity),
+            DrumVoice::HighTom => self.tom(200.0 * tune, d, tone, velocity),
+            DrumVoice::ClosedHat | DrumVoice::OpenHat => {
+                let mut metal = 0.0;
+                for (i, f) in HAT_FREQUENCIES.iter().enumerate() {
+                    metal += self.square(i, f * tune);
+                }
+                let x = metal / 6.0 + 0.2 * self.noise();
+                let x = self.bandpass.filter_mono(x);
+                3.0 * self.highpass.filter_mono(x) * exp(-t / d)
+            }
+            DrumVoice::Rim => {
+                let body = 0.5 * self.sine(0, 455.0 * tune) + 0.5 * self.sine(1, 1667.0 * tune);
+                let noise = self.noise();
+                let click = self.highpass.filter_mono(noise) * exp(-t / 0.001);
+                body * exp(-t / d) + click * tone
+            }
+            DrumVoice::Cowbell => {
+                let x = self.square(0, 540.0 * tune) + self.square(1, 800.0 * tune);
+                let envelope = 0.6 * exp(-t / 0.015) + 0.4 * exp(-t / d);
+                1.5 * self.bandpass.filter_mono(x) * envelope
+            }
+        };
+        self.time += 1.0 / self.sample_rate;
+        [output * velocity].into()
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output
+    }
+}
+
+/// Sound 006. Analog style drum voice, mono. Plays one hit.
+/// - Input 0: tune in semitones relative to the default pitch (for example, 0.0)
+/// - Input 1: decay time multiplier (for example, 1.0)
+/// - Input 2: tone in 0...1 (for example, 0.5)
+/// - Input 3: velocity in 0...1
+/// - Output 0: drum sound
+pub fn drum(voice: DrumVoice) -> An<Drum> {
+    An(Drum::new(DEFAULT_SR, voice))
+}
+
+/// Sound 007. Analog style drum hit with fixed parameters, mono.
+/// `tune` is in semitones relative to the default pitch, `decay` is a decay time multiplier,
+/// `tone` is in 0...1 and `velocity` is in 0...1.
+pub fn drum_hit(
+    voice: DrumVoice,
+    tune: f64,
+    decay: f64,
+    tone: f64,
+    velocity: f64,
+) -> An<impl AudioNode<Sample = f64, Inputs = U0, Outputs = U1>> {
+    dc((tune, decay, tone, velocity)) >> drum(voice)
+}
+
+/// Fade out time in seconds for choked drum voices.
+const CHOKE_TIME: f64 = 0.005;
+
+/// Plays drum voices on a `Sequencer64`.
+/// Voices in the same choke group cut each other off,
+/// so a closed hi-hat silences an open hi-hat that is still ringing.
+/// Voices are assumed to be pushed in order of start time.
+#[derive(Clone, Default)]
+pub struct DrumKit {
+    /// Choke group, event ID, start time and end time of sounding voices.
+    ringing: Vec<(usize, EventId, f64, f64)>,
+}
+
+impl DrumKit {
+    /// Create new drum kit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a drum hit to `sequencer` at `time` seconds.
+    /// `tune` is in semitones relative to the default pitch, `decay` is a decay time multiplier,
+    /// `tone` is in 0...1 and `velocity` is in 0...1.
+    /// Returns the ID of the event.
+    #[allow(clippy::too_many_arguments)]
+    pub fn push(
+        &mut self,
+        sequencer: &mut Sequencer64,
+        time: f64,
+        voice: DrumVoice,
+        tune: f64,
+        decay: f64,
+        tone: f64,
+        velocity: f64,
+    ) -> EventId {
+        let end_time = time + voice.duration(decay);
+        let id = sequencer.push(
+            time,
+            end_time,
+            Fade::Smooth,
+            0.0,
+            CHOKE_TIME,
+            Box::new(drum_hit(voice, tune, decay, tone, velocity)),
+        );
+        // Forget voices that have finished.
+        self.ringing.retain(|&(_, _, _, end)| end > time);
+        if let Some(group) = voice.choke_group() {
+            for &(other_group, other_id, start, end) in self.ringing.iter() {
+                if other_group == group && start < time && end > time + CHOKE_TIME {
+                    sequencer.edit(other_id, time + CHOKE_TIME, CHOKE_TIME);
+                }
+            }
+            self.ringing.retain(|&(g, _, _, _)| g != group);
+            self.ringing.push((group, id, time, end_time));
+        }
+        id
+    }
+}
+
+/// Sound 008. Wind, mono. Different `seed` values produce different gust patterns.
+/// - Input 0: gust intensity in 0...1
+/// - Output 0: wind sound
+pub fn wind(seed: i64) -> An<impl AudioNode<Sample = f64, Inputs = U1, Outputs = U1>> {
+    // Gust envelope is intensity modulated by slow fractal noise.
+    let gust = (pass() | lfo(move |t| fractal_noise(seed, 4, 0.5, t * 0.25)))
+        >> map(|x: &Frame<f64, U2>| clamp01(x[0]) * clamp01(0.7 + 0.6 * x[1]));
+    let howl =
+        (pink() | map(|x: &Frame<f64, U1>| xerp(150.0, 1500.0, x[0])) | dc(1.5)) >> bandpass();
+    let whistle =
+        (white() | map(|x: &Frame<f64, U1>| xerp(500.0, 2000.0, x[0])) | dc(30.0)) >> bandpass();
+    gust >> split::<U2>() >> ((howl & whistle * 0.5) * pass())
+}
+
+/// Random impulses with amplitudes in 0...1.
+/// - Input 0: probability of an impulse per sample
+/// - Output 0: impulses
+fn dust() -> An<impl AudioNode<Sample = f64, Inputs = U1, Outputs = U1>> {
+    (white() | white() | pass())
+        >> map(|x: &Frame<f64, U3>| {
+            if x[0] > 1.0 - 2.0 * x[2] {
+                0.5 + 0.5 * x[1]
+            } else {
+                0.0
+            }
+        })
+}
+
+/// Sound 009. Rain, mono.
+/// - Input 0: density of drops in 0...1
+/// - Input 1: drop size in 0...1
+/// - Output 0: rain sound
+pub fn rain() -> An<impl AudioNode<Sample = f64, Inputs = U2, Outputs = U1>> {
+    // Drops are random impulses exciting a resonance. Larger drops are louder and lower in pitch.
+    let drops = |ratio: f64| {
+        ((map(|x: &Frame<f64, U1>| 0.005 * clamp01(x[0])) >> dust()) | pass())
+            >> map(move |x: &Frame<f64, U2>| {
+                let size = clamp01(x[1]);
+                (x[0] * (0.3 + size), ratio * xerp(4000.0, 800.0, size))
+            })
+            >> (pass() | pass() | dc(5.0))
+            >> bandpass()
+    };
+    // Distant drops blend into a wash of noise.
+    let wash = (pink() >> lowpass_hz(5000.0, 0.7))
+        * map(|x: &Frame<f64, U2>| 0.2 * clamp01(x[0]) * (0.5 + 0.5 * clamp01(x[1])));
+    (drops(1.0) & drops(1.4) & drops(2.1)) * 3.0 & wash
+}
+
+/// Sound 010. Fire, mono. Different `seed` values produce different flicker patterns.
+/// - Input 0: crackle intensity in 0...1
+/// - Output 0: fire sound
+pub fn fire(seed: i64) -> An<impl AudioNode<Sample = f64, Inputs = U1, Outputs = U1>> {
+    let flicker = move |rate: f64| lfo(move |t| 0.6 + 0.4 * fractal_noise(seed, 3, 0.5, t * rate));
+    let roar = (brown() >> lowpass_hz(250.0, 0.7)) * flicker(1.5) * 0.3;
+    let hiss = (pink() >> bandpass_hz(3000.0, 0.7)) * flicker(4.0) * 0.1;
+    // Crackles are random impulses of random amplitude.
+    let crackle = map(|x: &Frame<f64, U1>| 0.001 * clamp01(x[0]))
+        >> dust()
+        >> (bandpass_hz(2500.0, 2.0) * 2.0 & lowpass_hz(600.0, 2.0) * 0.5);
+    ((roar + hiss) | crackle) >> (pass() + pass())
+}
+
+/// Sound 011. Running water, mono. Different `seed` values produce different surges.
+/// - Input 0: flow in 0...1
+/// - Output 0: water sound
+pub fn water(seed: i64) -> An<impl AudioNode<Sample = f64, Inputs = U1, Outputs = U1>> {
+    let surge = (pass() | lfo(move |t| fractal_noise(seed, 3, 0.5, t * 0.5)))
+        >> map(|x: &Frame<f64, U2>| clamp01(x[0]) * clamp01(0.8 + 0.4 * x[1]));
+    // Bubbles are random impulses exciting a resonance at a randomly wandering frequency.
+    let bubbles = (map(|x: &Frame<f64, U1>| 0.005 * x[0]) >> dust())
+        | ((white() | dc(40.0))
+            >> hold(0.5)
+            >> map(|x: &Frame<f64, U1>| xerp(300.0, 2500.0, 0.5 + 0.5 * x[0])))
+        | dc(20.0);
+    let bubbles = bubbles >> bandpass() * 5.0;
+    let rush = (pink() >> bandpass_hz(1200.0, 0.5)) * 0.2;
+    surge >> split::<U2>() >> ((bubbles | rush) >> (pass() + pass())) * pass()
+}
+
+/// Sound 012. Four cylinder four-stroke combustion engine, mono.
+/// - Input 0: engine speed in RPM (for example, 3000.0)
+/// - Output 0: engine sound
+pub fn engine() -> An<impl AudioNode<Sample = f64, Inputs = U1, Outputs = U1>> {
+    // Four cylinders fire twice per revolution.
+    let pulses = map(|x: &Frame<f64, U1>| max(x[0], 0.0) / 30.0)
+        >> saw()
+        >> map(|x: &Frame<f64, U1>| exp(6.0 * (x[0] - 1.0)));
+    let combustion = pulses >> split::<U2>() >> (pass() + pass() * (white() * 0.5));
+    // Crankshaft imbalance rumbles once per revolution.
+    let rumble = map(|x: &Frame<f64, U1>| max(x[0], 0.0) / 60.0) >> sine() * 0.3;
+    let exhaust = map(|x: &Frame<f64, U1>| 200.0 + 0.3 * max(x[0], 0.0));
+    split::<U2>() >> ((combustion & rumble) | exhaust | dc(1.0)) >> lowpass() >> dcblock()
+}
@@ -0,0 +1,269 @@
+//! Channel vocoder.
+
+use super::audionode::*;
+use super::follow::*;
+use super::math::*;
+use super::signal::*;
+use super::svf::*;
+use super::*;
+use funutd::*;
+use numeric_array::typenum::*;
+
+/// Minimum number of vocoder bands.
+pub const MIN_VOCODER_BANDS: usize = 8;
+
+/// Maximum number of vocoder bands.
+pub const MAX_VOCODER_BANDS: usize = 64;
+
+/// Cutoff in Hz of the highpass filter that detects unvoiced modulator sounds.
+const UNVOICED_CUTOFF: f64 = 5000.0;
+
+/// Vocoder band: two cascaded bandpass filters for modulator and carrier each,
+/// and an envelope follower.
+#[derive(Clone)]
+struct VocoderBand {
+    frequency: f64,
+    modulator: [FixedSvf<f64, f64, BandpassMode<f64>>; 2],
+    carrier: [FixedSvf<f64, f64, BandpassMode<f64>>; 2],
+    envelope: Follow<f64, f64>,
+    /// Emphasis gain.
+    gain: f64,
+}
+
+/// Channel vocoder with 8 to 64 logarithmically spaced bands.
+/// The spectral envelope of the modulator is imposed on the carrier.
+/// Setting: (formant shift ratio, unvoiced noise amount in 0...1, emphasis in 0...1).
+/// - Input 0: modulator (for example, voice)
+/// - Input 1: carrier (for example, a bright synth sound)
+/// - Output 0: vocoded signal
+#[derive(Clone)]
+pub struct Vocoder<T: Real> {
+    bands: Vec<VocoderBand>,
+    /// Lowest and highest band center frequencies in Hz.
+    range: (f64, f64),
+    q: f64,
+    /// Output gain that keeps level roughly independent of the number of bands.
+    makeup: f64,
+    shift: f64,
+    noise: f64,
+    emphasis: f64,
+    response_time: f64,
+    unvoiced: FixedSvf<f64, f64, HighpassMode<f64>>,
+    /// Envelopes of the highpassed modulator and the full modulator.
+    detector: [Follow<f64, f64>; 2],
+    rnd: Rnd,
+    hash: u64,
+    sample_rate: f64,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Real> Vocoder<T> {
+    /// Create new vocoder with `bands` bands (8 to 64) spaced logarithmically
+    /// between `low` and `high` Hz.
+    pub fn new(sample_rate: f64, bands: usize, low: f64, high: f64) -> Self {
+        let bands = bands.clamp(MIN_VOCODER_BANDS, MAX_VOCODER_BANDS);
+        let low = max(low, 10.0);
+        let high = max(high, low * 2.0);
+        // Neighboring bands cross over at their half power points.
+        let ratio = pow(high / low, 1.0 / (bands - 1) as f64);
+        let q = sqrt(ratio) / (ratio - 1.0);
+        let params = SvfParams {
+            sample_rate,
+            cutoff: low,
+            q,
+            gain: 1.0,
+        };
+        let bandpass = || FixedSvf::new(BandpassMode::new(), &params);
+        let bands = (0..bands)
+            .map(|i| VocoderBand {
+                frequency: low * pow(ratio, i as f64),
+                modulator: [bandpass(), bandpass()],
+                carrier: [bandpass(), bandpass()],
+                envelope: Follow::new(sample_rate, 0.01),
+                gain: 1.0,
+            })
+            .collect::<Vec<_>>();
+        let makeup = 3.0 * sqrt(bands.len() as f64);
+        let mut node = Self {
+            bands,
+            range: (low, high),
+            q,
+            makeup,
+            shift: 1.0,
+            noise: 0.0,
+            emphasis: 0.0,
+            response_time: 0.01,
+            unvoiced: FixedSvf::new(
+                HighpassMode::new(),
+                &SvfParams {
+                    sample_rate,
+                    cutoff: UNVOICED_CUTOFF,
+                    q: 0.7,
+                    gain: 1.0,
+                },
+            ),
+            detector: [
+                Follow::new(sample_rate, 0.01),
+                Follow::new(sample_rate, 0.01),
+            ],
+            rnd: Rnd::new(),
+            hash: 0,
+            sample_rate,
+            _marker: std::marker::PhantomData,
+        };
+        node.update_filters();
+        node.set_emphasis(0.5);
+        node.reset();
+        node
+    }
+
+    /// Number of bands.
+    pub fn bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Band center frequencies in Hz.
+    pub fn frequencies(&self) -> Vec<f64> {
+        self.bands.iter().map(|band| band.frequency).collect()
+    }
+
+    /// Set formant shift as a frequency ratio (0.25 to 4). Each modulator band
+    /// controls the carrier band at its frequency multiplied by the ratio.
+    pub fn set_formant_shift(&mut self, shift: f64) {
+        let shift = clamp(0.25, 4.0, shift);
+        if shift != self.shift {
+            self.shift = shift;
+            self.update_carrier();
+        }
+    }
+
+    /// Set amount of noise in 0...1 injected into the carrier during unvoiced
+    /// (sibilant) modulator sounds.
+    pub fn set_noise(&mut self, noise: f64) {
+        self.noise = clamp01(noise);
+    }
+
+    /// Set high frequency emphasis in 0...1. At 1, band gains rise 6 dB per octave
+    /// around the geometric center of the band range.
+    pub fn set_emphasis(&mut self, emphasis: f64) {
+        self.emphasis = clamp01(emphasis);
+        let center = sqrt(self.range.0 * self.range.1);
+        for band in self.bands.iter_mut() {
+            band.gain = pow(band.frequency / center, self.emphasis);
+        }
+    }
+
+    /// Set halfway response time of band envelope followers in seconds.
+    pub fn set_response_time(&mut self, response_time: f64) {
+        self.response_time = max(0.0, response_time);
+        for band in self.bands.iter_mut() {
+            band.envelope.set_response_time(self.response_time);
+        }
+        for detector in self.detector.iter_mut() {
+            detector.set_response_time(self.response_time);
+        }
+    }
+
+    fn update_filters(&mut self) {
+        let nyquist = 0.45 * self.sample_rate;
+        for band in self.bands.iter_mut() {
+            for filter in band.modulator.iter_mut() {
+                filter.set_cutoff_q(min(band.frequency, nyquist), self.q);
+            }
+        }
+        self.update_carrier();
+    }
+
+    fn update_carrier(&mut self) {
+        let nyquist = 0.45 * self.sample_rate;
+        for band in self.bands.iter_mut() {
+            for filter in band.carrier.iter_mut() {
+                filter.set_cutoff_q(min(band.frequency * self.shift, nyquist), self.q);
+            }
+        }
+    }
+}
+
+impl<T: Real> AudioNode for Vocoder<T> {
+    const ID: u64 = 110;
+    type Sample = T;
+    type Inputs = U2;
+    type Outputs = U1;
+    type Setting = (f64, f64, f64);
+
+    fn set(&mut self, (shift, noise, emphasis): Self::Setting) {
+        self.set_formant_shift(shift);
+        self.set_noise(noise);
+        self.set_emphasis(emphasis);
+    }
+
+    fn reset(&mut self) {
+        for band in self.bands.iter_mut() {
+            for filter in band.modulator.iter_mut().chain(band.carrier.iter_mut()) {
+                filter.reset();
+            }
+            band.envelope.reset();
+        }
+        self.unvoiced.reset();
+        for detector in self.detector.iter_mut() {
+            detector.reset();
+        }
+        self.rnd = Rnd::from_u64(self.hash);
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            for band in self.bands.iter_mut() {
+                for filter in band.modulator.iter_mut().chain(band.carrier.iter_mut()) {
+                    filter.set_sample_rate(sample_rate);
+                }
+                band.envelope.set_sample_rate(sample_rate);
+            }
+            self.unvoiced.set_sample_rate(sample_rate);
+            for detector in self.detector.iter_mut() {
+                detector.set_sample_rate(sample_rate);
+            }
+            self.update_filters();
+            self.reset();
+        }
+    }
+
+    fn set_hash(&mut self, hash: u64) {
+        self.hash = hash;
+        self.reset();
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let modulator = input[0].to_f64();
+        let mut carrier = input[1].to_f64();
+        if self.noise > 0.0 {
+            // Inject noise in proportion to the share of high frequencies in the modulator.
+            let high = self.unvoiced.filter_mono(modulator);
+            let high = self.detector[0].filter_mono(abs(high));
+            let full = self.detector[1].filter_mono(abs(modulator));
+            let unvoiced = clamp01(high / max(full, 1.0e-6));
+            carrier += self.noise * unvoiced * (self.rnd.f64() * 2.0 - 1.0);
+        }
+        // The bandpass filters have a peak gain of Q.
+        let k = 1.0 / self.q;
+        let mut output = 0.0;
+        for band in self.bands.iter_mut() {
+            let x = band.modulator[0].filter_mono(modulator) * k;
+            let x = band.modulator[1].filter_mono(x) * k;
+            let envelope = band.envelope.filter_mono(abs(x));
+            let y = band.carrier[0].filter_mono(carrier) * k;
+            let y = band.carrier[1].filter_mono(y) * k;
+            output += envelope * y * band.gain;
+        }
+        [T::from_f64(output * self.makeup)].into()
+    }
+
+    fn route(&mut self, input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        Routing::Arbitrary(0.0).propagate(input, 1)
+    }
+}
@@ -3,6 +3,7 @@
 use super::audionode::*;
 use super::audiounit::*;
 use super::combinator::*;
+use super::loudness::*;
 use super::math::*;
 use super::*;
 use duplicate::duplicate_item;
@@ -367,6 +368,28 @@ impl Wave48 {
         peak
     }
 
+    /// Measure the loudness of the wave according to EBU R128.
+    /// All channels are weighted equally.
+    ///
+    /// ### Example
+    /// ```
+    /// use fundsp::hacker::*;
+    /// let wave = Wave64::render(48000.0, 5.0, &mut (sine_hz(1000.0) * 0.1));
+    /// let loudness = wave.loudness();
+    /// assert!((loudness.integrated - -23.0).abs() < 0.1);
+    /// ```
+    pub fn loudness(&self) -> Loudness {
+        let mut meter = LoudnessMeter::new(self.sample_rate(), max(1, self.channels()));
+        let mut frame = vec![0.0; self.channels()];
+        for i in 0..self.len() {
+            for (channel, x) in frame.iter_mut().enumerate() {
+                *x = self.at(channel, i) as f64;
+            }
+            meter.tick(&frame);
+        }
+        Loudness::from_meter(&meter)
+    }
+
     /// Scales the wave to the range -1..1. Does nothing if the wave is empty.
     ///
     /// ### Example
@@ -389,6 +412,29 @@ impl Wave48 {
         }
     }
 
+    /// Scales the wave to the target integrated loudness `target` (in LUFS) according to EBU R128.
+    /// Does nothing if the wave is silent. Note that the result may exceed the range -1...1.
+    ///
+    /// ### Example
+    /// ```
+    /// use fundsp::hacker::*;
+    /// let mut wave = Wave64::render(48000.0, 5.0, &mut (sine_hz(1000.0) * 0.1));
+    /// wave.normalize_loudness(-16.0);
+    /// assert!((wave.loudness().integrated - -16.0).abs() < 0.1);
+    /// ```
+    pub fn normalize_loudness(&mut self, target: f64) {
+        let integrated = self.loudness().integrated;
+        if !integrated.is_finite() {
+            return;
+        }
+        let z = db_amp(target - integrated) as f48;
+        for channel in 0..self.channels() {
+            for i in 0..self.len() {
+                self.set(channel, i, self.at(channel, i) * z);
+            }
+        }
+    }
+
     /// Applies a fade-in envelope to the wave with a duration of `time` seconds.
     /// The duration may not exceed the duration of the wave.
     ///
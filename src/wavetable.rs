@@ -3,12 +3,14 @@
 use super::audionode::*;
 use super::math::*;
 use super::signal::*;
+use super::wave::*;
 use super::*;
 use num_complex::Complex32;
 use realfft::*;
 use rustfft::algorithm::Radix4;
 use rustfft::Fft;
 use rustfft::FftDirection;
+use std::sync::Arc;
 
 /// Interpolate between `a1` and `a2` taking previous (`a0`) and next (`a3`) points into account.
 /// Employs an optimal 4-point, 4th order interpolating polynomial for 4x oversampled signals.
@@ -68,8 +70,7 @@ where
     let fft = Radix4::new(length, FftDirection::Inverse);
     fft.process(&mut a);
 
-    let z = 1.0 / sqrt(length as f32);
-    a.iter().map(|x| x.im * z).collect()
+    a.iter().map(|x| x.im).collect()
 }
 
 #[derive(Clone)]
@@ -91,6 +92,24 @@ impl Wavetable {
         phase: &P,
         amplitude: &A,
     ) -> Wavetable
+    where
+        P: Fn(u32) -> f64,
+        A: Fn(f64, u32) -> f64,
+    {
+        let mut wavetable =
+            Self::new_unnormalized(min_pitch, max_pitch, tables_per_octave, phase, amplitude);
+        wavetable.normalize(wavetable.amplitude());
+        wavetable
+    }
+
+    /// Create new wavetable without normalizing it.
+    fn new_unnormalized<P, A>(
+        min_pitch: f64,
+        max_pitch: f64,
+        tables_per_octave: f64,
+        phase: &P,
+        amplitude: &A,
+    ) -> Wavetable
     where
         P: Fn(u32) -> f64,
         A: Fn(f64, u32) -> f64,
@@ -98,38 +117,60 @@ impl Wavetable {
         let mut table: Vec<(f32, Vec<f32>)> = vec![];
         let mut p = min_pitch;
         let p_factor = pow(2.0, 1.0 / tables_per_octave);
-        let mut max_amplitude = 0.0;
         //let mut total_size = 0;
         while p <= max_pitch {
             let wave = make_wave(p, phase, amplitude);
-            max_amplitude = wave.iter().fold(max_amplitude, |acc, &x| max(acc, abs(x)));
             //total_size += wave.len();
             table.push((p as f32, wave));
             p *= p_factor;
         }
-        if max_amplitude > 0.0 {
-            let z = 1.0 / max_amplitude;
-            table.iter_mut().for_each(|t| {
-                t.1.iter_mut().for_each(|x| {
-                    *x *= z;
-                })
-            });
-        }
         //println!(
-        //    "Wavetable transpositions {} max amplitude {} total bytes {}",
+        //    "Wavetable transpositions {} total bytes {}",
         //    table.len(),
-        //    max_amplitude,
         //    total_size * 4
         //);
         Wavetable { table }
     }
 
+    /// Peak amplitude of the wavetable.
+    fn amplitude(&self) -> f32 {
+        self.table.iter().fold(0.0, |acc, t| {
+            t.1.iter().fold(acc, |acc, &x| max(acc, abs(x)))
+        })
+    }
+
+    /// Divide wavetable by `amplitude`, if it is positive.
+    fn normalize(&mut self, amplitude: f32) {
+        if amplitude > 0.0 {
+            let z = 1.0 / amplitude;
+            self.table.iter_mut().for_each(|t| {
+                t.1.iter_mut().for_each(|x| {
+                    *x *= z;
+                })
+            });
+        }
+    }
+
     /// Create new wavetable from a single cycle wave. `min_pitch` and `max_pitch` are the minimum
     /// and maximum base frequencies in Hz (for example, 20.0 and 20_000.0).
     /// `tables_per_octave` is the number of wavetables per octave
     /// (for example, 4.0). The overall scale of numbers in `wave` is ignored;
     /// the wavetable is normalized to -1...1.
     pub fn from_wave(min_pitch: f64, max_pitch: f64, tables_per_octave: f64, wave: &[f32]) -> Self {
+        let mut wavetable =
+            Self::from_wave_unnormalized(min_pitch, max_pitch, tables_per_octave, wave);
+        wavetable.normalize(wavetable.amplitude());
+        wavetable
+    }
+
+    /// Create new wavetable from a single cycle wave without normalizing it.
+    /// The wave is band-limited by removing partials above the Nyquist frequency of each table.
+    fn from_wave_unnormalized(
+        min_pitch: f64,
+        max_pitch: f64,
+        tables_per_octave: f64,
+        wave: &[f32],
+    ) -> Self {
         let mut planner = RealFftPlanner::<f32>::new();
         let r2c = planner.plan_fft_forward(wave.len());
         let mut spectrum = r2c.make_output_vec();
@@ -137,7 +178,8 @@ impl Wavetable {
         r2c.process(&mut tmp_wave, &mut spectrum).unwrap();
         let phase = |i: u32| {
             if (i as usize) < spectrum.len() {
-                spectrum[i as usize].arg() as f64 / TAU
+                // The wave is reconstructed from sines, so shift cosine phases by a quarter cycle.
+                spectrum[i as usize].arg() as f64 / TAU + 0.25
             } else {
                 0.0
             }
@@ -149,7 +191,23 @@ impl Wavetable {
                 0.0
             }
         };
-        Wavetable::new(min_pitch, max_pitch, tables_per_octave, &phase, &amplitude)
+        Wavetable::new_unnormalized(min_pitch, max_pitch, tables_per_octave, &phase, &amplitude)
+    }
+
+    /// Create new wavetable from a single cycle wave stored in `channel` of `wave`.
+    /// The whole channel is taken to be one cycle. `min_pitch` and `max_pitch` are the minimum
+    /// and maximum base frequencies in Hz (for example, 20.0 and 20_000.0).
+    /// `tables_per_octave` is the number of wavetables per octave (for example, 4.0).
+    /// The wavetable is normalized to -1...1.
+    pub fn from_wave64(
+        min_pitch: f64,
+        max_pitch: f64,
+        tables_per_octave: f64,
+        wave: &Wave64,
+        channel: usize,
+    ) -> Self {
+        let cycle: Vec<f32> = wave.channel(channel).iter().map(|&x| x as f32).collect();
+        Self::from_wave(min_pitch, max_pitch, tables_per_octave, &cycle)
     }
 
     /// Read wave at the given phase (in 0...1).
@@ -167,9 +225,10 @@ impl Wavetable {
         optimal4x44(table[i0], table[i1], table[i2], table[i3], w)
     }
 
-    /// Read wavetable.
+    /// Find the transposition table for `frequency`, starting the search from `table_hint`.
+    /// Returns the table index and the interpolation weight for `read_table`.
     #[inline]
-    pub fn read(&self, table_hint: usize, frequency: f32, phase: f32) -> (f32, usize) {
+    pub fn find_table(&self, table_hint: usize, frequency: f32) -> (usize, f32) {
         let table =
             if frequency >= self.table[table_hint].0 && frequency <= self.table[table_hint + 1].0 {
                 table_hint
@@ -190,13 +249,129 @@ impl Wavetable {
                 }
                 i0
             };
-        let w = delerp(self.table[table].0, self.table[table + 1].0, frequency);
         (
-            // Note the different table index. We can use `table + 1` up to its designated pitch.
-            (1.0 - w) * self.at(table + 1, phase) + w * self.at(table + 2, phase),
             table,
+            delerp(self.table[table].0, self.table[table + 1].0, frequency),
         )
     }
+
+    /// Read wave from transposition `table` with interpolation weight `w` (see `find_table`).
+    #[inline]
+    pub fn read_table(&self, table: usize, w: f32, phase: f32) -> f32 {
+        // Note the different table index. We can use `table + 1` up to its designated pitch.
+        (1.0 - w) * self.at(table + 1, phase) + w * self.at(table + 2, phase)
+    }
+
+    /// Read wavetable.
+    #[inline]
+    pub fn read(&self, table_hint: usize, frequency: f32, phase: f32) -> (f32, usize) {
+        let (table, w) = self.find_table(table_hint, frequency);
+        (self.read_table(table, w, phase), table)
+    }
+}
+
+/// Multi-frame wavetable for morphing oscillators.
+/// All frames share the same transposition tables.
+#[derive(Clone)]
+pub struct MorphTable {
+    frames: Vec<Wavetable>,
+}
+
+impl MorphTable {
+    /// Create new morphing wavetable from `frames`. The frames must have been created
+    /// with the same minimum and maximum pitches and tables per octave.
+    /// Panics if `frames` is empty.
+    pub fn new(frames: Vec<Wavetable>) -> Self {
+        assert!(!frames.is_empty(), "MorphTable needs at least one frame");
+        for frame in frames.iter() {
+            assert!(frame.table.len() == frames[0].table.len());
+        }
+        Self { frames }
+    }
+
+    /// Create new morphing wavetable from single cycle waves. `min_pitch` and `max_pitch` are the minimum
+    /// and maximum base frequencies in Hz (for example, 20.0 and 20_000.0).
+    /// `tables_per_octave` is the number of wavetables per octave (for example, 4.0).
+    /// Waves can have different lengths. The frames are normalized together to -1...1.
+    /// Panics if `waves` is empty.
+    pub fn from_waves(
+        min_pitch: f64,
+        max_pitch: f64,
+        tables_per_octave: f64,
+        waves: &[&[f32]],
+    ) -> Self {
+        let mut frames: Vec<Wavetable> = waves
+            .iter()
+            .map(|wave| {
+                Wavetable::from_wave_unnormalized(min_pitch, max_pitch, tables_per_octave, wave)
+            })
+            .collect();
+        let amplitude = frames.iter().fold(0.0, |acc, x| max(acc, x.amplitude()));
+        frames.iter_mut().for_each(|x| x.normalize(amplitude));
+        Self::new(frames)
+    }
+
+    /// Create new morphing wavetable from `channel` of `wave`, which contains consecutive
+    /// single cycle frames of `frame_length` samples each. Serum style wavetables
+    /// use a frame length of 2048 samples. A partial frame at the end is ignored.
+    /// `min_pitch` and `max_pitch` are the minimum and maximum base frequencies in Hz
+    /// (for example, 20.0 and 20_000.0).
+    /// `tables_per_octave` is the number of wavetables per octave (for example, 4.0).
+    /// The frames are normalized together to -1...1.
+    /// Panics if `frame_length` is zero or the channel does not contain a complete frame.
+    pub fn from_wave64(
+        min_pitch: f64,
+        max_pitch: f64,
+        tables_per_octave: f64,
+        wave: &Wave64,
+        channel: usize,
+        frame_length: usize,
+    ) -> Self {
+        assert!(frame_length > 0, "frame length must be positive");
+        assert!(
+            wave.len() >= frame_length,
+            "wave must contain at least one complete frame"
+        );
+        let samples: Vec<f32> = wave.channel(channel).iter().map(|&x| x as f32).collect();
+        let waves: Vec<&[f32]> = samples.chunks_exact(frame_length).collect();
+        Self::from_waves(min_pitch, max_pitch, tables_per_octave, &waves)
+    }
+
+    /// Number of frames.
+    #[inline]
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Access frame `i`.
+    #[inline]
+    pub fn frame(&self, i: usize) -> &Wavetable {
+        &self.frames[i]
+    }
+
+    /// Read wavetable at morph `position` in 0...1, interpolating between adjacent frames.
+    #[inline]
+    pub fn read(
+        &self,
+        table_hint: usize,
+        frequency: f32,
+        position: f32,
+        phase: f32,
+    ) -> (f32, usize) {
+        let (table, w) = self.frames[0].find_table(table_hint, frequency);
+        let x = clamp01(position) * (self.frames.len() - 1) as f32;
+        let i = min(x as usize, self.frames.len() - 1);
+        let output = self.frames[i].read_table(table, w, phase);
+        let frac = x - i as f32;
+        if frac > 0.0 {
+            (
+                output + (self.frames[i + 1].read_table(table, w, phase) - output) * frac,
+                table,
+            )
+        } else {
+            (output, table)
+        }
+    }
 }
 
 /// Bandlimited wavetable synthesizer with `N` outputs (1 or 2).
@@ -381,6 +556,308 @@ where
     }
 }
 
+/// Bandlimited morphing wavetable synthesizer.
+/// - Input 0: frequency in Hz.
+/// - Input 1: morph position in 0...1.
+/// - Output 0: audio.
+#[derive(Clone)]
+pub struct MorphSynth<T: Float> {
+    table: Arc<MorphTable>,
+    /// Phase in 0...1.
+    phase: f32,
+    /// Initial phase in 0...1, seeded via pseudorandom phase system.
+    initial_phase: f32,
+    /// Previously used transposition table.
+    table_hint: usize,
+    sample_rate: f32,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Float> MorphSynth<T> {
+    pub fn new(sample_rate: f64, table: &Arc<MorphTable>) -> Self {
+        MorphSynth {
+            table: table.clone(),
+            phase: 0.0,
+            initial_phase: 0.0,
+            table_hint: 0,
+            sample_rate: sample_rate as f32,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Float> AudioNode for MorphSynth<T> {
+    const ID: u64 = 95;
+    type Sample = T;
+    type Inputs = numeric_array::typenum::U2;
+    type Outputs = numeric_array::typenum::U1;
+    type Setting = ();
+
+    fn reset(&mut self) {
+        self.phase = self.initial_phase;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate as f32;
+    }
+
+    fn set_hash(&mut self, hash: u64) {
+        self.initial_phase = super::hacker::rnd(hash as i64) as f32;
+        self.phase = self.initial_phase;
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let frequency = input[0].to_f32();
+        let delta = frequency / self.sample_rate;
+        self.phase += delta;
+        self.phase -= floor(self.phase);
+        let (output, hint) = self.table.read(
+            self.table_hint,
+            abs(frequency),
+            input[1].to_f32(),
+            self.phase,
+        );
+        self.table_hint = hint;
+        [convert(output)].into()
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output
+    }
+}
+
+/// Maximum number of voices in a unison oscillator.
+pub const MAX_UNISON_VOICES: usize = 16;
+
+/// Bandlimited unison wavetable oscillator. Renders detuned copies (voices)
+/// of a wavetable spread across the stereo field. The number of inputs `N` is 1, 2 or 3.
+/// Setting: (detune, mix).
+/// - Input 0: frequency in Hz.
+/// - Input 1 (optional): detune of the outermost voices in semitones.
+/// - Input 2 (optional): mix in 0...1 between center (0) and side (1) voices.
+/// - Output 0: left output.
+/// - Output 1: right output.
+#[derive(Clone)]
+pub struct Unison<T: Float, N: Size<T>> {
+    table: Arc<Wavetable>,
+    /// Detune of the outermost voices in semitones.
+    detune: f32,
+    /// Exponent applied to voice positions. Values above 1 gather voices near the center.
+    curve: f32,
+    /// Stereo spread in 0...1.
+    spread: f32,
+    /// Mix between center and side voices in 0...1.
+    mix: f32,
+    /// Phases of voices in 0...1.
+    phase: Vec<f32>,
+    /// Voice positions in -1...1 after the detune curve.
+    position: Vec<f32>,
+    /// Whether a voice is a center voice.
+    center: Vec<bool>,
+    /// Left and right pan weights of voices.
+    pan: Vec<(f32, f32)>,
+    /// Previously used transposition tables.
+    table_hint: Vec<usize>,
+    initial_phase: Option<f32>,
+    hash: u64,
+    sample_rate: f32,
+    _marker: std::marker::PhantomData<(T, N)>,
+}
+
+impl<T: Float, N: Size<T>> Unison<T, N> {
+    /// Create new unison oscillator with `voices` voices (1 to `MAX_UNISON_VOICES`).
+    /// `detune` is the detune of the outermost voices in semitones and
+    /// `mix` is the mix in 0...1 between center (0) and side (1) voices.
+    /// Voice state is allocated for `MAX_UNISON_VOICES` voices,
+    /// so changing voice settings later does not allocate.
+    pub fn new(
+        sample_rate: f64,
+        table: &Arc<Wavetable>,
+        voices: usize,
+        detune: f32,
+        mix: f32,
+    ) -> Self {
+        assert!(N::USIZE >= 1 && N::USIZE <= 3);
+        let mut node = Self {
+            table: table.clone(),
+            detune,
+            curve: 1.0,
+            spread: 1.0,
+            mix,
+            phase: Vec::with_capacity(MAX_UNISON_VOICES),
+            position: Vec::with_capacity(MAX_UNISON_VOICES),
+            center: Vec::with_capacity(MAX_UNISON_VOICES),
+            pan: Vec::with_capacity(MAX_UNISON_VOICES),
+            table_hint: Vec::with_capacity(MAX_UNISON_VOICES),
+            initial_phase: None,
+            hash: 0,
+            sample_rate: sample_rate as f32,
+            _marker: std::marker::PhantomData,
+        };
+        node.set_voices(voices);
+        node
+    }
+
+    /// Number of voices.
+    pub fn voices(&self) -> usize {
+        self.phase.len()
+    }
+
+    /// Set number of voices (1 to `MAX_UNISON_VOICES`). Resets the oscillator.
+    pub fn set_voices(&mut self, voices: usize) {
+        assert!((1..=MAX_UNISON_VOICES).contains(&voices));
+        self.phase.resize(voices, 0.0);
+        self.table_hint.resize(voices, 0);
+        self.update_voices();
+        self.reset();
+    }
+
+    /// Set detune of the outermost voices in semitones. Detune is used when there is no detune input.
+    pub fn set_detune(&mut self, detune: f32) {
+        self.detune = detune;
+    }
+
+    /// Set detune curve. Voice positions in -1...1 are raised to the power `curve`.
+    /// The default is 1, which spaces voices evenly in pitch.
+    /// Values above 1 gather voices near the center, like a supersaw.
+    pub fn set_curve(&mut self, curve: f32) {
+        self.curve = curve;
+        self.update_voices();
+    }
+
+    /// Set stereo spread in 0...1. At zero, all voices are in the center.
+    /// At one (the default), the outermost voices are panned hard left and right.
+    pub fn set_spread(&mut self, spread: f32) {
+        self.spread = spread;
+        self.update_voices();
+    }
+
+    /// Set mix in 0...1 between center (0) and side (1) voices. Mix is used when there is no mix input.
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix;
+    }
+
+    /// Set initial phase in 0...1 for all voices.
+    /// If `None` (the default), initial phases are pseudorandom.
+    pub fn set_initial_phase(&mut self, phase: Option<f32>) {
+        self.initial_phase = phase;
+        self.reset();
+    }
+
+    /// Calculate voice positions and pan weights.
+    fn update_voices(&mut self) {
+        let voices = self.phase.len();
+        self.position.clear();
+        self.pan.clear();
+        for i in 0..voices {
+            let x = if voices > 1 {
+                i as f32 / (voices - 1) as f32 * 2.0 - 1.0
+            } else {
+                0.0
+            };
+            self.position.push(signum(x) * pow(abs(x), self.curve));
+            self.pan.push(super::pan::pan_weights(x * self.spread));
+        }
+        // The center voices are the one or two voices closest to the center.
+        let closest = self.position.iter().fold(1.0, |acc, &x| min(acc, abs(x)));
+        self.center.clear();
+        self.center
+            .extend(self.position.iter().map(|&x| abs(x) == closest));
+    }
+}
+
+impl<T: Float, N: Size<T>> AudioNode for Unison<T, N> {
+    const ID: u64 = 96;
+    type Sample = T;
+    type Inputs = N;
+    type Outputs = numeric_array::typenum::U2;
+    type Setting = (f32, f32);
+
+    fn reset(&mut self) {
+        for (i, phase) in self.phase.iter_mut().enumerate() {
+            *phase = match self.initial_phase {
+                Some(phase) => phase,
+                None => rnd((self.hash as i64).wrapping_add(i as i64)) as f32,
+            };
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate as f32;
+    }
+
+    fn set_hash(&mut self, hash: u64) {
+        self.hash = hash;
+        self.reset();
+    }
+
+    fn set(&mut self, (detune, mix): Self::Setting) {
+        self.set_detune(detune);
+        self.set_mix(mix);
+    }
+
+    #[inline]
+    fn tick(
+        &mut self,
+        input: &Frame<Self::Sample, Self::Inputs>,
+    ) -> Frame<Self::Sample, Self::Outputs> {
+        let frequency = input[0].to_f32();
+        let detune = if N::USIZE > 1 {
+            input[1].to_f32()
+        } else {
+            self.detune
+        };
+        let mix = if N::USIZE > 2 {
+            clamp01(input[2].to_f32())
+        } else {
+            self.mix
+        };
+        let centers = self.center.iter().filter(|&&x| x).count();
+        let sides = self.center.len() - centers;
+        // Voices are summed at equal power.
+        let (center_gain, side_gain) = if sides > 0 {
+            ((1.0 - mix) / sqrt(centers as f32), mix / sqrt(sides as f32))
+        } else {
+            (1.0 / sqrt(centers as f32), 0.0)
+        };
+        let mut left = 0.0;
+        let mut right = 0.0;
+        for i in 0..self.phase.len() {
+            let f = frequency * exp2(detune * self.position[i] * (1.0 / 12.0));
+            self.phase[i] += f / self.sample_rate;
+            self.phase[i] -= floor(self.phase[i]);
+            let (output, hint) = self.table.read(self.table_hint[i], abs(f), self.phase[i]);
+            self.table_hint[i] = hint;
+            let gain = if self.center[i] {
+                center_gain
+            } else {
+                side_gain
+            };
+            left += output * gain * self.pan[i].0;
+            right += output * gain * self.pan[i].1;
+        }
+        [convert(left), convert(right)].into()
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = new_signal_frame(self.outputs());
+        output[0] = Signal::Latency(0.0);
+        output[1] = Signal::Latency(0.0);
+        output
+    }
+}
+
+lazy_static! {
+    /// Shared saw wavetable for unison oscillators.
+    pub(crate) static ref SHARED_SAW_TABLE: Arc<Wavetable> = Arc::new(SAW_TABLE.clone());
+}
+
 lazy_static! {
     /// Saw wavetable.
     pub static ref SAW_TABLE: Wavetable = Wavetable::new(
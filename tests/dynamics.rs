@@ -48,6 +48,59 @@ fn test_dynamics() {
         assert!(value >= 0.90 && value <= 1.00);
     }
 
+    // True peak mode catches inter-sample peaks.
+    let mut x = limiter((0.001, 0.01));
+    x.set_true_peak(true);
+    x.set_ceiling(-1.0);
+    let mut y = Wave64::new(1, DEFAULT_SR);
+    for i in 0..44100 {
+        // A sine at a quarter of the sample rate, sampled between its peaks.
+        let z = x.filter_mono(2.0 * sin(TAU * 0.25 * i as f64 + PI * 0.25));
+        y.push(z);
+    }
+    let y = y.loudness();
+    assert!(y.true_peak <= -1.0 && y.true_peak > -2.5);
+
+    // Unlinked channels are limited independently.
+    let mut x = multilimiter::<U3, _>((0.001, 0.01));
+    x.set_link(0.0);
+    let mut y = Frame::default();
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([4.0, 0.5, -2.0]));
+    }
+    assert!(y[0] > 0.9 && y[0] <= 1.0);
+    assert!((y[1] - 0.5).abs() < 1.0e-6);
+    assert!(y[2] < -0.9 && y[2] >= -1.0);
+
+    // Release slows down after sustained limiting.
+    let recovery = |sustain: usize| -> usize {
+        let mut x = limiter((0.001, 0.05));
+        for _ in 0..sustain {
+            x.filter_mono(4.0);
+        }
+        // Skip the look-ahead delay.
+        for _ in 0..100 {
+            x.filter_mono(0.5);
+        }
+        let mut i = 0;
+        while x.filter_mono(0.5) < 0.49 {
+            i += 1;
+        }
+        i
+    };
+    assert!(recovery(100) * 2 < recovery(200_000));
+
+    // A reset limiter behaves like a fresh one.
+    let mut x = limiter((0.001, 0.05));
+    for _ in 0..200_000 {
+        x.filter_mono(4.0);
+    }
+    x.reset();
+    let mut y = limiter((0.001, 0.05));
+    for _ in 0..10000 {
+        assert_eq!(x.filter_mono(0.5), y.filter_mono(0.5));
+    }
+
     // Test monitor and meter for consistency.
     let s1 = shared(0.0);
     let mut m1 = monitor(&s1, Meter::Sample);
@@ -70,3 +123,349 @@ fn test_dynamics() {
         assert_eq!(x2, s1.value());
     }
 }
+
+#[test]
+fn test_compressor() {
+    let mut rnd = Rnd::new();
+
+    // Test gain computer.
+    assert_eq!(compressor_gain(-20.0, 4.0, 0.0, -30.0), 0.0);
+    assert!((compressor_gain(-20.0, 4.0, 0.0, -10.0) + 7.5).abs() < 1.0e-9);
+    assert!((compressor_gain(-20.0, 4.0, 10.0, -10.0) + 7.5).abs() < 1.0e-9);
+    assert_eq!(compressor_gain(-20.0, 4.0, 10.0, -25.0), 0.0);
+    assert!(compressor_gain(-20.0, 4.0, 10.0, -20.0) < 0.0);
+
+    // Test steady state response to a constant signal.
+    for _ in 0..20 {
+        let threshold = lerp(-40.0, 0.0, rnd.f64());
+        let ratio = xerp(1.0, 20.0, rnd.f64());
+        let knee = lerp(0.0, 12.0, rnd.f64());
+        let level = lerp(-60.0, 10.0, rnd.f64());
+        let mut x = compressor_meter(threshold, ratio, knee, (0.001, 0.01));
+        let mut y = Frame::default();
+        for _ in 0..10000 {
+            y = x.tick(&Frame::from([db_amp(level)]));
+        }
+        let expected = compressor_gain(threshold, ratio, knee, level);
+        assert!((y[1] - expected).abs() < 1.0e-6);
+        assert!((amp_db(y[0]) - (level + expected)).abs() < 1.0e-6);
+    }
+
+    // Sidechain controls the gain of the main signal.
+    let mut x = compressor_sidechain(-20.0, 10.0, 0.0, 0.001);
+    let mut y = 0.0;
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([0.1, 1.0]))[0];
+    }
+    assert!((amp_db(y) - (-20.0 - 18.0)).abs() < 1.0e-6);
+
+    // Linked stereo compression applies the same gain to both channels.
+    let mut x = compressor_stereo(-20.0, 4.0, 0.0, 0.001);
+    let mut y = (0.0, 0.0);
+    for _ in 0..10000 {
+        let output = x.tick(&Frame::from([1.0, 0.1]));
+        y = (output[0], output[1]);
+    }
+    assert!((y.0 / y.1 - 10.0).abs() < 1.0e-6);
+
+    // A mono sidechain compressor with a meter output has as many inputs as outputs.
+    let mut x = An(Compressor::<f64, U2, U2>::new(
+        DEFAULT_SR, -20.0, 10.0, 0.0, 0.001, true,
+    ));
+    let mut y = Frame::default();
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([0.1, 1.0]));
+    }
+    assert!((amp_db(y[0]) - (-20.0 - 18.0)).abs() < 1.0e-6);
+    assert!((y[1] - (-18.0)).abs() < 1.0e-6);
+
+    // Ratios below 1 are clamped.
+    let mut x = compressor(-20.0, 0.5, 0.0, 0.001);
+    for _ in 0..10000 {
+        assert_eq!(x.filter_mono(1.0), 1.0);
+    }
+}
+
+#[test]
+fn test_gate() {
+    // Quiet signals are attenuated by the range and loud signals pass.
+    let mut x = gate_meter(-40.0, -60.0, 0.0001, 0.01, 0.001);
+    let mut y = Frame::default();
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([db_amp(-50.0)]));
+    }
+    assert!((amp_db(y[0]) - (-110.0)).abs() < 1.0e-3);
+    assert!((y[1] - db_amp(-60.0)).abs() < 1.0e-6);
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([db_amp(-30.0)]));
+    }
+    assert!((amp_db(y[0]) - (-30.0)).abs() < 1.0e-3);
+    assert!((y[1] - 1.0).abs() < 1.0e-6);
+    // The gate is held open after the signal drops.
+    let hold = (0.01 * DEFAULT_SR) as usize;
+    for _ in 0..hold {
+        y = x.tick(&Frame::from([db_amp(-50.0)]));
+    }
+    assert!(y[1] == 1.0);
+    for _ in 0..10000 {
+        y = x.tick(&Frame::from([db_amp(-50.0)]));
+    }
+    assert!(y[1] < db_amp(-59.0));
+
+    // Hysteresis keeps the gate open between the open and close thresholds.
+    let mut x = gate(-40.0, -60.0, 0.0001, 0.0, 0.001);
+    x.set_hysteresis(6.0);
+    for _ in 0..1000 {
+        x.filter_mono(db_amp(-30.0));
+    }
+    for _ in 0..1000 {
+        x.filter_mono(db_amp(-43.0));
+    }
+    assert!(x.is_open());
+    for _ in 0..1000 {
+        x.filter_mono(db_amp(-50.0));
+    }
+    assert!(!x.is_open());
+
+    // Expander attenuates proportionally below threshold.
+    let mut x = expander(-20.0, 2.0, -100.0, 0.0001);
+    let mut y = 0.0;
+    for _ in 0..10000 {
+        y = x.filter_mono(db_amp(-30.0));
+    }
+    assert!((amp_db(y) - (-40.0)).abs() < 1.0e-3);
+
+    // Look-ahead delays the signal and opens the gate in advance.
+    let mut x = gate(-40.0, -100.0, 0.0, 0.0, 0.0);
+    x.set_lookahead(0.001);
+    let length = (0.001 * DEFAULT_SR).round() as usize;
+    assert_eq!(x.latency(), Some(length as f64));
+    for i in 0..length * 4 {
+        let y = x.filter_mono(if i == length * 2 { 1.0 } else { 0.0 });
+        if i == length * 3 {
+            assert_eq!(y, 1.0);
+        } else {
+            assert_eq!(y, 0.0);
+        }
+    }
+
+    // Sidechain keys the gate.
+    let mut x = gate_sidechain(-40.0, -100.0, 0.0001, 0.0, 0.0001);
+    let mut y = 0.0;
+    for _ in 0..1000 {
+        y = x.tick(&Frame::from([0.5, 0.0]))[0];
+    }
+    assert!(y < 1.0e-4);
+    for _ in 0..1000 {
+        y = x.tick(&Frame::from([0.5, 0.5]))[0];
+    }
+    assert!((y - 0.5).abs() < 1.0e-6);
+
+    // Mono sidechain with gate state output has as many inputs as outputs.
+    let mut x = An(Gate::<f64, U2, U2>::new(
+        DEFAULT_SR, -40.0, -100.0, 0.0001, 0.0, 0.0001, true,
+    ));
+    let mut y = Frame::default();
+    for _ in 0..1000 {
+        y = x.tick(&Frame::from([0.5, 0.0]));
+    }
+    assert!(y[0] < 1.0e-4 && y[1] < 1.0e-4);
+    for _ in 0..1000 {
+        y = x.tick(&Frame::from([0.5, 0.5]));
+    }
+    assert!((y[0] - 0.5).abs() < 1.0e-6 && y[1] == 1.0);
+
+    // Sidechain filter ignores low frequency content.
+    let mut x = gate(-40.0, -100.0, 0.0001, 0.0, 0.0001);
+    x.set_sidechain_filter(1000.0, 10000.0);
+    for _ in 0..10000 {
+        x.filter_mono(0.5);
+    }
+    assert!(!x.is_open());
+}
+
+#[test]
+fn test_normalizer() {
+    // Steady state level approaches the target.
+    for &level in [-40.0, -20.0, -6.0].iter() {
+        let mut x = normalizer(-20.0, 30.0, -60.0, 0.05);
+        let mut phase = 0.0;
+        let mut rms = 0.0;
+        for i in 0..200_000 {
+            let y = x.filter_mono(db_amp(level) * sin(phase) * SQRT_2);
+            phase += TAU * 440.0 / DEFAULT_SR;
+            if i >= 100_000 {
+                rms += y * y;
+            }
+        }
+        let rms = amp_db(sqrt(rms / 100_000.0));
+        assert!((rms - (-20.0)).abs() < 0.5);
+    }
+
+    // Gain is limited to the maximum.
+    let mut x = normalizer(-20.0, 6.0, -100.0, 0.01);
+    for _ in 0..100_000 {
+        x.filter_mono(db_amp(-60.0));
+    }
+    assert!((x.gain() - 6.0).abs() < 1.0e-6);
+
+    // Gain is frozen below the noise floor.
+    let mut x = normalizer(-20.0, 30.0, -40.0, 0.01);
+    for _ in 0..100_000 {
+        x.filter_mono(db_amp(-30.0));
+    }
+    for _ in 0..50_000 {
+        x.filter_mono(0.0);
+    }
+    let gain = x.gain();
+    for _ in 0..50_000 {
+        x.filter_mono(0.0);
+    }
+    assert!(gain < 30.0 && gain == x.gain());
+}
+
+#[test]
+fn test_loudness() {
+    // A 0 dBFS 1 kHz sine in one channel measures -3.01 LUFS.
+    for &sample_rate in [44100.0, 48000.0, 96000.0].iter() {
+        let mut m = LoudnessMeter::new(sample_rate, 2);
+        let mut phase = 0.0;
+        for _ in 0..(sample_rate * 10.0) as usize {
+            m.tick(&[0.5 * sin(phase), 0.0]);
+            phase += TAU * 1000.0 / sample_rate;
+        }
+        assert!((m.momentary() - (-9.03)).abs() < 0.1);
+        assert!((m.short_term() - (-9.03)).abs() < 0.1);
+        assert!((m.integrated() - (-9.03)).abs() < 0.1);
+        assert!(m.loudness_range() < 0.2);
+        assert!(m.true_peak() >= 0.49 && m.true_peak() < 0.51);
+    }
+
+    // The relative gate excludes quiet passages from integrated loudness.
+    let mut m = LoudnessMeter::new(48000.0, 1);
+    let mut phase = 0.0;
+    for i in 0..480_000 {
+        let level = if i < 240_000 { -20.0 } else { -35.0 };
+        m.tick(&[db_amp(level) * sin(phase)]);
+        phase += TAU * 1000.0 / 48000.0;
+    }
+    assert!((m.integrated() - (-23.0)).abs() < 0.2);
+    assert!((m.loudness_range() - 15.0).abs() < 1.0);
+
+    // True peak detects inter-sample peaks.
+    let mut m = LoudnessMeter::new(48000.0, 1);
+    for i in 0..48000 {
+        m.tick(&[sin(TAU * 0.25 * i as f64 + PI * 0.25)]);
+    }
+    assert!(m.true_peak() > 0.99);
+
+    // Without true peak detection the sample peak is reported.
+    let mut m = LoudnessMeter::new(48000.0, 1);
+    m.set_true_peak(false);
+    for i in 0..48000 {
+        m.tick(&[sin(TAU * 0.25 * i as f64 + PI * 0.25)]);
+    }
+    assert!((m.true_peak() - sqrt(0.5)).abs() < 1.0e-9);
+
+    // Meter and wave measurements agree.
+    let wave = Wave64::render(48000.0, 5.0, &mut (sine_hz(1000.0) * 0.1));
+    let mut x = meter(Meter::ShortTerm);
+    x.set_sample_rate(48000.0);
+    let mut y = 0.0;
+    for i in 0..wave.len() {
+        y = x.filter_mono(wave.at(0, i));
+    }
+    assert!((y - (-23.0)).abs() < 0.1);
+    let mut x = meter(Meter::Integrated);
+    x.set_sample_rate(48000.0);
+    for i in 0..wave.len() {
+        y = x.filter_mono(wave.at(0, i));
+    }
+    assert!((y - (-23.0)).abs() < 0.1);
+    assert!((wave.loudness().integrated - (-23.0)).abs() < 0.1);
+    let mut wave = Wave64::render(48000.0, 5.0, &mut (sine_hz(1000.0) * 0.1));
+    wave.normalize_loudness(-14.0);
+    assert!((wave.loudness().integrated - (-14.0)).abs() < 0.1);
+    assert!(wave.loudness().true_peak > -11.1);
+}
+
+#[test]
+fn test_transient_shaper() {
+    // A steady signal is not shaped.
+    let mut x = transient_shaper(6.0, 6.0);
+    for _ in 0..100_000 {
+        x.filter_mono(0.5);
+    }
+    assert!(x.gain().abs() < 1.0e-3);
+
+    // Attacks are emphasized.
+    let mut x = transient_shaper(6.0, 0.0);
+    let mut peak: f64 = 0.0;
+    for _ in 0..1000 {
+        peak = peak.max(x.filter_mono(0.5));
+    }
+    assert!(peak > 0.9 && peak <= 1.0);
+    for _ in 0..100_000 {
+        x.filter_mono(0.5);
+    }
+    assert!(x.gain().abs() < 1.0e-3);
+
+    // Sustains are suppressed.
+    let mut x = transient_shaper(0.0, -6.0);
+    for _ in 0..100_000 {
+        x.filter_mono(0.5);
+    }
+    let mut y = 0.0;
+    for _ in 0..2000 {
+        y = x.filter_mono(0.05);
+    }
+    assert!(y < 0.05 * db_amp(-3.0));
+}
+
+#[test]
+fn test_deesser() {
+    let sample_rate = DEFAULT_SR;
+    let render = |x: &mut dyn AudioUnit64, f: f64, a: f64| -> (f64, f64) {
+        let mut phase = 0.0;
+        let mut input = 0.0;
+        let mut output = 0.0;
+        for i in 0..50_000 {
+            let z = a * sin(phase);
+            phase += TAU * f / sample_rate;
+            let y = x.filter_mono(z);
+            if i >= 40_000 {
+                input += z * z;
+                output += y * y;
+            }
+        }
+        (
+            amp_db(sqrt(input / 10_000.0)),
+            amp_db(sqrt(output / 10_000.0)),
+        )
+    };
+
+    // Low frequencies pass untouched.
+    let mut x = deesser(6000.0, -30.0, -12.0);
+    let (input, output) = render(&mut x, 200.0, 1.0);
+    assert!((input - output).abs() < 0.01);
+    assert!(x.gain_reduction() > -0.01);
+
+    // Loud high frequencies are attenuated by at most the range.
+    let mut x = deesser(6000.0, -30.0, -12.0);
+    let (input, output) = render(&mut x, 16000.0, 0.5);
+    assert!((x.gain_reduction() - (-12.0)).abs() < 1.0e-6);
+    assert!((output - (input - 12.0)).abs() < 1.0);
+
+    // Quiet high frequencies pass untouched.
+    let mut x = deesser(6000.0, -30.0, -12.0);
+    let (input, output) = render(&mut x, 16000.0, 0.01);
+    assert!((input - output).abs() < 0.01);
+
+    // The listen output contains the high band only.
+    let mut x = deesser_listen(6000.0, 0.0, -12.0);
+    let mut y = Frame::default();
+    for i in 0..10_000 {
+        y = x.tick(&Frame::from([sin(TAU * 100.0 * i as f64 / sample_rate)]));
+    }
+    assert!(y[1].abs() < 1.0e-3 && y[0].abs() > 0.1);
+}
@@ -0,0 +1,766 @@
+//! Effects tests.
+
+#![allow(clippy::manual_range_contains)]
+
+use fundsp::hacker::*;
+use funutd::*;
+
+/// Render `seconds` of stereo impulse response of `node` with the impulse in the left channel.
+fn impulse_response(node: &mut dyn AudioUnit64, seconds: f64) -> (Vec<f64>, Vec<f64>) {
+    let samples = (seconds * DEFAULT_SR) as usize;
+    let mut left = Vec::with_capacity(samples);
+    let mut right = Vec::with_capacity(samples);
+    let mut output = [0.0; 2];
+    for i in 0..samples {
+        let x = if i == 0 { 1.0 } else { 0.0 };
+        node.tick(&[x, 0.0], &mut output);
+        left.push(output[0]);
+        right.push(output[1]);
+    }
+    (left, right)
+}
+
+/// RMS level of `x` in the window from `t0` to `t1` seconds.
+fn rms(x: &[f64], t0: f64, t1: f64) -> f64 {
+    let window = &x[(t0 * DEFAULT_SR) as usize..(t1 * DEFAULT_SR) as usize];
+    (window.iter().map(|x| x * x).sum::<f64>() / window.len() as f64).sqrt()
+}
+
+#[test]
+fn test_hall() {
+    // Reverberation time matches with damping off.
+    for (size, time) in [(10.0, 1.0), (30.0, 2.0)] {
+        let mut x = hall_stereo(size, time);
+        x.set_damping(0.0, 0.0);
+        let (left, right) = impulse_response(&mut x, 2.0);
+        let decay = amp_db(rms(&left, 0.5, 0.7) / rms(&left, 1.5, 1.7));
+        let expected = 60.0 / time;
+        assert!(decay > expected * 0.75 && decay < expected * 1.25);
+        // Channels are decorrelated.
+        assert!(left != right);
+    }
+
+    // High damping shortens the tail.
+    let mut x = hall_stereo(10.0, 2.0);
+    x.set_damping(0.0, 1.0);
+    let (damped, _) = impulse_response(&mut x, 2.0);
+    let mut x = hall_stereo(10.0, 2.0);
+    x.set_damping(0.0, 0.0);
+    let (undamped, _) = impulse_response(&mut x, 2.0);
+    assert!(rms(&damped, 1.0, 1.5) < rms(&undamped, 1.0, 1.5) * 0.5);
+
+    // Nothing comes out before the pre-delay.
+    let mut x = hall_stereo(10.0, 1.0);
+    x.set_predelay(0.1);
+    let (left, right) = impulse_response(&mut x, 0.2);
+    let onset = (0.1 * DEFAULT_SR) as usize;
+    assert!(left[..onset]
+        .iter()
+        .chain(right[..onset].iter())
+        .all(|&x| x == 0.0));
+    assert!(rms(&left, 0.1, 0.2) > 0.0);
+
+    // Zero width produces mono output.
+    let mut x = hall_stereo(10.0, 1.0);
+    x.set((20.0, 1.5, 0.0));
+    assert!(x.size() == 20.0 && x.time() == 1.5);
+    let (left, right) = impulse_response(&mut x, 0.5);
+    assert!(left == right);
+
+    // Extreme settings remain bounded.
+    let mut x = hall_stereo(50.0, 1000.0);
+    x.set_damping(0.0, 0.0);
+    let mut noise = noise();
+    for _ in 0..DEFAULT_SR as usize * 2 {
+        let n = noise.get_mono();
+        let (l, r) = x.filter_stereo(n, -n);
+        assert!(l.abs() < 100.0 && r.abs() < 100.0);
+    }
+    x.set((1.0, 1000.0, 1.0));
+    for _ in 0..DEFAULT_SR as usize {
+        let (l, r) = x.filter_stereo(0.0, 0.0);
+        assert!(l.abs() < 100.0 && r.abs() < 100.0);
+    }
+}
+
+#[test]
+fn test_plate_spring() {
+    // Zero mix passes the dry signal through.
+    let mut x = plate_stereo(0.5, 0.5, 0.0);
+    let mut y = spring_stereo(0.5, 0.5, 0.0);
+    let mut noise = noise();
+    for _ in 0..1000 {
+        let n = noise.get_mono();
+        assert!(x.filter_stereo(n, -n) == (n, -n));
+        assert!(y.filter_stereo(n, -n) == (n, -n));
+    }
+
+    // Longer decay produces a longer tail.
+    fn tail<X>(mut node: An<X>, decay: f64) -> f64
+    where
+        X: AudioNode<Sample = f64, Inputs = U2, Outputs = U2, Setting = (f64, f64, f64)>,
+    {
+        node.set((decay, 0.1, 1.0));
+        let (response, _) = impulse_response(&mut node, 1.5);
+        rms(&response, 1.0, 1.5)
+    }
+    assert!(tail(plate_stereo(0.0, 0.0, 0.0), 0.9) > tail(plate_stereo(0.0, 0.0, 0.0), 0.3) * 10.0);
+    assert!(
+        tail(spring_stereo(0.0, 0.0, 0.0), 0.9) > tail(spring_stereo(0.0, 0.0, 0.0), 0.3) * 10.0
+    );
+
+    // The spring chirps: high frequencies arrive later than low frequencies.
+    let mut x = spring_stereo(0.0, 0.0, 1.0);
+    let (response, _) = impulse_response(&mut x, 0.1);
+    let centroid = |f: f64| {
+        let mut filter = bandpass_hz(f, 5.0);
+        let (mut sum, mut weight) = (0.0, 0.0);
+        for (i, x) in response.iter().enumerate() {
+            let y = filter.filter_mono(*x);
+            sum += i as f64 * y * y;
+            weight += y * y;
+        }
+        sum / weight / DEFAULT_SR
+    };
+    assert!(centroid(3800.0) - centroid(300.0) > 0.01);
+
+    // Any setting is stable.
+    for decay in [-1.0, 0.0, 0.5, 1.0, 10.0] {
+        for damping in [-1.0, 0.0, 1.0, 2.0] {
+            for mut node in [
+                Box::new(plate_stereo(decay, damping, 1.0)) as Box<dyn AudioUnit64>,
+                Box::new(spring_stereo(decay, damping, 1.0)),
+            ] {
+                let mut output = [0.0; 2];
+                for _ in 0..DEFAULT_SR as usize {
+                    let n = noise.get_mono();
+                    node.tick(&[n, n], &mut output);
+                    assert!(output[0].abs() < 100.0 && output[1].abs() < 100.0);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reflections() {
+    // Number of image sources up to each order.
+    let room = Room::new([10.0, 10.0, 10.0], 0.2);
+    let source = [2.0, 5.0, 5.0];
+    let listener = [8.0, 5.0, 5.0];
+    assert_eq!(room.reflections(source, listener, 1).len(), 6);
+    assert_eq!(room.reflections(source, listener, 2).len(), 24);
+    assert_eq!(room.reflections(source, listener, 3).len(), 62);
+    let mut visited = vec![];
+    room.for_each_reflection(source, listener, 3, |r| visited.push(r));
+    assert_eq!(visited, room.reflections(source, listener, 3));
+
+    // First order reflections off the X walls travel 10 meters.
+    let first = room.reflections(source, listener, 1);
+    let x_walls: Vec<&Reflection> = first
+        .iter()
+        .filter(|r| (r.delay - 10.0 / SPEED_OF_SOUND).abs() < 1.0e-9)
+        .collect();
+    assert_eq!(x_walls.len(), 2);
+    for r in x_walls.iter() {
+        assert!((r.gain - sqrt(0.8) / 10.0).abs() < 1.0e-9);
+        assert!(r.order == 1 && r.pan.abs() == 1.0);
+    }
+
+    // Fully absorbing walls do not reflect.
+    let dead = Room::new([10.0, 10.0, 10.0], 1.0);
+    assert!(dead
+        .reflections(source, listener, 3)
+        .iter()
+        .all(|r| r.gain == 0.0));
+
+    // The earliest reflection arrives on time and from the correct side.
+    // A source near the left wall reflects mostly from the left.
+    let listener = [2.0, 5.0, 5.0];
+    let source = [1.0, 7.0, 5.0];
+    let mut x = reflections(&room, source, listener, 2);
+    let mut left = vec![];
+    let mut right = vec![];
+    for i in 0..(0.1 * DEFAULT_SR) as usize {
+        let y = x.tick(&Frame::from([if i == 0 { 1.0 } else { 0.0 }]));
+        left.push(y[0]);
+        right.push(y[1]);
+    }
+    let earliest = x.reflections().iter().fold(1.0, |d: f64, r| d.min(r.delay));
+    let onset = left.iter().position(|&x| x.abs() > 1.0e-6).unwrap() as f64 / DEFAULT_SR;
+    assert!((onset - earliest).abs() < 3.0 / DEFAULT_SR);
+    let energy = |x: &[f64]| x.iter().map(|x| x * x).sum::<f64>();
+    assert!(energy(&left) > energy(&right) * 1.5);
+
+    // The onset stays in place after the write position wraps around the buffer.
+    for _ in 0..DEFAULT_SR as usize {
+        x.tick(&Frame::from([0.0]));
+    }
+    let onset = (0..(0.1 * DEFAULT_SR) as usize)
+        .position(|i| x.tick(&Frame::from([if i == 0 { 1.0 } else { 0.0 }]))[0].abs() > 1.0e-6)
+        .unwrap() as f64
+        / DEFAULT_SR;
+    assert!((onset - earliest).abs() < 3.0 / DEFAULT_SR);
+
+    // Moving the source is smooth.
+    let mut x = reflections(&room, [5.0, 5.0, 5.0], [2.0, 2.0, 2.0], 2);
+    let mut sine = sine_hz(220.0);
+    let mut previous = 0.0;
+    let mut max_step: f64 = 0.0;
+    for i in 0..DEFAULT_SR as usize {
+        if i == 22050 {
+            x.set_source([8.0, 8.0, 3.0]);
+        }
+        let y = x.tick(&Frame::from([sine.get_mono()]));
+        if i > 4410 {
+            max_step = max_step.max((y[0] - previous).abs());
+        }
+        previous = y[0];
+    }
+    assert!(max_step < 0.05);
+}
+
+#[test]
+fn test_modulation() {
+    let mut rnd = Rnd::from_u64(1);
+    let noise: Vec<f64> = (0..(2.0 * DEFAULT_SR) as usize)
+        .map(|_| rnd.f64() * 2.0 - 1.0)
+        .collect();
+
+    // Zero mix passes the dry signal through and channels differ when wet.
+    for mix in [0.0, 1.0] {
+        let mut chorus = chorus_stereo(0.5, 1.0, mix);
+        let mut ensemble = ensemble_stereo(8, 0.5, 1.0, mix);
+        let mut flanger = flanger_stereo(0.5, 1.0, 0.5, mix);
+        let mut phaser = phaser_stereo(12, 0.5, 1.0, 0.5, mix);
+        let mut differ = [false; 4];
+        for &x in noise.iter() {
+            let outputs = [
+                chorus.filter_stereo(x, x),
+                ensemble.filter_stereo(x, x),
+                flanger.filter_stereo(x, x),
+                phaser.filter_stereo(x, x),
+            ];
+            for (i, (l, r)) in outputs.into_iter().enumerate() {
+                if mix == 0.0 {
+                    assert!(l == x && r == x);
+                }
+                differ[i] |= (l - r).abs() > 1.0e-3;
+            }
+        }
+        assert!(differ == [mix > 0.0; 4]);
+    }
+
+    // Two phaser stages with a static sweep notch out their break frequency.
+    let f = xerp(100.0, 8000.0, 0.5);
+    let sine = |x: &mut An<Phaser<f64>>| {
+        let mut peak: f64 = 0.0;
+        for i in 0..(0.5 * DEFAULT_SR) as usize {
+            let (l, _) = x.filter_stereo(sin_hz(f, i as f64 / DEFAULT_SR), 0.0);
+            if i as f64 > 0.25 * DEFAULT_SR {
+                peak = peak.max(l.abs());
+            }
+        }
+        peak
+    };
+    let mut x = phaser_stereo(2, 0.5, 0.0, 0.0, 0.5);
+    assert!(sine(&mut x) < 0.01);
+    x.set_stages(4);
+    x.reset();
+    assert!(sine(&mut x) > 0.1);
+
+    // Tempo sync sets the LFO period.
+    let mut x = phaser_stereo(4, 0.0, 1.0, 0.0, 0.5);
+    x.set_tempo(120.0, 2.0);
+    let output: Vec<f64> = (0..(3.0 * DEFAULT_SR) as usize)
+        .map(|i| {
+            x.filter_stereo(sin_hz(1000.0, i as f64 / DEFAULT_SR), 0.0)
+                .0
+        })
+        .collect();
+    let second = DEFAULT_SR as usize;
+    let error = |shift: usize| {
+        (second..2 * second)
+            .map(|i| (output[i] - output[i + shift]).abs())
+            .fold(0.0, f64::max)
+    };
+    assert!(error(second) < 1.0e-6);
+    assert!(error(second / 2) > 0.1);
+
+    // Extreme settings remain stable.
+    let mut flanger = flanger_stereo(20.0, 1.0, 2.0, 1.0);
+    let mut phaser = phaser_stereo(100, 20.0, 1.0, -2.0, 1.0);
+    let mut chorus = ensemble_stereo(100, 20.0, 2.0, 1.0);
+    chorus.set_feedback(-1.0);
+    // Degenerate tempos stop the LFO instead of producing NaN.
+    flanger.set_tempo(120.0, 0.0);
+    phaser.set_tempo(f64::NAN, 1.0);
+    chorus.set_tempo(0.0, 0.0);
+    for &x in noise.iter() {
+        for (l, r) in [
+            flanger.filter_stereo(x, -x),
+            phaser.filter_stereo(x, -x),
+            chorus.filter_stereo(x, -x),
+        ] {
+            assert!(l.is_finite() && r.is_finite() && l.abs() < 100.0 && r.abs() < 100.0);
+        }
+    }
+}
+
+#[test]
+fn test_tape_delay() {
+    let peak = |x: &[f64]| (0..x.len()).fold(0, |i, j| if x[j].abs() > x[i].abs() { j } else { i });
+    let impulse = |x: &mut An<TapeDelay<f64>>, seconds: f64| -> Vec<f64> {
+        (0..(seconds * DEFAULT_SR) as usize)
+            .map(|i| x.filter_mono(if i == 0 { 1.0 } else { 0.0 }))
+            .collect()
+    };
+
+    // Without wow and flutter the echo arrives at the delay time.
+    let mut x = tape_delay(1.0, 0.1, 0.0, 1.0);
+    x.set_wow_flutter(0.0, 0.0);
+    x.set_filter(0.0, 1.0e6);
+    let response = impulse(&mut x, 0.5);
+    assert_eq!(peak(&response), (0.1 * DEFAULT_SR) as usize);
+
+    // Tempo sync and multiple heads.
+    x.set_tempo(120.0, 0.25);
+    // A zero tempo is ignored.
+    x.set_tempo(0.0, 1.0);
+    x.set_heads([0.0, 1.0, 0.0, 0.0]);
+    x.reset();
+    let response = impulse(&mut x, 0.5);
+    assert_eq!(peak(&response), (0.25 * DEFAULT_SR) as usize);
+
+    // Echoes stay in place after the write position wraps around the buffer.
+    let mut x = tape_delay(0.1, 0.1, 0.0, 1.0);
+    x.set_wow_flutter(0.0, 0.0);
+    x.set_filter(0.0, 1.0e6);
+    for _ in 0..(1.5 * DEFAULT_SR) as usize {
+        assert_eq!(x.filter_mono(0.0), 0.0);
+    }
+    let response = impulse(&mut x, 0.5);
+    assert_eq!(peak(&response), (0.1 * DEFAULT_SR) as usize);
+
+    // Repeats decay with feedback below 1.
+    let mut x = tape_delay(1.0, 0.1, 0.5, 1.0);
+    let response = impulse(&mut x, 1.0);
+    let level = |t: f64| rms(&response, t - 0.01, t + 0.01);
+    assert!(level(0.2) < level(0.1) && level(0.3) < level(0.2) && level(0.3) > 0.0);
+
+    // Shortening the delay time raises pitch while the tape path glides.
+    let mut x = tape_delay(1.0, 0.5, 0.0, 1.0);
+    x.set_wow_flutter(0.0, 0.0);
+    let crossings = |x: &mut An<TapeDelay<f64>>, t0: f64, t1: f64| {
+        let mut previous = 0.0;
+        let mut count = 0;
+        for i in (t0 * DEFAULT_SR) as usize..(t1 * DEFAULT_SR) as usize {
+            let y = x.filter_mono(sin_hz(1000.0, i as f64 / DEFAULT_SR));
+            if previous < 0.0 && y >= 0.0 {
+                count += 1;
+            }
+            previous = y;
+        }
+        count
+    };
+    let before = crossings(&mut x, 0.0, 1.0);
+    x.set_time(0.25);
+    let during = crossings(&mut x, 1.0, 1.1);
+    assert!(before > 450 && during > 110);
+
+    // Glide speed is limited at the longest active head too.
+    let mut x = tape_delay(1.0, 0.1, 0.0, 1.0);
+    x.set_wow_flutter(0.0, 0.0);
+    x.set_heads([0.0, 0.0, 0.0, 1.0]);
+    crossings(&mut x, 0.0, 1.0);
+    x.set_time(0.05);
+    let during = crossings(&mut x, 1.0, 1.1);
+    assert!(during > 110 && during <= 151);
+
+    // Saturation keeps self-oscillation bounded.
+    let mut rnd = Rnd::from_u64(2);
+    let mut x = tape_delay(1.0, 0.05, 1.5, 1.0);
+    x.set_drive(4.0);
+    for _ in 0..(2.0 * DEFAULT_SR) as usize {
+        let y = x.filter_mono(rnd.f64() * 2.0 - 1.0);
+        assert!(y.is_finite() && y.abs() < 2.0);
+    }
+}
+
+#[test]
+fn test_variable_delay() {
+    let methods = [
+        Interpolation::Linear,
+        Interpolation::Hermite,
+        Interpolation::Lagrange(3),
+        Interpolation::Lagrange(4),
+        Interpolation::Lagrange(5),
+        Interpolation::Thiran,
+        Interpolation::Sinc(8),
+    ];
+    let render = |x: &mut An<VariableDelay<f64>>, f: f64, delay: &dyn Fn(usize) -> f64| {
+        (0..(0.2 * DEFAULT_SR) as usize)
+            .map(|i| {
+                let input = sin_hz(f, i as f64 / DEFAULT_SR);
+                x.tick(&Frame::from([input, delay(i)]))[0]
+            })
+            .collect::<Vec<f64>>()
+    };
+
+    for interpolation in methods {
+        // Fractional delay of a low frequency sine is accurate.
+        let mut x = variable_delay(0.0, 0.01, interpolation);
+        let d = 10.3;
+        let output = render(&mut x, 500.0, &|_| d / DEFAULT_SR);
+        for (i, y) in output.iter().enumerate().skip(1000) {
+            assert!((y - sin_hz(500.0, (i as f64 - d) / DEFAULT_SR)).abs() < 0.01);
+        }
+
+        // Half sample delay of a high frequency sine.
+        x.reset();
+        let output = render(&mut x, 15000.0, &|_| 10.5 / DEFAULT_SR);
+        let level = rms(&output, 0.1, 0.2) * 2.0f64.sqrt();
+        match interpolation {
+            Interpolation::Linear => assert!(level < 0.6),
+            Interpolation::Thiran | Interpolation::Sinc(_) => assert!(level > 0.9 && level < 1.1),
+            _ => assert!(level > 0.5 && level < 1.1),
+        }
+
+        // Modulation-safe mode smooths a jump in delay time.
+        let jump = |i: usize| if i < 4410 { 0.002 } else { 0.004 };
+        let roughness = |output: &[f64]| {
+            output
+                .windows(2)
+                .skip(1000)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0, f64::max)
+        };
+        x.reset();
+        assert!(roughness(&render(&mut x, 200.0, &jump)) > 0.1);
+        x.set_smoothing(0.01);
+        x.reset();
+        assert!(roughness(&render(&mut x, 200.0, &jump)) < 0.05);
+    }
+}
+
+#[test]
+fn test_vocoder() {
+    // Bands are spaced logarithmically and the band count is clamped to 8...64.
+    let x = vocoder(4, 100.0, 6400.0);
+    assert_eq!(x.bands(), 8);
+    let x = vocoder(100, 100.0, 6400.0);
+    assert_eq!(x.bands(), 64);
+    let x = vocoder(19, 100.0, 6400.0);
+    let f = x.frequencies();
+    assert!((f[0] - 100.0).abs() < 1.0e-6 && (f[18] - 6400.0).abs() < 1.0e-6);
+    assert!((f[3] / f[0] - 2.0).abs() < 1.0e-6);
+
+    // RMS output level for modulator frequency `m` and carrier frequency `c` (0 for silence).
+    let level = |x: &mut An<Vocoder<f64>>, m: f64, c: f64| {
+        x.reset();
+        let output: Vec<f64> = (0..(0.5 * DEFAULT_SR) as usize)
+            .map(|i| {
+                let t = i as f64 / DEFAULT_SR;
+                let m = if m > 0.0 { sin_hz(m, t) } else { 0.0 };
+                let c = if c > 0.0 { sin_hz(c, t) } else { 0.0 };
+                x.tick(&Frame::from([m, c]))[0]
+            })
+            .collect();
+        rms(&output, 0.25, 0.5)
+    };
+
+    // The modulator spectrum gates the carrier.
+    let mut x = vocoder(32, 100.0, 8000.0);
+    let matched = level(&mut x, 1000.0, 1000.0);
+    assert!(matched > 0.05);
+    assert!(level(&mut x, 1000.0, 4000.0) < matched * 0.1);
+    assert!(level(&mut x, 0.0, 1000.0) == 0.0);
+
+    // Formant shift moves modulator bands up in the carrier.
+    x.set_formant_shift(2.0);
+    assert!(level(&mut x, 1000.0, 2000.0) > matched * 0.5);
+    assert!(level(&mut x, 1000.0, 1000.0) < matched * 0.1);
+    x.set_formant_shift(1.0);
+
+    // High frequency modulator sounds inject noise into a silent carrier.
+    assert!(level(&mut x, 10000.0, 0.0) == 0.0);
+    x.set_noise(1.0);
+    assert!(level(&mut x, 10000.0, 0.0) > 0.001);
+    assert!(level(&mut x, 200.0, 0.0) < level(&mut x, 10000.0, 0.0) * 0.1);
+}
+
+/// Amplitude of the `f` Hz component of `x` over the window from `t0` to `t1` seconds.
+fn amplitude(x: &[f64], f: f64, t0: f64, t1: f64) -> f64 {
+    let (mut re, mut im) = (0.0, 0.0);
+    let (i0, i1) = ((t0 * DEFAULT_SR) as usize, (t1 * DEFAULT_SR) as usize);
+    for (i, x) in x.iter().enumerate().take(i1).skip(i0) {
+        let phase = TAU * f * i as f64 / DEFAULT_SR;
+        re += x * phase.cos();
+        im += x * phase.sin();
+    }
+    2.0 * (re * re + im * im).sqrt() / (i1 - i0) as f64
+}
+
+#[test]
+fn test_hilbert() {
+    // The analytic signal of a sine wave has a constant magnitude
+    // and the quadrature component lags by a quarter cycle.
+    for f in [100.0, 441.0, 1000.0, 5000.0, 15000.0] {
+        let mut x = hilbert();
+        let mut output = Vec::new();
+        for i in 0..(0.2 * DEFAULT_SR) as usize {
+            let y = x.tick(&Frame::from([sin_hz(f, i as f64 / DEFAULT_SR)]));
+            output.push((y[0], y[1]));
+        }
+        for &(re, im) in output.iter().skip(4410) {
+            assert!(((re * re + im * im).sqrt() - 1.0).abs() < 0.03);
+        }
+        if f == 441.0 {
+            // The period is 100 samples.
+            for i in 4410..output.len() {
+                assert!((output[i].1 - output[i - 25].0).abs() < 0.03);
+            }
+        }
+        let re = x.response(0, f).unwrap();
+        let im = x.response(1, f).unwrap();
+        assert!((re.norm() - 1.0).abs() < 1.0e-6 && (im.norm() - 1.0).abs() < 1.0e-6);
+        let difference = (im / re).arg();
+        assert!((difference + PI / 2.0).abs() < 0.02);
+    }
+
+    // Frequency shifter moves all partials by a fixed amount.
+    let mut x = freq_shift_hz(100.0);
+    let (mut up, mut down) = (Vec::new(), Vec::new());
+    for i in 0..DEFAULT_SR as usize {
+        let t = i as f64 / DEFAULT_SR;
+        let y = x.tick(&Frame::from([sin_hz(1000.0, t) + 0.5 * sin_hz(3000.0, t)]));
+        up.push(y[0]);
+        down.push(y[1]);
+    }
+    assert!((amplitude(&up, 1100.0, 0.5, 1.0) - 1.0).abs() < 0.03);
+    assert!((amplitude(&up, 3100.0, 0.5, 1.0) - 0.5).abs() < 0.03);
+    assert!(amplitude(&up, 900.0, 0.5, 1.0) < 0.03);
+    assert!(amplitude(&up, 1000.0, 0.5, 1.0) < 0.03);
+    assert!((amplitude(&down, 900.0, 0.5, 1.0) - 1.0).abs() < 0.03);
+    assert!((amplitude(&down, 2900.0, 0.5, 1.0) - 0.5).abs() < 0.03);
+    assert!(amplitude(&down, 1100.0, 0.5, 1.0) < 0.03);
+
+    // Ring modulator produces sum and difference frequencies.
+    let mut x = ring_mod_hz(300.0);
+    let output: Vec<f64> = (0..DEFAULT_SR as usize)
+        .map(|i| x.filter_mono(sin_hz(1000.0, i as f64 / DEFAULT_SR)))
+        .collect();
+    assert!((amplitude(&output, 700.0, 0.0, 1.0) - 0.5).abs() < 0.01);
+    assert!((amplitude(&output, 1300.0, 0.0, 1.0) - 0.5).abs() < 0.01);
+    assert!(amplitude(&output, 1000.0, 0.0, 1.0) < 0.01);
+    let mut x = ring_mod();
+    assert_eq!(x.tick(&Frame::from([0.5, -0.25]))[0], -0.125);
+}
+
+#[test]
+fn test_shape() {
+    // New memoryless modes.
+    let mut fold = shape(Shape::Fold(1.0));
+    assert!((fold.filter_mono(1.5) - 0.5).abs() < 1.0e-12);
+    assert!((fold.filter_mono(-1.5) + 0.5).abs() < 1.0e-12);
+    assert!((fold.filter_mono(3.0) + 1.0).abs() < 1.0e-12);
+    let mut tube = shape(Shape::Tube(1.0));
+    assert!((tube.filter_mono(1.0) - tanh(1.0)).abs() < 1.0e-12);
+    assert!((tube.filter_mono(-1.0) + 0.5).abs() < 1.0e-12);
+    assert_eq!(shape(Shape::HalfRectify).filter_mono(-0.5), 0.0);
+    assert_eq!(shape(Shape::FullRectify).filter_mono(-0.5), 0.5);
+    let mut chebyshev = shape(Shape::Chebyshev(3));
+    for theta in [0.1, 0.7, 2.0, 3.0] {
+        assert!((chebyshev.filter_mono(cos(theta)) - cos(3.0 * theta)).abs() < 1.0e-12);
+    }
+    assert!((chebyshev.filter_mono(2.0) - 1.0).abs() < 1.0e-12);
+    assert!(shape(Shape::SoftChebyshev(2)).filter_mono(10.0) < 1.0);
+
+    // Antialiasing preserves the fundamental and suppresses aliases of hard clipping.
+    // With a fundamental of 2345 Hz, the 19th harmonic aliases to 455 Hz.
+    let render = |antialias: Antialias| -> Vec<f64> {
+        let mut x = shape_adaa(Shape::Clip, antialias);
+        (0..DEFAULT_SR as usize)
+            .map(|i| x.filter_mono(10.0 * sin_hz(2345.0, i as f64 / DEFAULT_SR)))
+            .collect()
+    };
+    let naive = render(Antialias::Off);
+    let first = render(Antialias::First);
+    let second = render(Antialias::Second);
+    let alias = |x: &[f64]| amplitude(x, 455.0, 0.0, 1.0);
+    assert!(alias(&first) < 0.2 * alias(&naive));
+    assert!(alias(&second) < 0.5 * alias(&first));
+    for x in [&first, &second] {
+        let fundamental = amplitude(x, 2345.0, 0.0, 1.0);
+        assert!((fundamental / amplitude(&naive, 2345.0, 0.0, 1.0) - 1.0).abs() < 0.02);
+    }
+
+    // Antialiased output of a slowly varying signal follows the delayed shaping function.
+    for mode in [
+        Shape::Tanh(2.0),
+        Shape::Atan(2.0),
+        Shape::Softsign(2.0),
+        Shape::Tube(2.0),
+        Shape::Fold(3.0),
+        Shape::HalfRectify,
+        Shape::FullRectify,
+        Shape::Chebyshev(4),
+        Shape::ClipTo(-0.5, 0.8),
+    ] {
+        for antialias in [Antialias::First, Antialias::Second] {
+            let mut x = shape_adaa(mode.clone(), antialias);
+            let mut y = shape(mode.clone());
+            let delay = if x.antialias() == Antialias::First {
+                0.5
+            } else {
+                1.0
+            };
+            for i in 0..1000 {
+                let input = |i: f64| 1.5 * sin(i * 0.01);
+                let output = x.filter_mono(input(i as f64));
+                let expected = y.filter_mono(input(i as f64 - delay));
+                // Kinks in the shaping function are smoothed slightly.
+                assert!(i < 2 || (output - expected).abs() < 0.03);
+            }
+        }
+    }
+
+    // Zero hardness or gain falls back to the plain shaping function.
+    for mode in [
+        Shape::Tanh(0.0),
+        Shape::Atan(0.0),
+        Shape::Softsign(0.0),
+        Shape::Tube(0.0),
+        Shape::Fold(0.0),
+    ] {
+        for antialias in [Antialias::First, Antialias::Second] {
+            let mut x = shape_adaa(mode.clone(), antialias);
+            let mut y = shape(mode.clone());
+            assert_eq!(x.antialias(), Antialias::Off);
+            for i in 0..100 {
+                let input = 1.5 * sin(i as f64 * 0.1);
+                assert_eq!(x.filter_mono(input), y.filter_mono(input));
+            }
+        }
+    }
+
+    // Antialiasing latency and constant propagation.
+    assert_eq!(shape(Shape::Fold(2.0)).latency(), Some(0.0));
+    assert_eq!(
+        shape_adaa(Shape::Tanh(1.0), Antialias::First).latency(),
+        Some(0.5)
+    );
+    assert_eq!(
+        shape_adaa(Shape::Clip, Antialias::Second).latency(),
+        Some(1.0)
+    );
+    assert_eq!(
+        shape_adaa(Shape::Tanh(1.0), Antialias::Second).latency(),
+        Some(0.5)
+    );
+    assert_eq!(
+        shape_adaa(Shape::Crush(4.0), Antialias::Second).latency(),
+        Some(0.0)
+    );
+    let mut x = dc(-1.0) >> shape_adaa(Shape::Tube(1.0), Antialias::First);
+    assert!(matches!(x.route(&new_signal_frame(0), 0.0)[0], Signal::Value(y) if y == -0.5));
+}
+
+#[test]
+fn test_saturation() {
+    let render = |node: &mut dyn AudioUnit64, f: f64, a: f64| -> Vec<f64> {
+        (0..DEFAULT_SR as usize)
+            .map(|i| {
+                let mut output = [0.0];
+                node.tick(&[a * sin_hz(f, i as f64 / DEFAULT_SR)], &mut output);
+                output[0]
+            })
+            .collect()
+    };
+
+    // Small signals pass almost linearly.
+    let x = render(&mut triode(1.0, 0.0, 1.0), 1000.0, 0.01);
+    assert!((amplitude(&x, 1000.0, 0.5, 1.0) - 0.01).abs() < 0.0005);
+    assert!(amplitude(&x, 2000.0, 0.5, 1.0) < 0.0002);
+    let x = render(&mut transformer_saturation(1.0, 0.0, 2.0), 1000.0, 0.01);
+    assert!((amplitude(&x, 1000.0, 0.5, 1.0) - 0.02).abs() < 0.0005);
+
+    // The triode is asymmetric and produces even harmonics at zero bias.
+    // Tape and transformer are symmetric at zero bias; bias adds even harmonics.
+    let x = render(&mut triode(8.0, 0.0, 1.0), 1000.0, 0.5);
+    assert!(amplitude(&x, 2000.0, 0.5, 1.0) > 0.2 * amplitude(&x, 1000.0, 0.5, 1.0));
+    for bias in [0.0, 0.3] {
+        let x = render(&mut tape_saturation(8.0, bias, 1.0), 1000.0, 0.5);
+        let y = render(&mut transformer_saturation(8.0, bias, 1.0), 50.0, 0.5);
+        let (even_x, even_y) = (
+            amplitude(&x, 2000.0, 0.5, 1.0),
+            amplitude(&y, 100.0, 0.5, 1.0),
+        );
+        assert!(amplitude(&x, 3000.0, 0.5, 1.0) > 0.1 * amplitude(&x, 1000.0, 0.5, 1.0));
+        assert!(amplitude(&y, 150.0, 0.5, 1.0) > 0.05 * amplitude(&y, 50.0, 0.5, 1.0));
+        if bias == 0.0 {
+            assert!(even_x < 0.01 && even_y < 0.01);
+        } else {
+            assert!(even_x > 0.03 && even_y > 0.03);
+        }
+    }
+
+    // Oversampling suppresses aliasing. The 5th harmonic of 7 kHz aliases to 9.1 kHz.
+    let naive = render(&mut shape(Shape::Tanh(8.0)), 7000.0, 0.5);
+    let x = render(&mut triode(8.0, 0.0, 1.0), 7000.0, 0.5);
+    let y = render(&mut tape_saturation(8.0, 0.0, 1.0), 7000.0, 0.5);
+    assert!(amplitude(&naive, 9100.0, 0.5, 1.0) > 0.1);
+    assert!(amplitude(&x, 9100.0, 0.5, 1.0) < 0.002);
+    assert!(amplitude(&y, 9100.0, 0.5, 1.0) < 0.005);
+
+    // Transformer distortion decreases with frequency.
+    for f in [2000.0, 7000.0] {
+        let x = render(&mut transformer_saturation(4.0, 0.0, 1.0), f, 0.5);
+        assert!(amplitude(&x, 3.0 * f, 0.5, 1.0) < 0.001 * amplitude(&x, f, 0.5, 1.0));
+    }
+
+    // Tape hysteresis delays the output compared to a memoryless curve.
+    let x = render(&mut tape_saturation(2.0, 0.0, 1.0), 100.0, 0.5);
+    let y = render(&mut triode(0.2, 0.0, 1.0), 100.0, 0.5);
+    // Number of samples from a rising zero crossing of the input to that of the output.
+    let lag = |x: &[f64]| (44100 - 441..44100).position(|i| x[i] >= 0.0).unwrap();
+    assert!(lag(&x) > lag(&y) + 5);
+
+    // Grid current shifts the triode operating point after loud passages,
+    // which lowers small signal gain until the coupling capacitor discharges.
+    let mut x = triode(8.0, 0.0, 1.0);
+    let output: Vec<f64> = (0..DEFAULT_SR as usize)
+        .map(|i| {
+            let a = if i < (0.2 * DEFAULT_SR) as usize {
+                0.5
+            } else {
+                0.01
+            };
+            x.filter_mono(a * sin_hz(1000.0, i as f64 / DEFAULT_SR))
+        })
+        .collect();
+    assert!(amplitude(&output, 1000.0, 0.25, 0.3) < 0.5 * amplitude(&output, 1000.0, 0.9, 1.0));
+
+    // Latency is that of the two stage oversampler.
+    let peak = |node: &mut dyn AudioUnit64| {
+        let response: Vec<f64> = (0..64)
+            .map(|i| {
+                let mut output = [0.0];
+                node.tick(&[if i == 0 { 0.001 } else { 0.0 }], &mut output);
+                output[0].abs()
+            })
+            .collect();
+        (0..response.len()).fold(0, |i, j| if response[j] > response[i] { j } else { i })
+    };
+    let mut reference = oversample(oversample(pass()));
+    let expected = peak(&mut reference);
+    assert!(expected > 0);
+    for node in [
+        &mut triode(1.0, 0.0, 1.0) as &mut dyn AudioUnit64,
+        &mut tape_saturation(1.0, 0.0, 1.0),
+        &mut transformer_saturation(1.0, 0.0, 1.0),
+    ] {
+        assert_eq!(node.latency(), reference.latency());
+        assert_eq!(peak(node), expected);
+    }
+}
@@ -66,6 +66,52 @@ fn test_filter() {
     }
 }
 
+#[test]
+fn test_efollow() {
+    let mut rnd = Rnd::new();
+
+    // Test halfway response times in the log domain.
+    for _ in 0..100 {
+        let attack_samples = round(xerp(1.0, 100_000.0, squared(rnd.f64())));
+        let release_samples = round(xerp(1.0, 100_000.0, squared(rnd.f64())));
+        let sample_rate = xerp(1000.0, 100_000.0, rnd.f64());
+        let mut x = efollow((attack_samples / sample_rate, release_samples / sample_rate));
+        x.set_sample_rate(sample_rate);
+        x.set_value(db_amp(-40.0));
+        for _ in 0..attack_samples as usize {
+            x.filter_mono(db_amp(-20.0));
+        }
+        assert!((x.value_db() - (-30.0)).abs() < 1.0e-6);
+        x.set_value(db_amp(-20.0));
+        for _ in 0..release_samples as usize {
+            x.filter_mono(db_amp(-40.0));
+        }
+        assert!((x.value_db() - (-30.0)).abs() < 1.0e-6);
+    }
+
+    // Test peak hold.
+    let mut x = efollow_hold(0.01, 0.001);
+    x.filter_mono(1.0);
+    let peak = x.value();
+    for _ in 0..(0.01 * DEFAULT_SR) as usize {
+        assert_eq!(x.filter_mono(0.0), peak);
+    }
+    assert!(x.filter_mono(0.0) < peak);
+
+    // Setting the value clears the hold.
+    x.filter_mono(1.0);
+    x.set_value(0.5);
+    assert!(x.filter_mono(0.0) < 0.5);
+
+    // Test RMS detection.
+    let mut x = efollow_rms(0.05, 0.01);
+    let mut y = 0.0;
+    for i in 0..100_000 {
+        y = x.filter_mono(sin(i as f64 * TAU * 1000.0 / DEFAULT_SR));
+    }
+    assert!((amp_db(y) - amp_db(1.0 / SQRT_2)).abs() < 0.1);
+}
+
 /// Complex64 with real component `x` and imaginary component zero.
 fn re<T: Float>(x: T) -> Complex64 {
     Complex64::new(x.to_f64(), 0.0)
@@ -0,0 +1,688 @@
+//! Oscillator tests.
+
+#![allow(clippy::manual_range_contains)]
+
+use fundsp::hacker::*;
+use std::sync::Arc;
+
+/// Band-limited rising saw wave at phase `x` with fundamental `f` Hz.
+fn ideal_saw(x: f64, f: f64) -> f64 {
+    let mut y = 0.0;
+    let mut k = 1.0;
+    while k * f < DEFAULT_SR * 0.5 {
+        y -= sin(TAU * k * x) / k;
+        k += 1.0;
+    }
+    y * 2.0 / PI
+}
+
+#[test]
+fn test_blep() {
+    // PolyBLEP saw is closer to the ideal saw than the naive saw is.
+    let f = 1234.5;
+    let mut x = blep_saw();
+    x.set_initial_phase(Some(0.0));
+    let mut blep_error = 0.0;
+    let mut naive_error = 0.0;
+    for i in 0..10000 {
+        let phase = (i as f64 * f / DEFAULT_SR).fract();
+        let y = x.filter_mono(f);
+        let ideal = ideal_saw(phase, f);
+        if i > 100 {
+            blep_error += squared(y - ideal);
+            naive_error += squared(2.0 * phase - 1.0 - ideal);
+        }
+    }
+    assert!(blep_error < naive_error * 0.5);
+
+    // Waveforms are bounded and zero centered.
+    for wave in [
+        BlepWave::Saw,
+        BlepWave::Pulse,
+        BlepWave::Triangle,
+        BlepWave::Morph,
+    ] {
+        for shape in [0.1, 0.5, 0.9] {
+            let mut x = blep(wave);
+            let mut sum = 0.0;
+            for i in 0..44100 {
+                let y = x.tick(&Frame::from([441.0, shape, 0.0, 0.0]))[0];
+                assert!(y.abs() < 1.3);
+                if i >= 100 {
+                    sum += y;
+                }
+            }
+            let mean = sum / 44000.0;
+            let expected = match wave {
+                BlepWave::Pulse => 2.0 * shape - 1.0,
+                _ => 0.0,
+            };
+            assert!((mean - expected).abs() < 0.01);
+        }
+    }
+
+    // Hard sync makes the output periodic at the master frequency.
+    let mut x = (dc((250.0, 0.5)) | sine_hz(100.0) | zero()) >> blep(BlepWave::Saw);
+    let output: Vec<f64> = (0..4410).map(|_| x.get_mono()).collect();
+    for i in 2000..3969 {
+        assert!((output[i] - output[i + 441]).abs() < 1.0e-6);
+    }
+    assert!(output.iter().all(|y| y.abs() < 1.3));
+
+    // Phase modulation is a phase offset.
+    let mut x = blep(BlepWave::Triangle);
+    x.set_initial_phase(Some(0.0));
+    let mut y = blep_triangle();
+    y.set_initial_phase(Some(0.25));
+    for i in 0..1000 {
+        let a = x.tick(&Frame::from([100.0, 0.5, 0.0, 0.25]))[0];
+        let b = y.filter_mono(100.0);
+        // The modulation input jumps at the start.
+        if i >= 2 {
+            assert!((a - b).abs() < 1.0e-6);
+        }
+    }
+
+    // Huge and non-finite phase modulation does not hang and the oscillator recovers.
+    let mut x = An(Blep::<f32, U4>::new(DEFAULT_SR, BlepWave::Morph, 0.5));
+    for pm in [
+        1.0e8,
+        -3.0e7,
+        f32::MAX,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NAN,
+        0.0,
+    ] {
+        for sync in [0.0, 1.0] {
+            x.tick(&Frame::from([440.0, 0.7, sync, pm]));
+        }
+    }
+    for _ in 0..10 {
+        let y = x.tick(&Frame::from([440.0, 0.7, 0.0, 0.0]))[0];
+        assert!(y.is_finite() && y.abs() < 1.3);
+    }
+
+    // Negative frequency reverses the waveform.
+    let mut x = blep_saw();
+    x.set_initial_phase(Some(0.5));
+    let mut y = blep_saw();
+    y.set_initial_phase(Some(0.5));
+    for _ in 0..1000 {
+        let a = x.filter_mono(-330.0);
+        let b = y.filter_mono(330.0);
+        assert!((a + b).abs() < 1.0e-6);
+    }
+}
+
+#[test]
+fn test_fm() {
+    // A single unmodulated operator is a sine wave at the note pitch.
+    let mut patch = FmPatch::new(vec![FmOperator::new(
+        2.0,
+        1.0,
+        FmEnvelope::adsr(0.0, 0.0, 1.0, 0.0),
+    )]);
+    patch.set_output(0, 1.0);
+    let mut x = fm(&patch);
+    for i in 0..1000 {
+        let y = x.tick(&Frame::from([100.0, 1.0]))[0];
+        // Envelope reaches full level on the first sample.
+        if i > 0 {
+            assert!((y - sin(TAU * 200.0 * i as f64 / DEFAULT_SR)).abs() < 1.0e-6);
+        }
+    }
+    // Releasing the note silences it.
+    for _ in 0..10 {
+        x.tick(&Frame::from([100.0, 0.0]));
+    }
+    assert!(!x.is_active());
+    assert!(x.tick(&Frame::from([100.0, 0.0]))[0] == 0.0);
+
+    // Phase modulation by an operator at the same frequency matches the formula.
+    let envelope = FmEnvelope::adsr(0.0, 0.0, 1.0, 0.0);
+    let mut patch = FmPatch::new(vec![
+        FmOperator::new(1.0, 1.0, envelope.clone()),
+        FmOperator::fixed(50.0, 0.5, envelope),
+    ]);
+    patch.set_modulation(1, 0, 2.0);
+    patch.set_output(0, 1.0);
+    let mut x = fm(&patch);
+    for i in 0..1000 {
+        let y = x.tick(&Frame::from([100.0, 1.0]))[0];
+        if i > 0 {
+            let t = i as f64 / DEFAULT_SR;
+            let expected = sin(TAU * 100.0 * t + sin(TAU * 50.0 * t));
+            assert!((y - expected).abs() < 1.0e-6);
+        }
+    }
+
+    // DX7 algorithms mix carriers to unity gain.
+    let operators = vec![FmOperator::default(); 6];
+    for algorithm in 1..=32 {
+        let patch = FmPatch::dx7(operators.clone(), algorithm, 1.0);
+        assert!((patch.output.iter().sum::<f64>() - 1.0).abs() < 1.0e-9);
+        let feedback: f64 = (0..6).map(|i| patch.modulation[i][i]).sum();
+        let loops = (0..6)
+            .flat_map(|i| (0..i).map(move |j| (i, j)))
+            .filter(|&(i, j)| patch.modulation[i][j] == 1.0)
+            .count();
+        assert!(feedback + loops as f64 == 1.0);
+    }
+    let patch = FmPatch::dx7(operators.clone(), 1, 0.0);
+    assert!(patch.output == vec![0.5, 0.0, 0.5, 0.0, 0.0, 0.0]);
+    assert!(patch.modulation[4][5] == FmPatch::DX7_INDEX);
+    let patch = FmPatch::dx7(operators, 32, 0.0);
+    assert!(patch.output.iter().all(|&x| x == 1.0 / 6.0));
+
+    // DX7 bulk dump parsing.
+    let mut bank = vec![0xf0, 0x43, 0x00, 0x09, 0x20, 0x00];
+    for voice in 0..32 {
+        let mut data = [0u8; 128];
+        for op in 0..6 {
+            let op_data = &mut data[op * 17..op * 17 + 17];
+            op_data[0..4].copy_from_slice(&[99, 50, 50, 50]);
+            op_data[4..8].copy_from_slice(&[99, 90, 90, 0]);
+            op_data[12] = 7 << 3;
+            op_data[14] = 94 + op as u8;
+            // Operator 1 is stored last; give it coarse ratio 2 and fine 50.
+            op_data[15] = if op == 5 { 2 << 1 } else { 1 << 1 };
+            op_data[16] = if op == 5 { 50 } else { 0 };
+        }
+        // Operator 6 has fixed frequency 100 Hz.
+        data[15] = (2 << 1) | 1;
+        data[110] = voice;
+        data[111] = (1 << 3) | 7;
+        data[117] = 36;
+        data[118..128].copy_from_slice(format!("VOICE {:02}  ", voice).as_bytes());
+        bank.extend_from_slice(&data);
+    }
+    bank.extend_from_slice(&[0, 0xf7]);
+    let patches = FmPatch::from_dx7_bank(&bank).unwrap();
+    assert!(patches.len() == 32);
+    for (voice, patch) in patches.iter().enumerate() {
+        assert!(patch.name == format!("VOICE {:02}", voice));
+        assert!(patch.transpose == 12.0);
+        assert!(patch.key_sync);
+        assert!(patch.operators[0].ratio == 3.0);
+        assert!(patch.operators[0].fixed.is_none());
+        assert!(patch.operators[0].level == 1.0);
+        assert!(patch.operators[5].fixed == Some(100.0));
+        assert!(patch.operators[1].detune == 0.0);
+        assert!(patch.operators[1].envelope.levels == [1.0, 90.0 / 99.0, 90.0 / 99.0, 0.0]);
+        assert!(*patch == FmPatch::from_dx7_voice(&bank[6 + voice * 128..]).unwrap());
+    }
+    assert!(patches[0].output == vec![0.5, 0.0, 0.5, 0.0, 0.0, 0.0]);
+    assert!(patches[31].output.iter().all(|&x| x == 1.0 / 6.0));
+    assert!(FmPatch::from_dx7_bank(&bank[0..1000]).is_err());
+
+    // Bank patches play.
+    let mut x = fm(&patches[4]);
+    let mut peak: f64 = 0.0;
+    for _ in 0..4410 {
+        let y = x.tick(&Frame::from([220.0, 1.0]))[0];
+        assert!(y.is_finite());
+        peak = peak.max(y.abs());
+    }
+    assert!(peak > 0.1 && peak <= 1.0);
+}
+
+#[test]
+fn test_wavetable() {
+    // Wavetable oscillator levels do not depend on pitch.
+    for f in [30.0, 200.0, 1000.0, 4000.0] {
+        let mut x = saw_hz(f);
+        let peak = (0..44100).fold(0.0, |peak: f64, _| peak.max(x.get_mono().abs()));
+        assert!(peak > 0.75 && peak <= 1.0);
+    }
+
+    // Imported waves keep their phase.
+    let wave: Vec<f32> = (0..256)
+        .map(|i| sin(TAU * i as f64 / 256.0) as f32)
+        .collect();
+    let table = Wavetable::from_wave(20.0, 20_000.0, 4.0, &wave);
+    for i in 0..64 {
+        let phase = i as f32 / 64.0;
+        assert!((table.at(0, phase) - wave[i * 4]).abs() < 1.0e-3);
+    }
+}
+
+#[test]
+fn test_morph_synth() {
+    // A single cycle sine of any length plays back as a sine.
+    let mut wave = Wave64::new(0, DEFAULT_SR);
+    let cycle: Vec<f64> = (0..1000).map(|i| sin(TAU * i as f64 / 1000.0)).collect();
+    wave.push_channel(&cycle);
+    let table = Arc::new(MorphTable::new(vec![Wavetable::from_wave64(
+        20.0, 20_000.0, 4.0, &wave, 0,
+    )]));
+    let mut x = morph_synth(&table);
+    for i in 0..1000 {
+        let y = x.tick(&Frame::from([440.0, 0.0]))[0];
+        assert!((y - sin(TAU * 440.0 * (i + 1) as f64 / DEFAULT_SR)).abs() < 1.0e-3);
+    }
+
+    // Imported waves are band-limited like the built-in tables.
+    let naive_saw: Vec<f32> = (0..2048)
+        .map(|i| 2.0 * ((i as f32 / 2048.0 + 0.5) % 1.0) - 1.0)
+        .collect();
+    let table = Arc::new(MorphTable::from_waves(20.0, 20_000.0, 4.0, &[&naive_saw]));
+    for f in [110.0, 3000.0, 7000.0] {
+        let mut x = morph_synth(&table);
+        let mut y = saw();
+        let output: Vec<(f64, f64)> = (0..2000)
+            .map(|_| (x.tick(&Frame::from([f, 0.5]))[0], y.filter_mono(f)))
+            .collect();
+        let gain = output.iter().map(|(a, b)| a * b).sum::<f64>()
+            / output.iter().map(|(_, b)| b * b).sum::<f64>();
+        let residual: f64 = output.iter().map(|(a, b)| squared(a - gain * b)).sum();
+        let energy: f64 = output.iter().map(|(a, _)| a * a).sum();
+        assert!(residual < energy * 1.0e-3);
+    }
+
+    // Serum style wavetables are split into frames. Frames are normalized together.
+    let mut wave = Wave64::new(0, DEFAULT_SR);
+    let frames: Vec<f64> = (0..3 * 2048 + 100)
+        .map(|i| sin(TAU * i as f64 / 2048.0) * (1.0 - (i / 2048) as f64 * 0.25))
+        .collect();
+    wave.push_channel(&frames);
+    let table = Arc::new(MorphTable::from_wave64(20.0, 20_000.0, 4.0, &wave, 0, 2048));
+    assert!(table.frames() == 3);
+
+    // Morph position interpolates between frames at audio rate.
+    let mut x = morph_synth(&table);
+    for i in 0..1000 {
+        let position = (i % 5) as f64 * 0.25;
+        let y = x.tick(&Frame::from([440.0, position]))[0];
+        let amplitude = 1.0 - position * 0.5;
+        assert!((y - amplitude * sin(TAU * 440.0 * (i + 1) as f64 / DEFAULT_SR)).abs() < 1.0e-3);
+    }
+}
+
+#[test]
+fn test_unison() {
+    let center = sqrt(0.5);
+
+    // A single voice is a centered saw.
+    let mut x = supersaw(1);
+    x.set_initial_phase(Some(0.0));
+    let mut y = saw();
+    for _ in 0..1000 {
+        let output = x.tick(&Frame::from([220.0]));
+        let z = y.filter_mono(220.0) * center;
+        assert!((output[0] - z).abs() < 1.0e-6 && (output[1] - z).abs() < 1.0e-6);
+    }
+
+    // With zero mix, only the center voice is heard.
+    let mut x = unison(&Arc::new(SAW_TABLE.clone()), 5);
+    x.set_initial_phase(Some(0.0));
+    let mut y = saw();
+    for _ in 0..1000 {
+        let output = x.tick(&Frame::from([220.0, 1.0, 0.0]));
+        let z = y.filter_mono(220.0) * center;
+        assert!((output[0] - z).abs() < 1.0e-6 && (output[1] - z).abs() < 1.0e-6);
+    }
+
+    // Side voices are detuned symmetrically: with 12 semitones of detune and full mix,
+    // the output is the sum of octaves below and above at equal power.
+    let sine = Arc::new(Wavetable::new(20.0, 20_000.0, 4.0, &|_| 0.0, &|_, i| {
+        if i == 1 {
+            1.0
+        } else {
+            0.0
+        }
+    }));
+    let mut x = unison(&sine, 3);
+    x.set_initial_phase(Some(0.0));
+    x.set_spread(0.0);
+    for i in 0..1000 {
+        let output = x.tick(&Frame::from([440.0, 12.0, 1.0]));
+        let t = (i + 1) as f64 / DEFAULT_SR;
+        let z = (sin(TAU * 220.0 * t) + sin(TAU * 880.0 * t)) * center * center;
+        assert!((output[0] - z).abs() < 1.0e-3 && (output[1] - z).abs() < 1.0e-3);
+    }
+
+    // Spread pans the outermost voices hard left and right.
+    let mut x = unison(&sine, 3);
+    x.set_initial_phase(Some(0.0));
+    for i in 0..1000 {
+        let output = x.tick(&Frame::from([440.0, 12.0, 1.0]));
+        let t = (i + 1) as f64 / DEFAULT_SR;
+        assert!((output[0] - sin(TAU * 220.0 * t) * center).abs() < 1.0e-3);
+        assert!((output[1] - sin(TAU * 880.0 * t) * center).abs() < 1.0e-3);
+    }
+
+    // Pseudorandom initial phases depend on the seed.
+    let render = |seed: u64| -> Vec<f64> {
+        let mut x = constant(110.0) >> supersaw(7);
+        x.ping(false, AttoHash::new(seed));
+        (0..100).map(|_| x.get_stereo().0).collect()
+    };
+    assert!(render(1) == render(1));
+    assert!(render(1) != render(2));
+
+    // The number of voices can be changed at runtime.
+    let mut x = supersaw(MAX_UNISON_VOICES);
+    assert!(x.voices() == MAX_UNISON_VOICES);
+    x.set_voices(3);
+    assert!(x.voices() == 3);
+    for _ in 0..1000 {
+        let output = x.tick(&Frame::from([220.0]));
+        assert!(output[0].abs() < 2.0 && output[1].abs() < 2.0);
+    }
+}
+
+#[test]
+fn test_additive() {
+    // A single partial is a sine wave.
+    let partials = Arc::new(AtomicPartials::new(&[Partial::new(2.0, 0.5, 0.25)]));
+    let mut x = additive(&partials);
+    for i in 0..10000 {
+        let y = x.filter_mono(100.0);
+        let t = i as f64 / DEFAULT_SR;
+        assert!((y - 0.5 * cos(TAU * 200.0 * t)).abs() < 1.0e-6);
+    }
+
+    // The recursive oscillators do not drift.
+    for _ in 0..DEFAULT_SR as usize * 10 {
+        x.filter_mono(1234.5);
+    }
+    let peak = (0..1000).fold(0.0, |acc: f64, _| acc.max(x.filter_mono(1234.5).abs()));
+    assert!((peak - 0.5).abs() < 1.0e-3);
+
+    // Parameter changes are applied within two blocks without clicks.
+    partials.set_amplitude(0, 0.0);
+    let mut previous = x.filter_mono(1234.5);
+    for _ in 0..128 {
+        let y = x.filter_mono(1234.5);
+        assert!((y - previous).abs() < 0.2);
+        previous = y;
+    }
+    assert!(x.filter_mono(1234.5) == 0.0);
+
+    // Stretch and inharmonicity.
+    let partials = Arc::new(AtomicPartials::new(&[Partial::new(2.0, 1.0, 0.0)]));
+    let mut x = additive(&partials);
+    x.set_stretch(2.0);
+    x.set_inharmonicity(0.75);
+    for i in 0..1000 {
+        let y = x.filter_mono(100.0);
+        let t = i as f64 / DEFAULT_SR;
+        assert!((y - sin(TAU * 800.0 * t)).abs() < 1.0e-6);
+    }
+
+    // Partials above Nyquist are culled.
+    let mut x = additive_fn(300, |_t, partials| {
+        for (i, partial) in partials.iter_mut().enumerate() {
+            partial.ratio = (i + 1) as f32;
+            partial.amplitude = 1.0 / (i + 1) as f32;
+        }
+    });
+    let mut y = additive_fn(220, |_t, partials| {
+        for (i, partial) in partials.iter_mut().enumerate() {
+            partial.ratio = (i + 1) as f32;
+            partial.amplitude = 1.0 / (i + 1) as f32;
+        }
+    });
+    for _ in 0..1000 {
+        let a = x.filter_mono(100.0);
+        let b = y.filter_mono(100.0);
+        assert!((a - b).abs() < 1.0e-6);
+        assert!(a.abs() < 2.0);
+    }
+
+    // The closure receives stream time.
+    let mut x = additive_fn(1, |t, partials| {
+        partials[0] = Partial::new(1.0, if t < 0.5 { 1.0 } else { 0.0 }, 0.25);
+    });
+    assert!(x.filter_mono(100.0) == 1.0);
+    for _ in 0..DEFAULT_SR as usize {
+        x.filter_mono(100.0);
+    }
+    assert!(x.filter_mono(100.0) == 0.0);
+}
+
+/// Magnitude of the DFT of `x` at frequency `f` Hz.
+fn dft_magnitude(x: &[f64], f: f64) -> f64 {
+    let (mut re, mut im) = (0.0, 0.0);
+    for (i, y) in x.iter().enumerate() {
+        let phase = TAU * f * i as f64 / DEFAULT_SR;
+        re += y * cos(phase);
+        im += y * sin(phase);
+    }
+    sqrt(re * re + im * im) / x.len() as f64
+}
+
+/// Frequency with the largest DFT magnitude in `f0...f1` Hz.
+fn spectral_peak(x: &[f64], f0: f64, f1: f64) -> f64 {
+    let mut best = (f0, 0.0);
+    let mut f = f0;
+    while f <= f1 {
+        let m = dft_magnitude(x, f);
+        if m > best.1 {
+            best = (f, m);
+        }
+        f += 0.25;
+    }
+    best.0
+}
+
+#[test]
+fn test_physical() {
+    // The waveguide string is in tune.
+    let render = |node: &mut dyn AudioUnit64, samples: usize| -> Vec<f64> {
+        (0..samples)
+            .map(|i| node.filter_mono(if i == 0 { 1.0 } else { 0.0 }))
+            .collect()
+    };
+    for f in [110.0, 220.0, 587.0] {
+        let mut x = waveguide(f, 0.5, 0.0);
+        let output = render(&mut x, 22050);
+        assert!((spectral_peak(&output, f * 0.97, f * 1.03) - f).abs() < 0.5);
+    }
+
+    // Exciting the string in the middle suppresses even partials.
+    let mut x = waveguide(220.0, 0.5, 0.0);
+    x.set_position(0.5);
+    let output = render(&mut x, 22050);
+    assert!(dft_magnitude(&output, 440.0) < 0.1 * dft_magnitude(&output, 220.0));
+    let mut x = waveguide(220.0, 0.5, 0.0);
+    x.set_position(0.2);
+    let output = render(&mut x, 22050);
+    assert!(dft_magnitude(&output, 440.0) > 0.5 * dft_magnitude(&output, 220.0));
+
+    // The string decays at the given rate.
+    let mut x = waveguide(220.0, 0.1, 0.0);
+    let output = render(&mut x, 55125);
+    let rms = |x: &[f64]| sqrt(x.iter().map(|y| y * y).sum::<f64>() / x.len() as f64);
+    let ratio = rms(&output[44100..]) / rms(&output[0..11025]);
+    assert!(ratio > 0.05 && ratio < 0.2);
+
+    // Dispersion keeps the fundamental in tune and makes upper partials sharp.
+    let mut x = waveguide(220.0, 0.5, 0.0);
+    x.set_dispersion(1.0);
+    let output = render(&mut x, 22050);
+    assert!((spectral_peak(&output, 210.0, 230.0) - 220.0).abs() < 0.5);
+    assert!(spectral_peak(&output, 880.0, 1000.0) > 900.0);
+
+    // Bowing sustains the string.
+    let mut x = (zero() | constant(0.1)) >> waveguide_bow(220.0, 0.5, 0.2);
+    let output: Vec<f64> = (0..44100).map(|_| x.get_mono()).collect();
+    assert!(rms(&output[33075..]) > 0.01);
+    assert!((spectral_peak(&output[22050..], 210.0, 230.0) - 220.0).abs() < 2.0);
+
+    // A single mode has a decaying sinusoidal impulse response.
+    let mut x = modal_modes(1000.0, &[Mode::new(1.0, 0.5, 1.0)]);
+    let output = render(&mut x, 44100);
+    let r = exp(-log(1000.0) / DEFAULT_SR);
+    let omega = TAU * 1000.0 / DEFAULT_SR;
+    // Impulse response of the two-pole section, which the resonator zeros difference.
+    let g = |i: usize| pow(r, i as f64) * sin(omega * (i + 1) as f64) / sin(omega);
+    for (i, y) in output.iter().enumerate() {
+        let expected = 0.25 * (g(i) - if i >= 2 { g(i - 2) } else { 0.0 });
+        assert!((y - expected).abs() < 1.0e-9);
+        // After the first two samples, this is an exponentially decaying cosine.
+        if i >= 2 {
+            assert!((y - 0.5 * pow(r, i as f64) * cos(omega * i as f64)).abs() < 1.0e-3);
+        }
+    }
+
+    // Modal resonators and waveguides propagate input latency.
+    let mut x = tick() >> modal_modes(1000.0, &[Mode::new(1.0, 0.5, 1.0)]);
+    assert_eq!(x.latency(), Some(1.0));
+    let mut x = tick() >> waveguide(220.0, 0.5, 0.0);
+    assert_eq!(x.latency(), Some(1.0));
+
+    // Very low frequencies are clamped.
+    let mut x = waveguide(440.0, 0.5, 0.5);
+    x.set_frequency(0.0);
+    assert!(x.filter_mono(1.0).is_finite());
+
+    // Modal bodies follow the frequency input and decay.
+    for body in [
+        ModalBody::Bar,
+        ModalBody::Bell,
+        ModalBody::Plate,
+        ModalBody::Membrane,
+    ] {
+        let modes = body.modes(1.0);
+        assert!(modes.iter().any(|mode| mode.ratio == 1.0));
+        let mut x = (impulse::<U1>() | constant(300.0)) >> modal(body, 1.0);
+        let output: Vec<f64> = (0..22050).map(|_| x.get_mono()).collect();
+        assert!((spectral_peak(&output, 290.0, 310.0) - 300.0).abs() < 0.5);
+        assert!(output.iter().all(|y| y.is_finite() && y.abs() <= 1.0));
+    }
+}
+
+#[test]
+fn test_drums() {
+    use fundsp::sound::*;
+    let voices = [
+        DrumVoice::Kick,
+        DrumVoice::Snare,
+        DrumVoice::Clap,
+        DrumVoice::LowTom,
+        DrumVoice::MidTom,
+        DrumVoice::HighTom,
+        DrumVoice::ClosedHat,
+        DrumVoice::OpenHat,
+        DrumVoice::Rim,
+        DrumVoice::Cowbell,
+    ];
+    let render = |voice: DrumVoice, tune: f64, decay: f64, velocity: f64| -> Vec<f64> {
+        let mut x = drum_hit(voice, tune, decay, 0.5, velocity);
+        let samples = (voice.duration(decay) * DEFAULT_SR) as usize;
+        (0..samples).map(|_| x.get_mono()).collect()
+    };
+    let peak = |x: &[f64]| x.iter().fold(0.0, |p: f64, y| p.max(y.abs()));
+    for voice in voices {
+        // Voices are audible, bounded and decay by the end of their duration.
+        let hit = render(voice, 0.0, 1.0, 1.0);
+        let p = peak(&hit);
+        assert!(p > 0.1 && p < 4.0);
+        assert!(peak(&hit[hit.len() - 100..]) < p * 0.01);
+        // Velocity scales the level.
+        assert!(peak(&render(voice, 0.0, 1.0, 0.25)) < p * 0.5);
+        assert!(peak(&render(voice, 0.0, 1.0, 0.0)) == 0.0);
+        // Decay scales the duration.
+        assert!(voice.duration(2.0) > voice.duration(1.0) * 1.5);
+    }
+
+    // Tune transposes the kick.
+    for tune in [0.0, 7.0] {
+        let hit = render(DrumVoice::Kick, tune, 1.0, 1.0);
+        let f = 50.0 * exp2(tune / 12.0);
+        let body = &hit[(0.05 * DEFAULT_SR) as usize..(0.5 * DEFAULT_SR) as usize];
+        assert!((spectral_peak(body, f * 0.8, f * 1.2) - f).abs() < 2.0);
+    }
+
+    // A closed hi-hat chokes an open hi-hat on the sequencer.
+    let render_sequence = |choke: bool| -> Vec<f64> {
+        let mut sequencer = Sequencer64::new(false, 1);
+        let mut kit = DrumKit::new();
+        kit.push(&mut sequencer, 0.0, DrumVoice::OpenHat, 0.0, 1.0, 0.5, 1.0);
+        kit.push(&mut sequencer, 0.1, DrumVoice::Kick, 0.0, 1.0, 0.5, 0.0);
+        if choke {
+            kit.push(
+                &mut sequencer,
+                0.1,
+                DrumVoice::ClosedHat,
+                0.0,
+                1.0,
+                0.5,
+                0.0,
+            );
+        }
+        (0..(0.4 * DEFAULT_SR) as usize)
+            .map(|_| sequencer.get_mono())
+            .collect()
+    };
+    let open = render_sequence(false);
+    let choked = render_sequence(true);
+    let tail = (0.15 * DEFAULT_SR) as usize;
+    assert!(peak(&open[tail..]) > 0.05);
+    assert!(peak(&choked[tail..]) == 0.0);
+    let onset = (0.1 * DEFAULT_SR) as usize;
+    assert!(open[..onset] == choked[..onset]);
+}
+
+#[test]
+fn test_ambience() {
+    use fundsp::sound::*;
+    // Render one second and return RMS and peak levels.
+    let level = |node: &mut dyn AudioUnit64, input: &[f64]| -> (f64, f64) {
+        let samples = DEFAULT_SR as usize;
+        let mut output = [0.0];
+        let (mut sum, mut peak) = (0.0, 0.0f64);
+        for _ in 0..samples {
+            node.tick(input, &mut output);
+            assert!(output[0].is_finite());
+            sum += output[0] * output[0];
+            peak = peak.max(output[0].abs());
+        }
+        ((sum / samples as f64).sqrt(), peak)
+    };
+    for seed in [0, 1] {
+        // Generators are silent at zero intensity and grow louder with intensity.
+        assert!(level(&mut wind(seed), &[0.0]).1 == 0.0);
+        assert!(level(&mut wind(seed), &[1.0]).0 > level(&mut wind(seed), &[0.3]).0);
+        assert!(level(&mut water(seed), &[0.0]).1 == 0.0);
+        assert!(level(&mut water(seed), &[1.0]).0 > level(&mut water(seed), &[0.3]).0);
+        // Fire roars without crackles.
+        let (rms, peak) = level(&mut fire(seed), &[0.0]);
+        assert!(rms > 0.01 && peak < 0.5);
+        assert!(level(&mut fire(seed), &[1.0]).1 > peak * 2.0);
+        for g in [0.0, 0.3, 1.0] {
+            for (rms, peak) in [
+                level(&mut wind(seed), &[g]),
+                level(&mut fire(seed), &[g]),
+                level(&mut water(seed), &[g]),
+            ] {
+                assert!(rms < 0.5 && peak < 4.0);
+            }
+        }
+    }
+    assert!(level(&mut rain(), &[0.0, 0.5]).1 == 0.0);
+    assert!(level(&mut rain(), &[1.0, 0.5]).0 > level(&mut rain(), &[0.3, 0.5]).0);
+    // Rain stays within a sensible level range and larger drops are louder.
+    for density in [0.3, 1.0] {
+        for size in [0.0, 0.5, 1.0] {
+            let (rms, peak) = level(&mut rain(), &[density, size]);
+            assert!(rms > 0.02 && rms < 0.3);
+            assert!(peak > 0.2 && peak < 2.0);
+        }
+        assert!(
+            level(&mut rain(), &[density, 1.0]).0 > level(&mut rain(), &[density, 0.0]).0 * 1.5
+        );
+    }
+
+    // Engine firing frequency follows RPM.
+    for rpm in [1500.0, 3000.0] {
+        let mut x = engine();
+        let output: Vec<f64> = (0..DEFAULT_SR as usize)
+            .map(|_| x.filter_mono(rpm))
+            .collect();
+        let f = rpm / 30.0;
+        assert!((spectral_peak(&output, f * 0.8, f * 1.2) - f).abs() < 1.0);
+    }
+}